@@ -7,6 +7,7 @@ use mio::*;
 use test::Bencher;
 use std::sync::Arc;
 use std::thread;
+use std::time::Duration;
 
 #[bench]
 fn bench_poll(bench: &mut Bencher) {
@@ -50,3 +51,33 @@ fn bench_poll(bench: &mut Bencher) {
         }
     })
 }
+
+#[bench]
+fn bench_registration_churn(bench: &mut Bencher) {
+    registration_churn(bench, 0);
+}
+
+#[bench]
+fn bench_registration_churn_pooled(bench: &mut Bencher) {
+    registration_churn(bench, 1024);
+}
+
+fn registration_churn(bench: &mut Bencher, pool: usize) {
+    const NUM: usize = 1_000;
+
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(1024);
+
+    poll.set_registration_pool(pool);
+
+    bench.iter(|| {
+        for i in 0..NUM {
+            let (r, s) = Registration::with_registry(poll.registry());
+            poll.register(&r, Token(i), Ready::readable(), PollOpt::edge()).unwrap();
+            s.set_readiness(Ready::readable()).unwrap();
+        }
+
+        // Releases the nodes of the dropped registrations
+        poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+    })
+}
@@ -29,7 +29,7 @@ fn main() {
 
     // Start listening for incoming connections
     poll.register(&server, SERVER, Ready::readable(),
-                  PollOpt::edge()).unwrap();
+                  PollOpt::level()).unwrap();
 
     println!("Creating client");
 
@@ -40,7 +40,7 @@ fn main() {
 
     // Register the socket
     poll.register(&sock, CLIENT, Ready::readable(),
-                  PollOpt::edge()).unwrap();
+                  PollOpt::level()).unwrap();
 
     println!("Creating Events storage");
 
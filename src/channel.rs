@@ -2,7 +2,7 @@
 
 #![allow(unused_imports, deprecated, missing_debug_implementations)]
 
-use crate::{io, Ready, Poll, PollOpt, Registration, SetReadiness, Token};
+use crate::{io, Ready, PollOpt, Registration, Registry, SetReadiness, Token};
 use crate::event::Evented;
 use lazycell::{LazyCell, AtomicLazyCell};
 use std::any::Any;
@@ -173,16 +173,16 @@ impl<T> Receiver<T> {
 }
 
 impl<T> Evented for Receiver<T> {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.ctl.register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.ctl.register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.ctl.reregister(poll, token, interest, opts)
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.ctl.reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.ctl.deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.ctl.deregister(registry)
     }
 }
 
@@ -250,12 +250,13 @@ impl ReceiverCtl {
 }
 
 impl Evented for ReceiverCtl {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
         if self.registration.borrow().is_some() {
             return Err(io::Error::new(io::ErrorKind::Other, "receiver already registered"));
         }
 
-        let (registration, set_readiness) = Registration::new(poll, token, interest, opts);
+        let (registration, set_readiness) = Registration::new2();
+        registry.register(&registration, token, interest, opts)?;
 
 
         if self.inner.pending.load(Ordering::Relaxed) > 0 {
@@ -269,16 +270,16 @@ impl Evented for ReceiverCtl {
         Ok(())
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
         match self.registration.borrow() {
-            Some(registration) => registration.update(poll, token, interest, opts),
+            Some(registration) => registry.reregister(registration, token, interest, opts),
             None => Err(io::Error::new(io::ErrorKind::Other, "receiver not registered")),
         }
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
         match self.registration.borrow() {
-            Some(registration) => registration.deregister(poll),
+            Some(registration) => registry.deregister(registration),
             None => Err(io::Error::new(io::ErrorKind::Other, "receiver not registered")),
         }
     }
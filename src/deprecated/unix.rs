@@ -1,4 +1,4 @@
-use crate::{io, sys, Ready, Poll, PollOpt, Token};
+use crate::{io, sys, Ready, PollOpt, Registry, Token};
 use crate::event::Evented;
 use crate::deprecated::TryAccept;
 use crate::io::MapNonBlock;
@@ -49,16 +49,16 @@ impl UnixSocket {
 }
 
 impl Evented for UnixSocket {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.sys.register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.sys.register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.sys.reregister(poll, token, interest, opts)
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.sys.reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.sys.deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
     }
 }
 
@@ -129,16 +129,16 @@ impl Write for UnixStream {
 }
 
 impl Evented for UnixStream {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.sys.register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.sys.register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.sys.reregister(poll, token, interest, opts)
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.sys.reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.sys.deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
     }
 }
 
@@ -177,16 +177,16 @@ impl UnixListener {
 }
 
 impl Evented for UnixListener {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.sys.register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.sys.register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.sys.reregister(poll, token, interest, opts)
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.sys.reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.sys.deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
     }
 }
 
@@ -250,16 +250,16 @@ impl<'a> Read for &'a PipeReader {
 }
 
 impl Evented for PipeReader {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.io.register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.io.register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.io.reregister(poll, token, interest, opts)
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.io.reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.io.deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.io.deregister(registry)
     }
 }
 
@@ -305,16 +305,16 @@ impl<'a> Write for &'a PipeWriter {
 }
 
 impl Evented for PipeWriter {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.io.register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.io.register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.io.reregister(poll, token, interest, opts)
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.io.reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.io.deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.io.deregister(registry)
     }
 }
 
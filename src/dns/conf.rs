@@ -0,0 +1,116 @@
+//! Parsing of `resolv.conf(5)` and `hosts(5)`.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+const DNS_PORT: u16 = 53;
+
+// resolv.conf caps these, see resolv.conf(5).
+const MAX_NDOTS: usize = 15;
+const MAX_TIMEOUT_SECS: u64 = 30;
+const MAX_ATTEMPTS: usize = 5;
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub nameservers: Vec<SocketAddr>,
+    pub search: Vec<String>,
+    pub ndots: usize,
+    pub timeout: Duration,
+    pub attempts: usize,
+    pub hosts: HashMap<String, Vec<IpAddr>>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        // Same defaults as the system resolver
+        Config {
+            nameservers: vec![],
+            search: vec![],
+            ndots: 1,
+            timeout: Duration::from_secs(5),
+            attempts: 2,
+            hosts: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn add_host(&mut self, name: &str, addr: IpAddr) {
+        let addrs = self.hosts.entry(name.trim_end_matches('.').to_ascii_lowercase())
+            .or_default();
+
+        if !addrs.contains(&addr) {
+            addrs.push(addr);
+        }
+    }
+
+    pub fn parse_resolv_conf(&mut self, contents: &str) {
+        for line in contents.lines() {
+            let mut words = strip_comment(line).split_whitespace();
+
+            match words.next() {
+                Some("nameserver") => {
+                    if let Some(Ok(ip)) = words.next().map(|w| w.parse::<IpAddr>()) {
+                        self.nameservers.push(SocketAddr::new(ip, DNS_PORT));
+                    }
+                }
+                // `domain` and `search` override each other; the last one wins.
+                Some("domain") => {
+                    self.search = words.next().map(normalize).into_iter().collect();
+                }
+                Some("search") => {
+                    self.search = words.map(normalize).collect();
+                }
+                Some("options") => {
+                    for opt in words {
+                        self.parse_option(opt);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_option(&mut self, opt: &str) {
+        let mut parts = opt.splitn(2, ':');
+        let name = parts.next().unwrap_or("");
+        let val = match parts.next().map(|v| v.parse::<u64>()) {
+            Some(Ok(val)) => val,
+            _ => return,
+        };
+
+        match name {
+            "ndots" => self.ndots = (val as usize).min(MAX_NDOTS),
+            "timeout" => self.timeout = Duration::from_secs(val.clamp(1, MAX_TIMEOUT_SECS)),
+            "attempts" => self.attempts = (val as usize).clamp(1, MAX_ATTEMPTS),
+            _ => {}
+        }
+    }
+
+    pub fn parse_hosts(&mut self, contents: &str) {
+        for line in contents.lines() {
+            let mut words = strip_comment(line).split_whitespace();
+
+            let addr = match words.next().map(|w| w.parse::<IpAddr>()) {
+                Some(Ok(addr)) => addr,
+                _ => continue,
+            };
+
+            for name in words {
+                self.add_host(name, addr);
+            }
+        }
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find(['#', ';']) {
+        Some(idx) => &line[..idx],
+        None => line,
+    }
+}
+
+fn normalize(domain: &str) -> String {
+    domain.trim_end_matches('.').to_ascii_lowercase()
+}
@@ -0,0 +1,193 @@
+//! Just enough of the RFC 1035 message format to ask for and read back A and
+//! AAAA records.
+
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+pub const TYPE_A: u16 = 1;
+pub const TYPE_AAAA: u16 = 28;
+
+const CLASS_IN: u16 = 1;
+const HEADER_LEN: usize = 12;
+
+// Header flags
+const FLAG_QR: u16 = 0x8000;
+const FLAG_TC: u16 = 0x0200;
+const FLAG_RD: u16 = 0x0100;
+const RCODE_MASK: u16 = 0x000f;
+
+pub const RCODE_NOERROR: u8 = 0;
+pub const RCODE_NXDOMAIN: u8 = 3;
+
+// Upper bound on compression pointers followed while reading a single name.
+// Guards against pointer loops in malformed responses.
+const MAX_POINTERS: usize = 32;
+
+/// A decoded response, restricted to the parts the resolver looks at.
+#[derive(Debug)]
+pub struct Response {
+    pub id: u16,
+    pub truncated: bool,
+    pub rcode: u8,
+    pub name: String,
+    pub qtype: u16,
+    pub addrs: Vec<IpAddr>,
+}
+
+/// Encode a recursive query for `name` with the given record type.
+pub fn query(id: u16, name: &str, qtype: u16) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(HEADER_LEN + name.len() + 6);
+
+    put_u16(&mut buf, id);
+    put_u16(&mut buf, FLAG_RD);
+    put_u16(&mut buf, 1); // qdcount
+    put_u16(&mut buf, 0); // ancount
+    put_u16(&mut buf, 0); // nscount
+    put_u16(&mut buf, 0); // arcount
+
+    let name = name.trim_end_matches('.');
+
+    if name.is_empty() || name.len() > 253 {
+        return Err(invalid_name());
+    }
+
+    for label in name.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(invalid_name());
+        }
+
+        buf.push(label.len() as u8);
+        buf.extend_from_slice(label.as_bytes());
+    }
+
+    buf.push(0);
+    put_u16(&mut buf, qtype);
+    put_u16(&mut buf, CLASS_IN);
+
+    Ok(buf)
+}
+
+/// Decode a response to a query built by `query`.
+pub fn parse(buf: &[u8]) -> io::Result<Response> {
+    if buf.len() < HEADER_LEN {
+        return Err(malformed());
+    }
+
+    let id = get_u16(buf, 0)?;
+    let flags = get_u16(buf, 2)?;
+    let qdcount = get_u16(buf, 4)?;
+    let ancount = get_u16(buf, 6)?;
+
+    if flags & FLAG_QR == 0 || qdcount != 1 {
+        return Err(malformed());
+    }
+
+    let (name, mut pos) = read_name(buf, HEADER_LEN)?;
+    let qtype = get_u16(buf, pos)?;
+    pos += 4;
+
+    let mut addrs = vec![];
+
+    for _ in 0..ancount {
+        let (_, next) = read_name(buf, pos)?;
+        let rtype = get_u16(buf, next)?;
+        let class = get_u16(buf, next + 2)?;
+        let rdlen = get_u16(buf, next + 8)? as usize;
+        let rdata = next + 10;
+
+        if buf.len() < rdata + rdlen {
+            return Err(malformed());
+        }
+
+        let data = &buf[rdata..rdata + rdlen];
+
+        // CNAME records are skipped; recursive servers include the records
+        // of the canonical name in the same answer section.
+        match (rtype, class, rdlen) {
+            (TYPE_A, CLASS_IN, 4) if rtype == qtype => {
+                addrs.push(IpAddr::V4(Ipv4Addr::new(data[0], data[1], data[2], data[3])));
+            }
+            (TYPE_AAAA, CLASS_IN, 16) if rtype == qtype => {
+                let mut octets = [0; 16];
+                octets.copy_from_slice(data);
+                addrs.push(IpAddr::V6(Ipv6Addr::from(octets)));
+            }
+            _ => {}
+        }
+
+        pos = rdata + rdlen;
+    }
+
+    Ok(Response {
+        id: id,
+        truncated: flags & FLAG_TC != 0,
+        rcode: (flags & RCODE_MASK) as u8,
+        name: name,
+        qtype: qtype,
+        addrs: addrs,
+    })
+}
+
+/// Read a possibly compressed name starting at `pos`, returning it in dotted
+/// form along with the offset just past it.
+fn read_name(buf: &[u8], mut pos: usize) -> io::Result<(String, usize)> {
+    let mut name = String::new();
+    let mut end = None;
+    let mut pointers = 0;
+
+    loop {
+        let len = *buf.get(pos).ok_or_else(malformed)? as usize;
+
+        match len & 0xc0 {
+            0x00 if len == 0 => {
+                return Ok((name, end.unwrap_or(pos + 1)));
+            }
+            0x00 => {
+                let label = buf.get(pos + 1..pos + 1 + len).ok_or_else(malformed)?;
+
+                if !name.is_empty() {
+                    name.push('.');
+                }
+
+                name.push_str(&String::from_utf8_lossy(label));
+                pos += 1 + len;
+            }
+            0xc0 => {
+                pointers += 1;
+
+                if pointers > MAX_POINTERS {
+                    return Err(malformed());
+                }
+
+                let target = get_u16(buf, pos)? as usize & 0x3fff;
+
+                if end.is_none() {
+                    end = Some(pos + 2);
+                }
+
+                pos = target;
+            }
+            _ => return Err(malformed()),
+        }
+    }
+}
+
+fn put_u16(buf: &mut Vec<u8>, val: u16) {
+    buf.push((val >> 8) as u8);
+    buf.push(val as u8);
+}
+
+fn get_u16(buf: &[u8], pos: usize) -> io::Result<u16> {
+    match buf.get(pos..pos + 2) {
+        Some(b) => Ok((b[0] as u16) << 8 | b[1] as u16),
+        None => Err(malformed()),
+    }
+}
+
+fn invalid_name() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "invalid domain name")
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed DNS response")
+}
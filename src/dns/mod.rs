@@ -0,0 +1,857 @@
+//! Non-blocking name resolution
+//!
+//! `TcpStream::connect` needs an already resolved `SocketAddr`, and resolving
+//! a host name through `std::net::ToSocketAddrs` blocks the calling thread.
+//! [`Resolver`] instead sends A and AAAA queries over non-blocking sockets
+//! and is driven by [`Poll`] like any other handle.
+//!
+//! A resolver is registered with a [`Poll`] instance under a single token.
+//! Whenever an event is received for that token, or the duration returned by
+//! [`Resolver::timeout`] has elapsed, [`Resolver::process`] must be called.
+//! Finished lookups are then returned by [`Resolver::next_result`].
+//!
+//! Queries are sent over UDP and retried over TCP when the name server
+//! signals that the answer was truncated. Unanswered queries are retried,
+//! rotating through the configured name servers, inside `process`.
+//!
+//! [`Resolver`]: struct.Resolver.html
+//! [`Poll`]: ../struct.Poll.html
+//! [`Resolver::timeout`]: struct.Resolver.html#method.timeout
+//! [`Resolver::process`]: struct.Resolver.html#method.process
+//! [`Resolver::next_result`]: struct.Resolver.html#method.next_result
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # use std::error::Error;
+//! # fn try_main() -> Result<(), Box<dyn Error>> {
+//! use mio::{Events, Poll, PollOpt, Ready, Token};
+//! use mio::dns::Resolver;
+//!
+//! const DNS: Token = Token(0);
+//!
+//! let poll = Poll::new()?;
+//! let mut events = Events::with_capacity(128);
+//!
+//! let mut resolver = Resolver::new()?;
+//! poll.register(&resolver, DNS, Ready::readable(), PollOpt::edge())?;
+//!
+//! let query = resolver.resolve("example.com")?;
+//!
+//! loop {
+//!     poll.poll(&mut events, resolver.timeout())?;
+//!     resolver.process()?;
+//!
+//!     if let Some((q, result)) = resolver.next_result() {
+//!         assert_eq!(q, query);
+//!         println!("example.com: {:?}", result?);
+//!         break;
+//!     }
+//! }
+//! #     Ok(())
+//! # }
+//! #
+//! # fn main() {
+//! #     try_main().unwrap();
+//! # }
+//! ```
+
+mod conf;
+mod message;
+
+use crate::{io, PollOpt, Ready, Registration, Registry, SetReadiness, Token};
+use crate::event::Evented;
+use crate::net::{TcpStream, UdpSocket};
+
+use self::conf::Config;
+use self::message::{Response, TYPE_A, TYPE_AAAA, RCODE_NOERROR, RCODE_NXDOMAIN};
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::collections::hash_map::RandomState;
+use std::fs;
+use std::hash::{BuildHasher, Hasher};
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+use std::{fmt, mem};
+
+const RESOLV_CONF: &str = "/etc/resolv.conf";
+const HOSTS: &str = "/etc/hosts";
+
+// Large enough for any response sent without EDNS, which caps UDP payloads
+// at 512 bytes, with room to spare for servers that ignore the limit.
+const RECV_BUF_LEN: usize = 4096;
+
+/// Configures and builds a [`Resolver`].
+///
+/// [`Resolver`]: struct.Resolver.html
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn try_main() -> Result<(), Box<dyn Error>> {
+/// use mio::dns::Builder;
+/// use std::time::Duration;
+///
+/// let resolver = Builder::new()
+///     .nameserver("127.0.0.1:53".parse()?)
+///     .search("example.com")
+///     .timeout(Duration::from_secs(1))
+///     .build()?;
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Builder {
+    config: Config,
+}
+
+impl Builder {
+    /// Construct a new `Builder` without any name servers, search domains or
+    /// host entries.
+    pub fn new() -> Builder {
+        Builder::default()
+    }
+
+    /// Construct a new `Builder` from the system's `/etc/resolv.conf` and
+    /// `/etc/hosts`.
+    ///
+    /// Missing files are treated as empty. If no name server is configured,
+    /// the resolver falls back to one on the local host, as the system
+    /// resolver does.
+    pub fn from_system() -> io::Result<Builder> {
+        let mut builder = Builder::new();
+
+        if let Some(contents) = read_optional(RESOLV_CONF)? {
+            builder.parse_resolv_conf(&contents);
+        }
+
+        if let Some(contents) = read_optional(HOSTS)? {
+            builder.parse_hosts(&contents);
+        }
+
+        if builder.config.nameservers.is_empty() {
+            builder.nameserver(SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 53));
+        }
+
+        Ok(builder)
+    }
+
+    /// Applies the `nameserver`, `domain`, `search` and `options` lines of a
+    /// file in the `resolv.conf(5)` format.
+    ///
+    /// Of the options, `ndots`, `timeout` and `attempts` are understood.
+    pub fn parse_resolv_conf(&mut self, contents: &str) -> &mut Self {
+        self.config.parse_resolv_conf(contents);
+        self
+    }
+
+    /// Adds the entries of a file in the `hosts(5)` format.
+    pub fn parse_hosts(&mut self, contents: &str) -> &mut Self {
+        self.config.parse_hosts(contents);
+        self
+    }
+
+    /// Adds a name server. Name servers are tried in the order they are
+    /// added.
+    pub fn nameserver(&mut self, addr: SocketAddr) -> &mut Self {
+        self.config.nameservers.push(addr);
+        self
+    }
+
+    /// Adds a domain to the search list used for names with fewer than
+    /// `ndots` dots.
+    pub fn search(&mut self, domain: &str) -> &mut Self {
+        self.config.search.push(domain.trim_end_matches('.').to_ascii_lowercase());
+        self
+    }
+
+    /// Adds a static host entry. Names with host entries are answered
+    /// without querying a name server.
+    pub fn host(&mut self, name: &str, addr: IpAddr) -> &mut Self {
+        self.config.add_host(name, addr);
+        self
+    }
+
+    /// Sets the number of dots a name must contain before it is first tried
+    /// as an absolute name.
+    ///
+    /// The default value for this is 1.
+    pub fn ndots(&mut self, ndots: usize) -> &mut Self {
+        self.config.ndots = ndots;
+        self
+    }
+
+    /// Sets how long to wait for an answer before retrying with the next
+    /// name server.
+    ///
+    /// The default value for this is 5 seconds.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.config.timeout = timeout;
+        self
+    }
+
+    /// Sets how many times each name server is tried before a lookup fails.
+    ///
+    /// The default value for this is 2.
+    pub fn attempts(&mut self, attempts: usize) -> &mut Self {
+        self.config.attempts = attempts;
+        self
+    }
+
+    /// Builds a `Resolver` with this configuration.
+    pub fn build(&self) -> io::Result<Resolver> {
+        let config = self.config.clone();
+
+        let udp_v4 = if config.nameservers.iter().any(|a| a.is_ipv4()) {
+            let any = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 0);
+            Some(UdpSocket::bind(&any)?)
+        } else {
+            None
+        };
+
+        let udp_v6 = if config.nameservers.iter().any(|a| a.is_ipv6()) {
+            let any = SocketAddr::new(IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0)), 0);
+            Some(UdpSocket::bind(&any)?)
+        } else {
+            None
+        };
+
+        let (registration, set_readiness) = Registration::new2();
+
+        Ok(Resolver {
+            config: config,
+            udp_v4: udp_v4,
+            udp_v6: udp_v6,
+            lookups: vec![],
+            completed: VecDeque::new(),
+            registration: registration,
+            set_readiness: set_readiness,
+            registered: RefCell::new(None),
+            next_query: 0,
+            random: RandomState::new(),
+            next_id: 0,
+        })
+    }
+}
+
+/// A non-blocking DNS resolver.
+///
+/// See the [module documentation](index.html) for how a resolver is driven.
+pub struct Resolver {
+    config: Config,
+    udp_v4: Option<UdpSocket>,
+    udp_v6: Option<UdpSocket>,
+    // Lookups waiting on a name server
+    lookups: Vec<Lookup>,
+    // Finished lookups not yet handed out by `next_result`
+    completed: VecDeque<(Query, io::Result<Vec<IpAddr>>)>,
+    // Readable while `completed` is not empty
+    registration: Registration,
+    set_readiness: SetReadiness,
+    // Set on registration with Poll, used for TCP fallback connections
+    registered: RefCell<Option<Registered>>,
+    next_query: usize,
+    // Keyed hasher used to pick unpredictable message IDs
+    random: RandomState,
+    next_id: u64,
+}
+
+/// The `Poll` instance a resolver is registered with, and its token.
+#[derive(Clone)]
+struct Registered {
+    registry: Registry,
+    token: Token,
+}
+
+/// Identifies a lookup started with [`Resolver::resolve`].
+///
+/// [`Resolver::resolve`]: struct.Resolver.html#method.resolve
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Query(usize);
+
+struct Lookup {
+    query: Query,
+    // Candidate names still to be tried, the first one is in flight
+    names: VecDeque<String>,
+    questions: Vec<Question>,
+    addrs: Vec<IpAddr>,
+    // Index into the configured name servers
+    server: usize,
+    tries: usize,
+    deadline: Instant,
+}
+
+struct Question {
+    qtype: u16,
+    id: u16,
+    done: bool,
+    tcp: Option<Tcp>,
+}
+
+/// A query retried over TCP after a truncated answer.
+struct Tcp {
+    stream: TcpStream,
+    // Length prefixed query while writing, the response while reading
+    buf: Vec<u8>,
+    written: usize,
+    writing: bool,
+}
+
+impl Resolver {
+    /// Construct a new `Resolver` configured from the system's
+    /// `/etc/resolv.conf` and `/etc/hosts`.
+    pub fn new() -> io::Result<Resolver> {
+        Builder::from_system()?.build()
+    }
+
+    /// Starts resolving `name` to its IPv4 and IPv6 addresses.
+    ///
+    /// IP address literals and names with a host entry are answered
+    /// immediately. The result is returned by `next_result` once available.
+    pub fn resolve(&mut self, name: &str) -> io::Result<Query> {
+        let query = Query(self.next_query);
+
+        if let Ok(addr) = name.parse::<IpAddr>() {
+            self.next_query += 1;
+            self.complete(query, Ok(vec![addr]));
+            return self.update_readiness().map(|_| query);
+        }
+
+        // Fails with `InvalidInput` if `name` is not a valid domain name
+        message::query(0, name, TYPE_A)?;
+
+        let key = name.trim_end_matches('.').to_ascii_lowercase();
+
+        if let Some(addrs) = self.config.hosts.get(&key).cloned() {
+            self.next_query += 1;
+            self.complete(query, Ok(addrs));
+            return self.update_readiness().map(|_| query);
+        }
+
+        if self.config.nameservers.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::Other, "no name servers configured"));
+        }
+
+        self.next_query += 1;
+
+        let mut lookup = Lookup {
+            query: query,
+            names: self.candidates(name),
+            questions: vec![],
+            addrs: vec![],
+            server: 0,
+            tries: 0,
+            deadline: Instant::now(),
+        };
+
+        self.start_name(&mut lookup);
+        self.lookups.push(lookup);
+
+        Ok(query)
+    }
+
+    /// Returns how long `Poll::poll` may block before `process` must be
+    /// called to retry unanswered queries.
+    ///
+    /// Returns `None` when no lookup is waiting on a name server.
+    pub fn timeout(&self) -> Option<Duration> {
+        let now = Instant::now();
+
+        self.lookups.iter()
+            .map(|l| l.deadline)
+            .min()
+            .map(|deadline| if deadline > now { deadline - now } else { Duration::from_millis(0) })
+    }
+
+    /// Reads answers from the name servers and retries queries that timed
+    /// out.
+    ///
+    /// This must be called whenever the resolver's token is returned by
+    /// `Poll::poll` and whenever the duration returned by `timeout` has
+    /// elapsed. TCP fallback connections are registered with the `Poll`
+    /// instance the resolver itself is registered with.
+    pub fn process(&mut self) -> io::Result<()> {
+        let registered = self.registered.borrow().clone();
+        let poll = registered.as_ref();
+
+        for (buf, from) in self.recv_datagrams()? {
+            self.on_datagram(poll, &buf, from);
+        }
+
+        let now = Instant::now();
+        let pending = mem::take(&mut self.lookups);
+
+        for mut lookup in pending {
+            let mut res = self.drive_tcp(poll, &mut lookup);
+
+            if res.is_none() && lookup.deadline <= now {
+                let err = io::Error::new(io::ErrorKind::TimedOut, "name server did not respond");
+                res = self.retry(poll, &mut lookup, err);
+            }
+
+            match res {
+                Some(res) => self.complete(lookup.query, res),
+                None => self.lookups.push(lookup),
+            }
+        }
+
+        self.update_readiness()
+    }
+
+    /// Returns the result of a finished lookup, if any.
+    ///
+    /// A lookup for a name without addresses fails with
+    /// `ErrorKind::NotFound`, and one that got no answer from any name server
+    /// fails with `ErrorKind::TimedOut`.
+    pub fn next_result(&mut self) -> Option<(Query, io::Result<Vec<IpAddr>>)> {
+        let next = self.completed.pop_front();
+
+        if let Err(e) = self.update_readiness() {
+            debug!("failed to update resolver readiness; err={:?}", e);
+        }
+
+        next
+    }
+
+    /// Names to query for `name`, following the search list and `ndots`
+    /// rules of the system resolver.
+    fn candidates(&self, name: &str) -> VecDeque<String> {
+        let mut names = VecDeque::new();
+
+        if name.ends_with('.') {
+            names.push_back(name.trim_end_matches('.').to_string());
+            return names;
+        }
+
+        let absolute_first = name.matches('.').count() >= self.config.ndots;
+
+        if absolute_first {
+            names.push_back(name.to_string());
+        }
+
+        for domain in &self.config.search {
+            let candidate = format!("{}.{}", name, domain);
+
+            if message::query(0, &candidate, TYPE_A).is_ok() {
+                names.push_back(candidate);
+            }
+        }
+
+        if !absolute_first {
+            names.push_back(name.to_string());
+        }
+
+        names
+    }
+
+    /// Asks for the addresses of the lookup's current candidate name.
+    fn start_name(&mut self, lookup: &mut Lookup) {
+        lookup.tries = 0;
+        lookup.questions = [TYPE_A, TYPE_AAAA].iter()
+            .map(|&qtype| Question { qtype: qtype, id: 0, done: false, tcp: None })
+            .collect();
+
+        self.send(lookup);
+    }
+
+    /// Sends every unanswered question of the lookup over UDP.
+    fn send(&mut self, lookup: &mut Lookup) {
+        let server = self.config.nameservers[lookup.server];
+
+        lookup.deadline = Instant::now() + self.config.timeout;
+
+        for i in 0..lookup.questions.len() {
+            if lookup.questions[i].done {
+                continue;
+            }
+
+            let id = self.next_id();
+            lookup.questions[i].id = id;
+
+            let buf = match message::query(id, &lookup.names[0], lookup.questions[i].qtype) {
+                Ok(buf) => buf,
+                Err(_) => continue,
+            };
+
+            let sock = if server.is_ipv4() { &self.udp_v4 } else { &self.udp_v6 };
+
+            // A lost query is retried once the deadline passes
+            if let Some(Err(e)) = sock.as_ref().map(|s| s.send_to(&buf, &server)) {
+                debug!("failed to send DNS query; server={}; err={:?}", server, e);
+            }
+        }
+    }
+
+    /// Retries the lookup's current name with the next name server, or
+    /// fails the lookup with `err` once every attempt is used up.
+    fn retry(&mut self, poll: Option<&Registered>, lookup: &mut Lookup, err: io::Error)
+        -> Option<io::Result<Vec<IpAddr>>>
+    {
+        self.close_tcp(poll, lookup);
+
+        let servers = self.config.nameservers.len();
+
+        lookup.tries += 1;
+
+        if lookup.tries >= self.config.attempts * servers {
+            return Some(Err(err));
+        }
+
+        lookup.server = (lookup.server + 1) % servers;
+        self.send(lookup);
+
+        None
+    }
+
+    /// Called once every question for the current name is answered.
+    fn next_name(&mut self, lookup: &mut Lookup) -> Option<io::Result<Vec<IpAddr>>> {
+        if !lookup.addrs.is_empty() {
+            return Some(Ok(mem::take(&mut lookup.addrs)));
+        }
+
+        lookup.names.pop_front();
+
+        if lookup.names.is_empty() {
+            return Some(Err(io::Error::new(io::ErrorKind::NotFound, "no addresses found for name")));
+        }
+
+        self.start_name(lookup);
+
+        None
+    }
+
+    fn on_response(&mut self, poll: Option<&Registered>, lookup: &mut Lookup, idx: usize, response: Response)
+        -> Option<io::Result<Vec<IpAddr>>>
+    {
+        match response.rcode {
+            RCODE_NOERROR | RCODE_NXDOMAIN => {
+                lookup.questions[idx].done = true;
+                lookup.addrs.extend(response.addrs);
+
+                if lookup.questions.iter().all(|q| q.done) {
+                    self.next_name(lookup)
+                } else {
+                    None
+                }
+            }
+            rcode => {
+                let err = io::Error::new(io::ErrorKind::Other,
+                                         format!("name server failure; rcode={}", rcode));
+                self.retry(poll, lookup, err)
+            }
+        }
+    }
+
+    fn recv_datagrams(&self) -> io::Result<Vec<(Vec<u8>, SocketAddr)>> {
+        let mut datagrams = vec![];
+        let mut buf = [0; RECV_BUF_LEN];
+
+        for sock in self.udp_v4.iter().chain(self.udp_v6.iter()) {
+            loop {
+                match sock.recv_from(&mut buf) {
+                    Ok((n, from)) => datagrams.push((buf[..n].to_vec(), from)),
+                    Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        Ok(datagrams)
+    }
+
+    fn on_datagram(&mut self, poll: Option<&Registered>, buf: &[u8], from: SocketAddr) {
+        let response = match message::parse(buf) {
+            Ok(response) => response,
+            Err(e) => {
+                debug!("ignoring DNS response; from={}; err={:?}", from, e);
+                return;
+            }
+        };
+
+        let nameservers = &self.config.nameservers;
+
+        // Only accept answers from the server that was asked, to the
+        // question that was asked.
+        let found = self.lookups.iter().enumerate().filter_map(|(i, lookup)| {
+            if nameservers[lookup.server] != from
+                || !lookup.names[0].eq_ignore_ascii_case(&response.name)
+            {
+                return None;
+            }
+
+            lookup.questions.iter()
+                .position(|q| {
+                    !q.done && q.tcp.is_none() && q.id == response.id && q.qtype == response.qtype
+                })
+                .map(|idx| (i, idx))
+        }).next();
+
+        let (i, idx) = match found {
+            Some(found) => found,
+            None => return,
+        };
+
+        let mut lookup = self.lookups.swap_remove(i);
+
+        let res = if response.truncated {
+            match self.start_tcp(poll, &mut lookup, idx) {
+                Ok(()) => None,
+                Err(e) => self.retry(poll, &mut lookup, e),
+            }
+        } else {
+            self.on_response(poll, &mut lookup, idx, response)
+        };
+
+        match res {
+            Some(res) => self.complete(lookup.query, res),
+            None => self.lookups.push(lookup),
+        }
+    }
+
+    fn start_tcp(&mut self, poll: Option<&Registered>, lookup: &mut Lookup, idx: usize) -> io::Result<()> {
+        let server = self.config.nameservers[lookup.server];
+        let question = &mut lookup.questions[idx];
+        let msg = message::query(question.id, &lookup.names[0], question.qtype)?;
+
+        let mut buf = Vec::with_capacity(msg.len() + 2);
+        buf.push((msg.len() >> 8) as u8);
+        buf.push(msg.len() as u8);
+        buf.extend_from_slice(&msg);
+
+        let stream = TcpStream::connect(&server)?;
+
+        if let Some(poll) = poll {
+            poll.registry.register(&stream, poll.token, Ready::readable() | Ready::writable(), PollOpt::level())?;
+        }
+
+        question.tcp = Some(Tcp {
+            stream: stream,
+            buf: buf,
+            written: 0,
+            writing: true,
+        });
+
+        Ok(())
+    }
+
+    fn drive_tcp(&mut self, poll: Option<&Registered>, lookup: &mut Lookup) -> Option<io::Result<Vec<IpAddr>>> {
+        for idx in 0..lookup.questions.len() {
+            let res = match lookup.questions[idx].tcp {
+                Some(ref mut tcp) => tcp.drive(poll),
+                None => continue,
+            };
+
+            match res {
+                Ok(None) => {}
+                Ok(Some(buf)) => {
+                    if let Some(tcp) = lookup.questions[idx].tcp.take() {
+                        tcp.close(poll);
+                    }
+
+                    let response = match message::parse(&buf) {
+                        Ok(ref response) if response.id != lookup.questions[idx].id => {
+                            Err(io::Error::new(io::ErrorKind::InvalidData,
+                                               "DNS response does not match query"))
+                        }
+                        res => res,
+                    };
+
+                    let res = match response {
+                        Ok(response) => self.on_response(poll, lookup, idx, response),
+                        Err(e) => self.retry(poll, lookup, e),
+                    };
+
+                    if res.is_some() {
+                        return res;
+                    }
+                }
+                Err(e) => return self.retry(poll, lookup, e),
+            }
+        }
+
+        None
+    }
+
+    fn close_tcp(&self, poll: Option<&Registered>, lookup: &mut Lookup) {
+        for question in &mut lookup.questions {
+            if let Some(tcp) = question.tcp.take() {
+                tcp.close(poll);
+            }
+        }
+    }
+
+    fn complete(&mut self, query: Query, res: io::Result<Vec<IpAddr>>) {
+        self.completed.push_back((query, res));
+    }
+
+    fn update_readiness(&self) -> io::Result<()> {
+        if self.completed.is_empty() {
+            self.set_readiness.set_readiness(Ready::empty())
+        } else {
+            self.set_readiness.set_readiness(Ready::readable())
+        }
+    }
+
+    fn next_id(&mut self) -> u16 {
+        let mut hasher = self.random.build_hasher();
+        hasher.write_u64(self.next_id);
+        self.next_id += 1;
+        hasher.finish() as u16
+    }
+
+    fn tcp_streams(&self) -> impl Iterator<Item = &Tcp> {
+        self.lookups.iter()
+            .flat_map(|l| l.questions.iter())
+            .filter_map(|q| q.tcp.as_ref())
+    }
+}
+
+impl Tcp {
+    /// Writes the query and reads the response as far as possible without
+    /// blocking. Returns the response once it is complete.
+    fn drive(&mut self, poll: Option<&Registered>) -> io::Result<Option<Vec<u8>>> {
+        if self.writing {
+            while self.written < self.buf.len() {
+                match (&self.stream).write(&self.buf[self.written..]) {
+                    Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                    Ok(n) => self.written += n,
+                    Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                    Err(e) => return Err(e),
+                }
+            }
+
+            self.writing = false;
+            self.buf.clear();
+
+            if let Some(poll) = poll {
+                poll.registry.reregister(&self.stream, poll.token, Ready::readable(), PollOpt::level())?;
+            }
+        }
+
+        let mut chunk = [0; RECV_BUF_LEN];
+
+        loop {
+            if self.buf.len() >= 2 {
+                let len = (self.buf[0] as usize) << 8 | self.buf[1] as usize;
+
+                if self.buf.len() >= len + 2 {
+                    return Ok(Some(self.buf[2..len + 2].to_vec()));
+                }
+            }
+
+            match (&self.stream).read(&mut chunk) {
+                Ok(0) => {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                              "name server closed the connection"));
+                }
+                Ok(n) => self.buf.extend_from_slice(&chunk[..n]),
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    fn interest(&self) -> Ready {
+        if self.writing {
+            Ready::readable() | Ready::writable()
+        } else {
+            Ready::readable()
+        }
+    }
+
+    fn close(self, poll: Option<&Registered>) {
+        if let Some(poll) = poll {
+            let _ = poll.registry.deregister(&self.stream);
+        }
+    }
+}
+
+impl Evented for Resolver {
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        registry.register(&self.registration, token, interest, opts)?;
+
+        // The sockets are registered level-triggered regardless of `opts`:
+        // the poll(2) selector rejects edge-triggered registrations in strict
+        // mode, and `process` drains them anyway.
+        for sock in self.udp_v4.iter().chain(self.udp_v6.iter()) {
+            registry.register(sock, token, Ready::readable(), PollOpt::level())?;
+        }
+
+        for tcp in self.tcp_streams() {
+            registry.register(&tcp.stream, token, tcp.interest(), PollOpt::level())?;
+        }
+
+        *self.registered.borrow_mut() = Some(Registered { registry: registry.clone(), token: token });
+        Ok(())
+    }
+
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        registry.reregister(&self.registration, token, interest, opts)?;
+
+        for sock in self.udp_v4.iter().chain(self.udp_v6.iter()) {
+            registry.reregister(sock, token, Ready::readable(), PollOpt::level())?;
+        }
+
+        for tcp in self.tcp_streams() {
+            registry.reregister(&tcp.stream, token, tcp.interest(), PollOpt::level())?;
+        }
+
+        *self.registered.borrow_mut() = Some(Registered { registry: registry.clone(), token: token });
+        Ok(())
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        registry.deregister(&self.registration)?;
+
+        for sock in self.udp_v4.iter().chain(self.udp_v6.iter()) {
+            registry.deregister(sock)?;
+        }
+
+        for tcp in self.tcp_streams() {
+            registry.deregister(&tcp.stream)?;
+        }
+
+        *self.registered.borrow_mut() = None;
+        Ok(())
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        from.transfer(&self.registration, to, token, interest, opts)?;
+
+        for sock in self.udp_v4.iter().chain(self.udp_v6.iter()) {
+            from.transfer(sock, to, token, Ready::readable(), PollOpt::level())?;
+        }
+
+        for tcp in self.tcp_streams() {
+            from.transfer(&tcp.stream, to, token, tcp.interest(), PollOpt::level())?;
+        }
+
+        *self.registered.borrow_mut() = Some(Registered { registry: to.clone(), token: token });
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Resolver {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Resolver")
+            .field("nameservers", &self.config.nameservers)
+            .field("pending", &self.lookups.len())
+            .field("completed", &self.completed.len())
+            .finish()
+    }
+}
+
+fn read_optional(path: &str) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
@@ -1,11 +1,14 @@
-use crate::{Poll, Token};
+use crate::{RegisterError, Registry, Token};
 use std::{fmt, io, ops};
 
 /// A value that may be registered with `Poll`
 ///
 /// Values that implement `Evented` can be registered with `Poll`. Users of Mio
 /// should not use the `Evented` trait functions directly. Instead, the
-/// equivalent functions on `Poll` should be used.
+/// equivalent functions on `Poll`, or on a [`Registry`], should be used.
+///
+/// The functions are passed the [`Registry`] of the `Poll` instance the value
+/// is being registered with.
 ///
 /// See [`Poll`] for more details.
 ///
@@ -22,6 +25,7 @@ use std::{fmt, io, ops};
 /// responsibility for driving the readiness state changes.
 ///
 /// [`Poll`]: ../struct.Poll.html
+/// [`Registry`]: ../struct.Registry.html
 /// [`Registration`]: ../struct.Registration.html
 /// [`SetReadiness`]: ../struct.SetReadiness.html
 ///
@@ -30,7 +34,7 @@ use std::{fmt, io, ops};
 /// Implementing `Evented` on a struct containing a socket:
 ///
 /// ```
-/// use mio::{Ready, Poll, PollOpt, Token};
+/// use mio::{Ready, PollOpt, Registry, Token};
 /// use mio::event::Evented;
 /// use mio::net::TcpStream;
 ///
@@ -41,23 +45,23 @@ use std::{fmt, io, ops};
 /// }
 ///
 /// impl Evented for MyEvented {
-///     fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt)
+///     fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt)
 ///         -> io::Result<()>
 ///     {
 ///         // Delegate the `register` call to `socket`
-///         self.socket.register(poll, token, interest, opts)
+///         self.socket.register(registry, token, interest, opts)
 ///     }
 ///
-///     fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt)
+///     fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt)
 ///         -> io::Result<()>
 ///     {
 ///         // Delegate the `reregister` call to `socket`
-///         self.socket.reregister(poll, token, interest, opts)
+///         self.socket.reregister(registry, token, interest, opts)
 ///     }
 ///
-///     fn deregister(&self, poll: &Poll) -> io::Result<()> {
+///     fn deregister(&self, registry: &Registry) -> io::Result<()> {
 ///         // Delegate the `deregister` call to `socket`
-///         self.socket.deregister(poll)
+///         self.socket.deregister(registry)
 ///     }
 /// }
 /// ```
@@ -65,7 +69,7 @@ use std::{fmt, io, ops};
 /// Implement `Evented` using [`Registration`] and [`SetReadiness`].
 ///
 /// ```
-/// use mio::{Ready, Registration, Poll, PollOpt, Token};
+/// use mio::{Ready, Registration, PollOpt, Registry, Token};
 /// use mio::event::Evented;
 ///
 /// use std::io;
@@ -103,25 +107,25 @@ use std::{fmt, io, ops};
 /// }
 ///
 /// impl Evented for Deadline {
-///     fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt)
+///     fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt)
 ///         -> io::Result<()>
 ///     {
-///         self.registration.register(poll, token, interest, opts)
+///         self.registration.register(registry, token, interest, opts)
 ///     }
 ///
-///     fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt)
+///     fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt)
 ///         -> io::Result<()>
 ///     {
-///         self.registration.reregister(poll, token, interest, opts)
+///         self.registration.reregister(registry, token, interest, opts)
 ///     }
 ///
-///     fn deregister(&self, poll: &Poll) -> io::Result<()> {
-///         self.registration.deregister(poll)
+///     fn deregister(&self, registry: &Registry) -> io::Result<()> {
+///         self.registration.deregister(registry)
 ///     }
 /// }
 /// ```
 pub trait Evented {
-    /// Register `self` with the given `Poll` instance.
+    /// Register `self` with the `Poll` instance `registry` belongs to.
     ///
     /// This function should not be called directly. Use [`Poll::register`]
     /// instead. Implementors should handle registration by either delegating
@@ -129,9 +133,9 @@ pub trait Evented {
     ///
     /// [`Poll::register`]: ../struct.Poll.html#method.register
     /// [`Registration`]: ../struct.Registration.html
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>;
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>;
 
-    /// Re-register `self` with the given `Poll` instance.
+    /// Re-register `self` with the `Poll` instance `registry` belongs to.
     ///
     /// This function should not be called directly. Use [`Poll::reregister`]
     /// instead. Implementors should handle re-registration by either delegating
@@ -140,9 +144,9 @@ pub trait Evented {
     ///
     /// [`Poll::reregister`]: ../struct.Poll.html#method.reregister
     /// [`SetReadiness::set_readiness`]: ../struct.SetReadiness.html#method.set_readiness
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>;
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>;
 
-    /// Deregister `self` from the given `Poll` instance
+    /// Deregister `self` from the `Poll` instance `registry` belongs to.
     ///
     /// This function should not be called directly. Use [`Poll::deregister`]
     /// instead. Implementors should handle deregistration by either delegating
@@ -151,48 +155,132 @@ pub trait Evented {
     ///
     /// [`Poll::deregister`]: ../struct.Poll.html#method.deregister
     /// [`Registration`]: ../struct.Registration.html
-    fn deregister(&self, poll: &Poll) -> io::Result<()>;
+    fn deregister(&self, registry: &Registry) -> io::Result<()>;
+
+    /// Move `self` from the `Poll` instance `from` belongs to, to the one `to`
+    /// belongs to.
+    ///
+    /// This function should not be called directly. Use [`Poll::transfer`]
+    /// instead. The default implementation deregisters `self` from `from` and
+    /// registers it with `to`. Implementors that only accept being registered
+    /// with a single `Poll` instance have to override it.
+    ///
+    /// [`Poll::transfer`]: ../struct.Poll.html#method.transfer
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.deregister(from)?;
+        self.register(to, token, interest, opts)
+    }
+
+    /// Register `self` with the `Poll` instance `registry` belongs to, with
+    /// readable and writable readiness reported with separate tokens.
+    ///
+    /// This function should not be called directly. Use
+    /// [`Poll::register_split`] instead. The default implementation fails
+    /// with [`RegisterError::Unsupported`], handles backed by a single system
+    /// handle can override it.
+    ///
+    /// [`Poll::register_split`]: ../struct.Poll.html#method.register_split
+    /// [`RegisterError::Unsupported`]: ../enum.RegisterError.html#variant.Unsupported
+    fn register_split(&self, _registry: &Registry, _read_token: Token, _write_token: Token, opts: PollOpt) -> io::Result<()> {
+        Err(RegisterError::Unsupported {
+            interest: Ready::readable() | Ready::writable(),
+            opts: opts,
+        }.into())
+    }
+
+    /// Re-register `self` with separate tokens for its readable and writable
+    /// readiness, watching only the readiness in `interest`.
+    ///
+    /// This function should not be called directly. Use
+    /// [`Poll::reregister_split`] instead. The default implementation fails
+    /// with [`RegisterError::Unsupported`], like [`register_split`].
+    ///
+    /// [`Poll::reregister_split`]: ../struct.Poll.html#method.reregister_split
+    /// [`RegisterError::Unsupported`]: ../enum.RegisterError.html#variant.Unsupported
+    /// [`register_split`]: #method.register_split
+    fn reregister_split(&self, _registry: &Registry, _read_token: Token, _write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        Err(RegisterError::Unsupported {
+            interest: interest,
+            opts: opts,
+        }.into())
+    }
 }
 
 impl Evented for Box<dyn Evented> {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.as_ref().register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().register(registry, token, interest, opts)
+    }
+
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().reregister(registry, token, interest, opts)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.as_ref().deregister(registry)
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().transfer(from, to, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.as_ref().reregister(poll, token, interest, opts)
+    fn register_split(&self, registry: &Registry, read_token: Token, write_token: Token, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().register_split(registry, read_token, write_token, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.as_ref().deregister(poll)
+    fn reregister_split(&self, registry: &Registry, read_token: Token, write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().reregister_split(registry, read_token, write_token, interest, opts)
     }
 }
 
 impl<T: Evented> Evented for Box<T> {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.as_ref().register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.as_ref().reregister(poll, token, interest, opts)
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.as_ref().deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.as_ref().deregister(registry)
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().transfer(from, to, token, interest, opts)
+    }
+
+    fn register_split(&self, registry: &Registry, read_token: Token, write_token: Token, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().register_split(registry, read_token, write_token, opts)
+    }
+
+    fn reregister_split(&self, registry: &Registry, read_token: Token, write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().reregister_split(registry, read_token, write_token, interest, opts)
     }
 }
 
 impl<T: Evented> Evented for ::std::sync::Arc<T> {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.as_ref().register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().register(registry, token, interest, opts)
+    }
+
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().reregister(registry, token, interest, opts)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.as_ref().deregister(registry)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.as_ref().reregister(poll, token, interest, opts)
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().transfer(from, to, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.as_ref().deregister(poll)
+    fn register_split(&self, registry: &Registry, read_token: Token, write_token: Token, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().register_split(registry, read_token, write_token, opts)
+    }
+
+    fn reregister_split(&self, registry: &Registry, read_token: Token, write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.as_ref().reregister_split(registry, read_token, write_token, interest, opts)
     }
 }
 
@@ -300,9 +388,27 @@ impl PollOpt {
         PollOpt(0b0100)
     }
 
-    #[deprecated(since = "0.6.5", note = "removed")]
-    #[cfg(feature = "with-deprecated")]
-    #[doc(hidden)]
+    /// Return a `PollOpt` representing urgent notifications.
+    ///
+    /// Events for urgent registrations are placed at the front of [`Events`]
+    /// and are drained ahead of any other pending readiness, so that a
+    /// control socket is never starved by a busy data plane. `urgent` is
+    /// combined with one of the triggering modes.
+    ///
+    /// See [`Poll`] for more documentation on polling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mio::PollOpt;
+    ///
+    /// let opt = PollOpt::level() | PollOpt::urgent();
+    ///
+    /// assert!(opt.is_urgent());
+    /// ```
+    ///
+    /// [`Poll`]: struct.Poll.html
+    /// [`Events`]: struct.Events.html
     #[inline]
     pub fn urgent() -> PollOpt {
         PollOpt(0b1000)
@@ -376,10 +482,21 @@ impl PollOpt {
         self.contains(PollOpt::oneshot())
     }
 
-    #[deprecated(since = "0.6.5", note = "removed")]
-    #[cfg(feature = "with-deprecated")]
-    #[doc(hidden)]
-    #[allow(deprecated)]
+    /// Returns true if the options includes urgent.
+    ///
+    /// See [`Poll`] for more documentation on polling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mio::PollOpt;
+    ///
+    /// let opt = PollOpt::edge() | PollOpt::urgent();
+    ///
+    /// assert!(opt.is_urgent());
+    /// ```
+    ///
+    /// [`Poll`]: struct.Poll.html
     #[inline]
     pub fn is_urgent(&self) -> bool {
         self.contains(PollOpt::urgent())
@@ -530,7 +647,8 @@ impl fmt::Debug for PollOpt {
         let flags = [
             (PollOpt::edge(), "Edge-Triggered"),
             (PollOpt::level(), "Level-Triggered"),
-            (PollOpt::oneshot(), "OneShot")];
+            (PollOpt::oneshot(), "OneShot"),
+            (PollOpt::urgent(), "Urgent")];
 
         for &(flag, msg) in &flags {
             if self.contains(flag) {
@@ -555,6 +673,7 @@ fn test_debug_pollopt() {
     assert_eq!("Edge-Triggered", format!("{:?}", PollOpt::edge()));
     assert_eq!("Level-Triggered", format!("{:?}", PollOpt::level()));
     assert_eq!("OneShot", format!("{:?}", PollOpt::oneshot()));
+    assert_eq!("Level-Triggered | Urgent", format!("{:?}", PollOpt::level() | PollOpt::urgent()));
 }
 
 /// A set of readiness event kinds
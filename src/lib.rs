@@ -60,14 +60,14 @@
 //!
 //! // Start listening for incoming connections
 //! poll.register(&server, SERVER, Ready::readable(),
-//!               PollOpt::edge()).unwrap();
+//!               PollOpt::level()).unwrap();
 //!
 //! // Setup the client socket
 //! let sock = TcpStream::connect(&addr).unwrap();
 //!
 //! // Register the socket
 //! poll.register(&sock, CLIENT, Ready::readable(),
-//!               PollOpt::edge()).unwrap();
+//!               PollOpt::level()).unwrap();
 //!
 //! // Create storage for events
 //! let mut events = Events::with_capacity(1024);
@@ -107,8 +107,10 @@ mod io;
 mod poll;
 mod sys;
 mod token;
+mod token_map;
 
 pub mod net;
+pub mod dns;
 
 #[deprecated(since = "0.6.5", note = "use mio-extras instead")]
 #[cfg(feature = "with-deprecated")]
@@ -144,15 +146,21 @@ pub mod tcp {
 pub mod udp;
 
 pub use crate::poll::{
+    Capabilities,
     Poll,
+    PollClosed,
+    RegisterError,
+    Registry,
     Registration,
     SetReadiness,
+    Waker,
 };
 pub use crate::event_imp::{
     PollOpt,
     Ready,
 };
 pub use crate::token::Token;
+pub use crate::token_map::TokenMap;
 
 pub mod event {
     //! Readiness event types and utilities.
@@ -184,8 +192,11 @@ pub mod unix {
     //! Unix only extensions
     pub use crate::sys::{
         EventedFd,
+        Source,
     };
     pub use crate::sys::UnixReady;
+    #[cfg(target_os = "linux")]
+    pub use crate::sys::{listen_fds, ListenFd};
 }
 
 #[cfg(target_os = "fuchsia")]
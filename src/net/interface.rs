@@ -0,0 +1,123 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use crate::{io, sys};
+
+/// A network interface of the local host, as returned by [`interfaces`].
+///
+/// [`interfaces`]: fn.interfaces.html
+///
+/// # Examples
+///
+/// Joining a multicast group on every interface that supports it:
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn try_main() -> Result<(), Box<dyn Error>> {
+/// use mio::net::{self, UdpSocket};
+/// use std::net::{Ipv4Addr, Ipv6Addr};
+///
+/// let v4 = UdpSocket::bind(&"0.0.0.0:0".parse()?)?;
+/// let v6 = UdpSocket::bind(&"[::]:0".parse()?)?;
+///
+/// for interface in net::interfaces()? {
+///     if !interface.is_up() || !interface.is_multicast() {
+///         continue;
+///     }
+///
+///     if let Some(addr) = interface.ipv4() {
+///         v4.join_multicast_v4(&Ipv4Addr::new(224, 0, 0, 123), &addr)?;
+///     }
+///
+///     if interface.addrs().iter().any(|a| a.addr().is_ipv6()) {
+///         let group = Ipv6Addr::new(0xff02, 0, 0, 0, 0, 0, 0, 0x123);
+///         v6.join_multicast_v6(&group, interface.index())?;
+///     }
+/// }
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Interface {
+    pub(crate) name: String,
+    pub(crate) index: u32,
+    pub(crate) up: bool,
+    pub(crate) loopback: bool,
+    pub(crate) multicast: bool,
+    pub(crate) addrs: Vec<InterfaceAddr>,
+}
+
+/// An address assigned to an [`Interface`].
+///
+/// [`Interface`]: struct.Interface.html
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct InterfaceAddr {
+    pub(crate) addr: IpAddr,
+    pub(crate) netmask: Option<IpAddr>,
+}
+
+impl Interface {
+    /// The name of the interface, e.g. `eth0`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The index of the interface, as expected by `join_multicast_v6` and
+    /// `leave_multicast_v6`.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns `true` if the interface is administratively up.
+    pub fn is_up(&self) -> bool {
+        self.up
+    }
+
+    /// Returns `true` if this is a loopback interface.
+    pub fn is_loopback(&self) -> bool {
+        self.loopback
+    }
+
+    /// Returns `true` if the interface supports multicast.
+    pub fn is_multicast(&self) -> bool {
+        self.multicast
+    }
+
+    /// The IPv4 and IPv6 addresses assigned to the interface.
+    pub fn addrs(&self) -> &[InterfaceAddr] {
+        &self.addrs
+    }
+
+    /// The first IPv4 address of the interface, as expected by
+    /// `join_multicast_v4` and `leave_multicast_v4`.
+    pub fn ipv4(&self) -> Option<Ipv4Addr> {
+        self.addrs.iter().filter_map(|a| match a.addr {
+            IpAddr::V4(addr) => Some(addr),
+            IpAddr::V6(..) => None,
+        }).next()
+    }
+}
+
+impl InterfaceAddr {
+    /// The address.
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// The netmask of the address, if the system reports one.
+    pub fn netmask(&self) -> Option<IpAddr> {
+        self.netmask
+    }
+}
+
+/// Returns the network interfaces of the local host along with their
+/// addresses.
+///
+/// Interfaces are returned in the order the system reports them. Interfaces
+/// without any IP address are included with an empty address list.
+pub fn interfaces() -> io::Result<Vec<Interface>> {
+    sys::interfaces()
+}
@@ -7,8 +7,14 @@
 //!
 //! [portability guidelines]: ../struct.Poll.html#portability
 
+#[cfg(not(target_os = "horizon"))]
+mod interface;
+mod socks5;
 mod tcp;
 mod udp;
 
+#[cfg(not(target_os = "horizon"))]
+pub use self::interface::{interfaces, Interface, InterfaceAddr};
+pub use self::socks5::{Socks5Connector, Socks5Target};
 pub use self::tcp::{TcpListener, TcpStream};
 pub use self::udp::UdpSocket;
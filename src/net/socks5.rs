@@ -0,0 +1,474 @@
+//! SOCKS5 client handshake (RFC 1928 and RFC 1929)
+
+use std::fmt;
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+
+use crate::{io, Ready, PollOpt, Registry, Token};
+use crate::event::Evented;
+use crate::net::TcpStream;
+
+const VERSION: u8 = 5;
+const AUTH_VERSION: u8 = 1;
+
+const METHOD_NONE: u8 = 0x00;
+const METHOD_PASSWORD: u8 = 0x02;
+const METHOD_UNACCEPTABLE: u8 = 0xff;
+
+const CMD_CONNECT: u8 = 1;
+
+const ATYP_V4: u8 = 1;
+const ATYP_DOMAIN: u8 = 3;
+const ATYP_V6: u8 = 4;
+
+/// The destination a [`Socks5Connector`] asks the proxy to connect to.
+///
+/// [`Socks5Connector`]: struct.Socks5Connector.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Socks5Target {
+    /// An address the proxy connects to as is.
+    Addr(SocketAddr),
+    /// A domain name and port, resolved by the proxy.
+    Domain(String, u16),
+}
+
+impl From<SocketAddr> for Socks5Target {
+    fn from(addr: SocketAddr) -> Socks5Target {
+        Socks5Target::Addr(addr)
+    }
+}
+
+/// Establishes a TCP connection through a SOCKS5 proxy without blocking.
+///
+/// The connector owns the `TcpStream` to the proxy and is registered with
+/// `Poll` in its place. Every time the stream is reported ready,
+/// [`handshake`] must be called to drive the negotiation forward. Once it
+/// returns `true`, the proxy has connected to the target and
+/// [`into_stream`] hands back the tunneled stream.
+///
+/// Both the "no authentication" and the username/password methods are
+/// offered, the latter only if [`set_credentials`] was called.
+///
+/// [`handshake`]: #method.handshake
+/// [`into_stream`]: #method.into_stream
+/// [`set_credentials`]: #method.set_credentials
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn try_main() -> Result<(), Box<dyn Error>> {
+/// use mio::{Events, Ready, Poll, PollOpt, Token};
+/// use mio::net::{Socks5Connector, Socks5Target};
+///
+/// let target = Socks5Target::Domain("example.com".to_string(), 80);
+/// let mut connector = Socks5Connector::connect(&"127.0.0.1:1080".parse()?, target)?;
+///
+/// let poll = Poll::new()?;
+/// let mut events = Events::with_capacity(128);
+///
+/// poll.register(&connector, Token(0), Ready::readable() | Ready::writable(),
+///               PollOpt::level())?;
+///
+/// while !connector.handshake()? {
+///     poll.poll(&mut events, None)?;
+/// }
+///
+/// // The stream stays registered with the same token
+/// let stream = connector.into_stream()?;
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+pub struct Socks5Connector {
+    stream: TcpStream,
+    target: Socks5Target,
+    credentials: Option<(String, String)>,
+    state: State,
+    // Message being written, or the reply being read
+    buf: Vec<u8>,
+    written: usize,
+    bound_addr: Option<SocketAddr>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum State {
+    Start,
+    SendGreeting,
+    ReadMethod,
+    SendAuth,
+    ReadAuth,
+    SendConnect,
+    ReadReply,
+    Done,
+}
+
+impl Socks5Connector {
+    /// Opens a connection to the proxy at `proxy` and prepares a handshake
+    /// for `target`.
+    pub fn connect(proxy: &SocketAddr, target: Socks5Target) -> io::Result<Socks5Connector> {
+        Socks5Connector::from_stream(TcpStream::connect(proxy)?, target)
+    }
+
+    /// Prepares a handshake for `target` over an existing, possibly still
+    /// connecting, stream to the proxy.
+    pub fn from_stream(stream: TcpStream, target: Socks5Target) -> io::Result<Socks5Connector> {
+        if let Socks5Target::Domain(ref name, _) = target {
+            if name.is_empty() || name.len() > 255 {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          "domain name must be 1 to 255 bytes long"));
+            }
+        }
+
+        Ok(Socks5Connector {
+            stream: stream,
+            target: target,
+            credentials: None,
+            state: State::Start,
+            buf: vec![],
+            written: 0,
+            bound_addr: None,
+        })
+    }
+
+    /// Offers username/password authentication to the proxy.
+    ///
+    /// This must be called before the first call to `handshake`.
+    pub fn set_credentials(&mut self, username: &str, password: &str) -> io::Result<()> {
+        if self.state != State::Start {
+            return Err(io::Error::new(io::ErrorKind::Other, "handshake already started"));
+        }
+
+        if username.is_empty() || username.len() > 255 || password.is_empty() || password.len() > 255 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "username and password must be 1 to 255 bytes long"));
+        }
+
+        self.credentials = Some((username.to_string(), password.to_string()));
+        Ok(())
+    }
+
+    /// Drives the handshake as far as possible without blocking.
+    ///
+    /// Returns `true` once the proxy reports that the connection to the
+    /// target is established, and `false` if the stream must become ready
+    /// again before the handshake can make progress.
+    ///
+    /// A refusal by the proxy is returned as an error whose kind matches the
+    /// reply code where possible, e.g. `ConnectionRefused`. Rejected
+    /// credentials fail with `PermissionDenied`.
+    pub fn handshake(&mut self) -> io::Result<bool> {
+        loop {
+            match self.state {
+                State::Start => {
+                    let mut msg = vec![VERSION];
+
+                    if self.credentials.is_some() {
+                        msg.extend_from_slice(&[2, METHOD_NONE, METHOD_PASSWORD]);
+                    } else {
+                        msg.extend_from_slice(&[1, METHOD_NONE]);
+                    }
+
+                    self.send(State::SendGreeting, msg);
+                }
+                State::SendGreeting => {
+                    if !self.write()? {
+                        return Ok(false);
+                    }
+
+                    self.recv(State::ReadMethod);
+                }
+                State::ReadMethod => {
+                    if !self.read(2)? {
+                        return Ok(false);
+                    }
+
+                    check_version(self.buf[0], VERSION)?;
+
+                    match (self.buf[1], self.credentials.clone()) {
+                        (METHOD_NONE, _) => self.send_connect(),
+                        (METHOD_PASSWORD, Some((username, password))) => {
+                            let mut msg = vec![AUTH_VERSION, username.len() as u8];
+                            msg.extend_from_slice(username.as_bytes());
+                            msg.push(password.len() as u8);
+                            msg.extend_from_slice(password.as_bytes());
+
+                            self.send(State::SendAuth, msg);
+                        }
+                        (METHOD_UNACCEPTABLE, _) => {
+                            return Err(io::Error::new(io::ErrorKind::PermissionDenied,
+                                                      "no acceptable authentication method"));
+                        }
+                        _ => {
+                            return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                                      "proxy selected an unknown method"));
+                        }
+                    }
+                }
+                State::SendAuth => {
+                    if !self.write()? {
+                        return Ok(false);
+                    }
+
+                    self.recv(State::ReadAuth);
+                }
+                State::ReadAuth => {
+                    if !self.read(2)? {
+                        return Ok(false);
+                    }
+
+                    check_version(self.buf[0], AUTH_VERSION)?;
+
+                    if self.buf[1] != 0 {
+                        return Err(io::Error::new(io::ErrorKind::PermissionDenied,
+                                                  "proxy rejected the credentials"));
+                    }
+
+                    self.send_connect();
+                }
+                State::SendConnect => {
+                    if !self.write()? {
+                        return Ok(false);
+                    }
+
+                    self.recv(State::ReadReply);
+                }
+                State::ReadReply => {
+                    // Version, reply, reserved, address type and the first
+                    // byte of the address, which holds the length of a
+                    // domain name.
+                    if !self.read(5)? {
+                        return Ok(false);
+                    }
+
+                    check_version(self.buf[0], VERSION)?;
+
+                    if self.buf[1] != 0 {
+                        return Err(reply_error(self.buf[1]));
+                    }
+
+                    let len = match self.buf[3] {
+                        ATYP_V4 => 4 + 4 + 2,
+                        ATYP_V6 => 4 + 16 + 2,
+                        ATYP_DOMAIN => 4 + 1 + self.buf[4] as usize + 2,
+                        _ => {
+                            return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                                      "unknown address type in proxy reply"));
+                        }
+                    };
+
+                    if !self.read(len)? {
+                        return Ok(false);
+                    }
+
+                    self.bound_addr = bound_addr(&self.buf);
+                    self.buf = vec![];
+                    self.state = State::Done;
+                }
+                State::Done => return Ok(true),
+            }
+        }
+    }
+
+    /// Returns `true` once the handshake has completed.
+    pub fn is_connected(&self) -> bool {
+        self.state == State::Done
+    }
+
+    /// The readiness the handshake is currently waiting for.
+    ///
+    /// With level-triggered registrations, reregistering with this interest
+    /// after each call to `handshake` avoids spurious wakeups.
+    pub fn interest(&self) -> Ready {
+        match self.state {
+            State::ReadMethod | State::ReadAuth | State::ReadReply => Ready::readable(),
+            State::Done => Ready::readable() | Ready::writable(),
+            _ => Ready::writable(),
+        }
+    }
+
+    /// The address the proxy bound for the outgoing connection, as reported
+    /// in its reply.
+    ///
+    /// Returns `None` before the handshake completes, or if the proxy
+    /// reported a domain name.
+    pub fn bound_addr(&self) -> Option<SocketAddr> {
+        self.bound_addr
+    }
+
+    /// Returns the tunneled stream.
+    ///
+    /// Fails with `NotConnected` if the handshake has not completed. The
+    /// stream keeps any registration made through the connector.
+    pub fn into_stream(self) -> io::Result<TcpStream> {
+        if self.state != State::Done {
+            return Err(io::Error::new(io::ErrorKind::NotConnected,
+                                      "SOCKS5 handshake not completed"));
+        }
+
+        Ok(self.stream)
+    }
+
+    /// Returns a reference to the stream to the proxy.
+    pub fn get_ref(&self) -> &TcpStream {
+        &self.stream
+    }
+
+    fn send_connect(&mut self) {
+        let mut msg = vec![VERSION, CMD_CONNECT, 0];
+
+        let port = match self.target {
+            Socks5Target::Addr(SocketAddr::V4(ref addr)) => {
+                msg.push(ATYP_V4);
+                msg.extend_from_slice(&addr.ip().octets());
+                addr.port()
+            }
+            Socks5Target::Addr(SocketAddr::V6(ref addr)) => {
+                msg.push(ATYP_V6);
+                msg.extend_from_slice(&addr.ip().octets());
+                addr.port()
+            }
+            Socks5Target::Domain(ref name, port) => {
+                msg.push(ATYP_DOMAIN);
+                msg.push(name.len() as u8);
+                msg.extend_from_slice(name.as_bytes());
+                port
+            }
+        };
+
+        msg.push((port >> 8) as u8);
+        msg.push(port as u8);
+
+        self.send(State::SendConnect, msg);
+    }
+
+    fn send(&mut self, state: State, msg: Vec<u8>) {
+        self.state = state;
+        self.buf = msg;
+        self.written = 0;
+    }
+
+    fn recv(&mut self, state: State) {
+        self.state = state;
+        self.buf.clear();
+    }
+
+    /// Writes the rest of `buf`, returning `true` once all of it is written.
+    fn write(&mut self) -> io::Result<bool> {
+        while self.written < self.buf.len() {
+            match (&self.stream).write(&self.buf[self.written..]) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => self.written += n,
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(false),
+                // Some platforms report this while the connection to the
+                // proxy is still in progress.
+                Err(ref e) if e.kind() == io::ErrorKind::NotConnected => {
+                    return match self.stream.take_error()? {
+                        Some(e) => Err(e),
+                        None => Ok(false),
+                    };
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Reads until `buf` holds `len` bytes. Never reads past the end of the
+    /// reply, so no tunneled data is consumed.
+    fn read(&mut self, len: usize) -> io::Result<bool> {
+        let mut chunk = [0; 256 + 7];
+
+        while self.buf.len() < len {
+            let want = len - self.buf.len();
+
+            match (&self.stream).read(&mut chunk[..want]) {
+                Ok(0) => {
+                    return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                              "proxy closed the connection"));
+                }
+                Ok(n) => self.buf.extend_from_slice(&chunk[..n]),
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(false),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+fn check_version(version: u8, expected: u8) -> io::Result<()> {
+    if version != expected {
+        return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                  "unexpected SOCKS version in proxy reply"));
+    }
+
+    Ok(())
+}
+
+fn reply_error(code: u8) -> io::Error {
+    let (kind, msg) = match code {
+        1 => (io::ErrorKind::Other, "general SOCKS server failure"),
+        2 => (io::ErrorKind::PermissionDenied, "connection not allowed by ruleset"),
+        3 => (io::ErrorKind::Other, "network unreachable"),
+        4 => (io::ErrorKind::Other, "host unreachable"),
+        5 => (io::ErrorKind::ConnectionRefused, "connection refused"),
+        6 => (io::ErrorKind::TimedOut, "TTL expired"),
+        7 => (io::ErrorKind::Other, "command not supported"),
+        8 => (io::ErrorKind::InvalidInput, "address type not supported"),
+        _ => (io::ErrorKind::Other, "unknown SOCKS reply code"),
+    };
+
+    io::Error::new(kind, msg)
+}
+
+fn bound_addr(reply: &[u8]) -> Option<SocketAddr> {
+    let port = |b: &[u8]| (b[0] as u16) << 8 | b[1] as u16;
+
+    match reply[3] {
+        ATYP_V4 => {
+            let ip = Ipv4Addr::new(reply[4], reply[5], reply[6], reply[7]);
+            Some(SocketAddr::V4(SocketAddrV4::new(ip, port(&reply[8..]))))
+        }
+        ATYP_V6 => {
+            let mut octets = [0; 16];
+            octets.copy_from_slice(&reply[4..20]);
+            let ip = Ipv6Addr::from(octets);
+            Some(SocketAddr::V6(SocketAddrV6::new(ip, port(&reply[20..]), 0, 0)))
+        }
+        _ => None,
+    }
+}
+
+impl Evented for Socks5Connector {
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.stream.register(registry, token, interest, opts)
+    }
+
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.stream.reregister(registry, token, interest, opts)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.stream.deregister(registry)
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.stream.transfer(from, to, token, interest, opts)
+    }
+}
+
+impl fmt::Debug for Socks5Connector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Socks5Connector")
+            .field("stream", &self.stream)
+            .field("target", &self.target)
+            .field("state", &self.state)
+            .finish()
+    }
+}
+
@@ -15,7 +15,7 @@ use std::time::Duration;
 use net2::TcpBuilder;
 use iovec::IoVec;
 
-use crate::{io, sys, Ready, Poll, PollOpt, Token};
+use crate::{io, sys, Ready, PollOpt, Registry, Token};
 use crate::event::Evented;
 use crate::poll::SelectorId;
 
@@ -48,7 +48,7 @@ use crate::poll::SelectorId;
 ///
 /// // Register the socket with `Poll`
 /// poll.register(&stream, Token(0), Ready::writable(),
-///               PollOpt::edge())?;
+///               PollOpt::level())?;
 ///
 /// poll.poll(&mut events, Some(Duration::from_millis(100)))?;
 ///
@@ -102,6 +102,30 @@ impl TcpStream {
         TcpStream::connect_stream(sock.to_tcp_stream()?, addr)
     }
 
+    /// Create a new TCP stream bound to `local` and issue a non-blocking
+    /// connect to `remote`.
+    ///
+    /// Binding before connecting selects the source address, and with it the
+    /// outbound interface, on hosts with more than one address. `local` must
+    /// be of the same address family as `remote`.
+    ///
+    /// If the port of `local` is 0, the kernel picks the ephemeral port. On
+    /// Linux, `IP_BIND_ADDRESS_NO_PORT` is set on the socket so that the port
+    /// is only allocated by `connect`, once the full 4-tuple is known. This
+    /// allows the same ephemeral port to be shared between connections to
+    /// different destinations instead of reserving one per bound socket.
+    pub fn connect_from(local: &SocketAddr, remote: &SocketAddr) -> io::Result<TcpStream> {
+        let sock = match *remote {
+            SocketAddr::V4(..) => TcpBuilder::new_v4(),
+            SocketAddr::V6(..) => TcpBuilder::new_v6(),
+        }?;
+        if local.port() == 0 {
+            sys::set_bind_address_no_port(sock.as_raw_fd())?;
+        }
+        sock.bind(local)?;
+        TcpStream::connect_stream(sock.to_tcp_stream()?, remote)
+    }
+
     /// Creates a new `TcpStream` from the pending socket inside the given
     /// `std::net::TcpBuilder`, connecting it to the address specified.
     ///
@@ -437,19 +461,43 @@ impl<'a> Write for &'a TcpStream {
 }
 
 impl Evented for TcpStream {
-    fn register(&self, poll: &Poll, token: Token,
+    fn register(&self, registry: &Registry, token: Token,
                 interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.selector_id.associate_selector(poll)?;
-        self.sys.register(poll, token, interest, opts)
+        self.selector_id.associate_selector(registry)?;
+        self.sys.register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token,
+    fn reregister(&self, registry: &Registry, token: Token,
                   interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.sys.reregister(poll, token, interest, opts)
+        self.sys.reregister(registry, token, interest, opts)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token,
+                interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.selector_id.transfer(from, to)?;
+
+        if let Err(e) = self.sys.transfer(from, to, token, interest, opts) {
+            // Still registered with `from`
+            let _ = self.selector_id.transfer(to, from);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    fn register_split(&self, registry: &Registry, read_token: Token,
+                      write_token: Token, opts: PollOpt) -> io::Result<()> {
+        self.selector_id.associate_selector(registry)?;
+        self.sys.register_split(registry, read_token, write_token, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.sys.deregister(poll)
+    fn reregister_split(&self, registry: &Registry, read_token: Token,
+                        write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.sys.reregister_split(registry, read_token, write_token, interest, opts)
     }
 }
 
@@ -483,7 +531,7 @@ impl fmt::Debug for TcpStream {
 ///
 /// // Register the socket with `Poll`
 /// poll.register(&listener, Token(0), Ready::writable(),
-///               PollOpt::edge())?;
+///               PollOpt::level())?;
 ///
 /// poll.poll(&mut events, Some(Duration::from_millis(100)))?;
 ///
@@ -655,19 +703,32 @@ impl TcpListener {
 }
 
 impl Evented for TcpListener {
-    fn register(&self, poll: &Poll, token: Token,
+    fn register(&self, registry: &Registry, token: Token,
                 interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.selector_id.associate_selector(poll)?;
-        self.sys.register(poll, token, interest, opts)
+        self.selector_id.associate_selector(registry)?;
+        self.sys.register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token,
+    fn reregister(&self, registry: &Registry, token: Token,
                   interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.sys.reregister(poll, token, interest, opts)
+        self.sys.reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.sys.deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token,
+                interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.selector_id.transfer(from, to)?;
+
+        if let Err(e) = self.sys.transfer(from, to, token, interest, opts) {
+            // Still registered with `from`
+            let _ = self.selector_id.transfer(to, from);
+            return Err(e);
+        }
+
+        Ok(())
     }
 }
 
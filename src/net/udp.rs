@@ -7,7 +7,7 @@
 //!
 /// [portability guidelines]: ../struct.Poll.html#portability
 
-use crate::{io, sys, Ready, Poll, PollOpt, Token};
+use crate::{io, sys, Ready, PollOpt, Registry, Token};
 use crate::event::Evented;
 use crate::poll::SelectorId;
 use std::fmt;
@@ -50,8 +50,8 @@ use std::net::{self, Ipv4Addr, Ipv6Addr, SocketAddr};
 /// let poll = Poll::new()?;
 ///
 /// // We register our sockets here so that we can check if they are ready to be written/read.
-/// poll.register(&sender_socket, SENDER, Ready::writable(), PollOpt::edge())?;
-/// poll.register(&echoer_socket, ECHOER, Ready::readable(), PollOpt::edge())?;
+/// poll.register(&sender_socket, SENDER, Ready::writable(), PollOpt::level())?;
+/// poll.register(&echoer_socket, ECHOER, Ready::readable(), PollOpt::level())?;
 ///
 /// let msg_to_send = [9; 9];
 /// let mut buffer = [0; 9];
@@ -545,17 +545,38 @@ impl UdpSocket {
 }
 
 impl Evented for UdpSocket {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.selector_id.associate_selector(poll)?;
-        self.sys.register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.selector_id.associate_selector(registry)?;
+        self.sys.register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.sys.reregister(poll, token, interest, opts)
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.sys.reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.sys.deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.selector_id.transfer(from, to)?;
+
+        if let Err(e) = self.sys.transfer(from, to, token, interest, opts) {
+            // Still registered with `from`
+            let _ = self.selector_id.transfer(to, from);
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    fn register_split(&self, registry: &Registry, read_token: Token, write_token: Token, opts: PollOpt) -> io::Result<()> {
+        self.selector_id.associate_selector(registry)?;
+        self.sys.register_split(registry, read_token, write_token, opts)
+    }
+
+    fn reregister_split(&self, registry: &Registry, read_token: Token, write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.sys.reregister_split(registry, read_token, write_token, interest, opts)
     }
 }
 
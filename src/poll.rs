@@ -1,10 +1,12 @@
 use crate::{sys, Token};
+use crate::sys::Sink;
 use crate::event_imp::{self as event, Ready, Event, Evented, PollOpt};
-use std::{fmt, io, ptr, usize};
+use std::{cmp, error, fmt, io, ptr, usize};
 use std::cell::UnsafeCell;
+use std::collections::{HashMap, VecDeque};
 use std::{mem, ops, isize};
-use std::sync::{Arc, Mutex, Condvar};
-use std::sync::atomic::{AtomicUsize, AtomicPtr, AtomicBool};
+use std::sync::{Arc, Mutex, MutexGuard, Condvar};
+use std::sync::atomic::{self, AtomicUsize, AtomicPtr, AtomicBool};
 use std::sync::atomic::Ordering::{self, Acquire, Release, AcqRel, Relaxed, SeqCst};
 use std::time::{Duration, Instant};
 
@@ -55,6 +57,16 @@ use std::time::{Duration, Instant};
 // again as the `until` argument. If the next node to pop is `until`, then
 // `Dequeue::Empty` is returned.
 //
+// Nodes registered with `PollOpt::urgent()` are not pushed into the MPSC
+// queue. They go into a separate, mutex protected, urgent queue that
+// `Poll::poll` drains before it calls the system selector. This keeps
+// control-plane handles from waiting behind a long queue of other nodes. Since
+// the urgent queue does not participate in the `sleep_marker` protocol, a
+// producer pushing an urgent node checks for `sleep_marker` at the head of the
+// MPSC queue, and `Poll::poll` checks the urgent queue again after inserting
+// `sleep_marker`. A `SeqCst` fence on both sides ensures at least one of the
+// two sees the other.
+//
 // [1] http://www.1024cores.net/home/lock-free-algorithms/queues/intrusive-mpsc-node-based-queue
 
 
@@ -102,7 +114,7 @@ use std::time::{Duration, Instant};
 /// let stream = TcpStream::connect(&server.local_addr()?)?;
 ///
 /// // Register the stream with `Poll`
-/// poll.register(&stream, Token(0), Ready::readable() | Ready::writable(), PollOpt::edge())?;
+/// poll.register(&stream, Token(0), Ready::readable() | Ready::writable(), PollOpt::level())?;
 ///
 /// // Wait for the socket to become ready. This has to happens in a loop to
 /// // handle spurious wakeups.
@@ -190,6 +202,12 @@ use std::time::{Duration, Instant};
 /// In order to receive the event for the data received in step 6, the socket
 /// would need to be reregistered using [`reregister`].
 ///
+/// Handles registered with the [`urgent`] option are given priority. Their
+/// events are collected first and placed at the front of [`Events`], so they
+/// are not held back when many other handles are ready at the same time. This
+/// is meant for a small number of control sockets sharing a `Poll` with many
+/// busy data sockets.
+///
 /// [`PollOpt`]: struct.PollOpt.html
 /// [`edge`]: struct.PollOpt.html#method.edge
 /// [`level`]: struct.PollOpt.html#method.level
@@ -199,6 +217,8 @@ use std::time::{Duration, Instant};
 /// [`TcpStream`]: tcp/struct.TcpStream.html
 /// [`reregister`]: #method.reregister
 /// [`oneshot`]: struct.PollOpt.html#method.oneshot
+/// [`urgent`]: struct.PollOpt.html#method.urgent
+/// [`Events`]: struct.Events.html
 ///
 /// # Portability
 ///
@@ -274,7 +294,47 @@ use std::time::{Duration, Instant};
 ///
 /// // The connect is not guaranteed to have started until it is registered at
 /// // this point
-/// poll.register(&sock, Token(0), Ready::readable() | Ready::writable(), PollOpt::edge())?;
+/// poll.register(&sock, Token(0), Ready::readable() | Ready::writable(), PollOpt::level())?;
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// ### Nesting
+///
+/// `Poll` itself implements [`Evented`], so a `Poll` instance can be
+/// registered with another one. It is reported readable while any of its
+/// handles is ready, or while its readiness queue holds events, and stays
+/// readable until it is polled. Only readable interest is supported, and the
+/// event is always level-triggered.
+///
+/// ```
+/// # use std::error::Error;
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use mio::{Events, Poll, Ready, PollOpt, Registration, Token};
+///
+/// let poll = Poll::new()?;
+/// let inner = Poll::new()?;
+///
+/// poll.register(&inner, Token(0), Ready::readable(), PollOpt::level())?;
+///
+/// let (registration, set_readiness) = Registration::new2();
+/// inner.register(&registration, Token(1), Ready::readable(), PollOpt::edge())?;
+/// set_readiness.set_readiness(Ready::readable())?;
+///
+/// let mut events = Events::with_capacity(16);
+/// poll.poll(&mut events, None)?;
+///
+/// for event in &events {
+///     if event.token() == Token(0) {
+///         inner.poll(&mut events, None)?;
+///         assert_eq!(events.get(0).unwrap().token(), Token(1));
+///         break;
+///     }
+/// }
 /// #     Ok(())
 /// # }
 /// #
@@ -321,10 +381,10 @@ use std::time::{Duration, Instant};
 /// [`SetReadiness`]: struct.SetReadiness.html
 /// [`Poll::poll`]: struct.Poll.html#method.poll
 pub struct Poll {
-    // Platform specific IO selector
-    selector: sys::Selector,
+    // Registers handles with the selector and the readiness queue
+    registry: Registry,
 
-    // Custom readiness queue
+    // Custom readiness queue, closed when `Poll` is dropped
     readiness_queue: ReadinessQueue,
 
     // Use an atomic to first check if a full lock will be required. This is a
@@ -336,8 +396,98 @@ pub struct Poll {
 
     // Wakeup the next waiter
     condvar: Condvar,
+
+    // Drop events carrying a stale token generation, see `set_drop_stale`
+    drop_stale: bool,
+
+    // Capacity `Events` may be grown to, see `set_max_events`
+    max_events: usize,
+
+    // Number of threads allowed in the selector at once, see
+    // `set_concurrent_pollers`
+    pollers: usize,
+
+    // Partitions of the selector claimed by concurrent pollers
+    slots: Mutex<Vec<bool>>,
+
+    // Wakeup a poller waiting for a free slot
+    slot_freed: Condvar,
+
+    // Set while a concurrent poller drains the readiness queue
+    draining: AtomicBool,
+}
+
+/// Registers `Evented` handles with a [`Poll`] instance.
+///
+/// A `Registry` is obtained by calling [`Poll::registry`]. It provides the
+/// [`register`], [`reregister`], and [`deregister`] functions of `Poll`, and is
+/// the handle [`Evented`] implementations are passed. Unlike `Poll`, a
+/// `Registry` can be cloned, so handles can be registered from other threads
+/// or components while a single owner of the `Poll` instance waits for
+/// events.
+///
+/// Handles registered through any clone of a `Registry` deliver their events to
+/// the `Poll` instance it was obtained from. Once that `Poll` instance is
+/// dropped, handles can still be registered but never become ready.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn try_main() -> Result<(), Box<Error>> {
+/// use mio::{Events, Poll, PollOpt, Ready, Registration, Token};
+/// use std::thread;
+///
+/// let poll = Poll::new()?;
+/// let registry = poll.registry().clone();
+///
+/// let (registration, set_readiness) = Registration::new2();
+///
+/// let handle = thread::spawn(move || {
+///     // Register the handle without access to `poll`
+///     registry.register(&registration, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
+///     set_readiness.set_readiness(Ready::readable()).unwrap();
+///
+///     registration
+/// });
+///
+/// let registration = handle.join().unwrap();
+///
+/// let mut events = Events::with_capacity(16);
+/// poll.poll(&mut events, None)?;
+///
+/// assert_eq!(events.get(0).unwrap().token(), Token(0));
+/// # drop(registration);
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`Poll`]: struct.Poll.html
+/// [`Poll::registry`]: struct.Poll.html#method.registry
+/// [`register`]: #method.register
+/// [`reregister`]: #method.reregister
+/// [`deregister`]: #method.deregister
+/// [`Evented`]: event/trait.Evented.html
+#[derive(Clone)]
+pub struct Registry {
+    // Platform specific IO selector
+    selector: Arc<sys::Selector>,
+
+    // Handle to the custom readiness queue owned by `Poll`
+    readiness_queue: Arc<ReadinessQueueInner>,
+
+    // Latest generation registered for each token index, set when stale
+    // events are dropped. See `Poll::set_drop_stale`.
+    generations: Arc<Mutex<Option<HashMap<usize, usize>>>>,
 }
 
+unsafe impl Send for Registry {}
+unsafe impl Sync for Registry {}
+
 /// Handle to a user space `Poll` registration.
 ///
 /// `Registration` allows implementing [`Evented`] for types that cannot work
@@ -367,7 +517,7 @@ pub struct Poll {
 /// # Examples
 ///
 /// ```
-/// use mio::{Ready, Registration, Poll, PollOpt, Token};
+/// use mio::{Ready, Registration, PollOpt, Registry, Token};
 /// use mio::event::Evented;
 ///
 /// use std::io;
@@ -405,20 +555,20 @@ pub struct Poll {
 /// }
 ///
 /// impl Evented for Deadline {
-///     fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt)
+///     fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt)
 ///         -> io::Result<()>
 ///     {
-///         self.registration.register(poll, token, interest, opts)
+///         self.registration.register(registry, token, interest, opts)
 ///     }
 ///
-///     fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt)
+///     fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt)
 ///         -> io::Result<()>
 ///     {
-///         self.registration.reregister(poll, token, interest, opts)
+///         self.registration.reregister(registry, token, interest, opts)
 ///     }
 ///
-///     fn deregister(&self, poll: &Poll) -> io::Result<()> {
-///         self.registration.deregister(poll)
+///     fn deregister(&self, registry: &Registry) -> io::Result<()> {
+///         self.registration.deregister(registry)
 ///     }
 /// }
 /// ```
@@ -454,6 +604,62 @@ pub struct SetReadiness {
 unsafe impl Send for SetReadiness {}
 unsafe impl Sync for SetReadiness {}
 
+/// Wakes up a [`Poll`] instance from any thread.
+///
+/// A `Waker` is bound to a `Poll` instance and a token when it is created.
+/// Calling [`wake`] makes the next call to [`Poll::poll`] return a readable
+/// event for that token, waking the polling thread if it is blocked.
+///
+/// Unlike a [`Registration`] / [`SetReadiness`] pair, a `Waker` needs no
+/// registration step. Repeated calls to `wake` before `Poll::poll` observes
+/// the first one are coalesced into a single event.
+///
+/// [`Poll`]: struct.Poll.html
+/// [`Poll::poll`]: struct.Poll.html#method.poll
+/// [`wake`]: #method.wake
+/// [`Registration`]: struct.Registration.html
+/// [`SetReadiness`]: struct.SetReadiness.html
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn try_main() -> Result<(), Box<dyn Error>> {
+/// use mio::{Events, Poll, Token, Waker};
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// const WAKER: Token = Token(0);
+///
+/// let poll = Poll::new()?;
+/// let waker = Arc::new(Waker::new(&poll, WAKER)?);
+/// let mut events = Events::with_capacity(128);
+///
+/// let w = waker.clone();
+/// let handle = thread::spawn(move || {
+///     w.wake().unwrap();
+/// });
+///
+/// poll.poll(&mut events, None)?;
+///
+/// let event = events.iter().next().unwrap();
+/// assert_eq!(event.token(), WAKER);
+/// assert!(event.readiness().is_readable());
+/// #     handle.join().unwrap();
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+pub struct Waker {
+    inner: RegistrationInner,
+}
+
+unsafe impl Send for Waker {}
+unsafe impl Sync for Waker {}
+
 /// Used to associate an IO type with a Selector
 #[derive(Debug)]
 pub struct SelectorId {
@@ -502,6 +708,47 @@ struct ReadinessQueueInner {
     // This happens when `ReadyQueue` is dropped and signals to producers that
     // the nodes should no longer be pushed into the queue.
     closed_marker: Box<ReadinessNode>,
+
+    // Nodes registered with `PollOpt::urgent()`, drained before the MPSC
+    // queue.
+    urgent: Mutex<UrgentQueue>,
+
+    // Set while `urgent` is not empty. Lets `Poll::poll` check for urgent
+    // nodes without taking the lock.
+    urgent_pending: AtomicBool,
+
+    // Number of `Poll` instances this one is registered with. While nested,
+    // every readiness change wakes the awakener, which the parents poll.
+    nested: AtomicUsize,
+
+    // Set by `Poll::close`
+    poll_closed: AtomicBool,
+
+    // Nodes of released registrations, reused by the next ones
+    pool: Mutex<NodePool>,
+}
+
+struct UrgentQueue {
+    nodes: VecDeque<*mut ReadinessNode>,
+    closed: bool,
+}
+
+/// Free list of `ReadinessNode`s, see `Poll::set_registration_pool`.
+///
+/// A node only makes it here once its ref count dropped to zero, so nothing
+/// else can reach it. The nodes are not associated with the queue, which
+/// would keep it alive.
+struct NodePool {
+    nodes: Vec<*mut ReadinessNode>,
+    cap: usize,
+}
+
+impl Drop for NodePool {
+    fn drop(&mut self) {
+        for &ptr in &self.nodes {
+            drop(unsafe { Box::from_raw(ptr) });
+        }
+    }
 }
 
 /// Node shared by a `Registration` / `SetReadiness` pair as well as the node
@@ -554,6 +801,10 @@ struct ReadinessNode {
 
     // Tracks the number of `ReadyRef` pointers
     ref_count: AtomicUsize,
+
+    // Pointer to the Arc<ReadinessQueueInner> the node is being moved to, see
+    // `RegistrationInner::transfer`
+    moved_to: AtomicPtr<()>,
 }
 
 /// Stores the ReadinessNode state in an AtomicUsize. This wrapper around the
@@ -564,22 +815,23 @@ struct AtomicState {
 
 const MASK_2: usize = 4 - 1;
 const MASK_4: usize = 16 - 1;
+const MASK_9: usize = 512 - 1;
 const QUEUED_MASK: usize = 1 << QUEUED_SHIFT;
 const DROPPED_MASK: usize = 1 << DROPPED_SHIFT;
 
 const READINESS_SHIFT: usize = 0;
-const INTEREST_SHIFT: usize = 4;
-const POLL_OPT_SHIFT: usize = 8;
-const TOKEN_RD_SHIFT: usize = 12;
-const TOKEN_WR_SHIFT: usize = 14;
-const QUEUED_SHIFT: usize = 16;
-const DROPPED_SHIFT: usize = 17;
+const INTEREST_SHIFT: usize = 9;
+const POLL_OPT_SHIFT: usize = 18;
+const TOKEN_RD_SHIFT: usize = 22;
+const TOKEN_WR_SHIFT: usize = 24;
+const QUEUED_SHIFT: usize = 26;
+const DROPPED_SHIFT: usize = 27;
 
 /// Tracks all state for a single `ReadinessNode`. The state is packed into a
 /// `usize` variable from low to high bit as follows:
 ///
-/// 4 bits: Registration current readiness
-/// 4 bits: Registration interest
+/// 9 bits: Registration current readiness, wide enough for every `UnixReady`
+/// 9 bits: Registration interest
 /// 4 bits: Poll options
 /// 2 bits: Token position currently being read from by `poll`
 /// 2 bits: Token position last written to by `update`
@@ -599,6 +851,9 @@ enum Dequeue {
 const AWAKEN: Token = Token(usize::MAX);
 const MAX_REFCOUNT: usize = (isize::MAX) as usize;
 
+// Number of released nodes kept for reuse by default
+const POOL_CAP: usize = 64;
+
 /*
  *
  * ===== Poll =====
@@ -646,121 +901,149 @@ impl Poll {
     pub fn new() -> io::Result<Poll> {
         is_send::<Poll>();
         is_sync::<Poll>();
+        is_send::<Registry>();
+        is_sync::<Registry>();
+
+        let readiness_queue = ReadinessQueue::new()?;
+
+        let registry = Registry {
+            selector: Arc::new(sys::Selector::new()?),
+            readiness_queue: readiness_queue.inner.clone(),
+            generations: Arc::new(Mutex::new(None)),
+        };
 
         let poll = Poll {
-            selector: sys::Selector::new()?,
-            readiness_queue: ReadinessQueue::new()?,
+            registry: registry,
+            readiness_queue: readiness_queue,
             lock_state: AtomicUsize::new(0),
             lock: Mutex::new(()),
             condvar: Condvar::new(),
+            drop_stale: false,
+            max_events: 0,
+            pollers: 1,
+            slots: Mutex::new(vec![]),
+            slot_freed: Condvar::new(),
+            draining: AtomicBool::new(false),
         };
 
         // Register the notification wakeup FD with the IO poller
-        poll.readiness_queue.inner.awakener.register(&poll, AWAKEN, Ready::readable(), PollOpt::edge())?;
+        poll.readiness_queue.inner.awakener.register(&poll.registry, AWAKEN, Ready::readable(), PollOpt::level())?;
 
         Ok(poll)
     }
 
-    /// Register an `Evented` handle with the `Poll` instance.
-    ///
-    /// Once registered, the `Poll` instance will monitor the `Evented` handle
-    /// for readiness state changes. When it notices a state change, it will
-    /// return a readiness event for the handle the next time [`poll`] is
-    /// called.
-    ///
-    /// See the [`struct`] docs for a high level overview.
-    ///
-    /// # Arguments
+    /// Returns the `Registry` used to register handles with this `Poll`
+    /// instance.
     ///
-    /// `handle: &E: Evented`: This is the handle that the `Poll` instance
-    /// should monitor for readiness state changes.
+    /// The `Registry` can be cloned and sent to other threads, see its
+    /// [documentation] for details.
     ///
-    /// `token: Token`: The caller picks a token to associate with the socket.
-    /// When [`poll`] returns an event for the handle, this token is included.
-    /// This allows the caller to map the event to its handle. The token
-    /// associated with the `Evented` handle can be changed at any time by
-    /// calling [`reregister`].
+    /// [documentation]: struct.Registry.html
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Drop events carrying a stale token generation.
     ///
-    /// `token` cannot be `Token(usize::MAX)` as it is reserved for internal
-    /// usage.
+    /// When enabled, `Poll` remembers the [generation] of the token each
+    /// handle was last registered or reregistered with, per token [index].
+    /// Events whose token is of another generation than the latest one
+    /// registered for its index are not returned by [`poll`]. This covers
+    /// events that were already collected for a handle when its token was
+    /// handed to a new handle, for example by another thread.
     ///
-    /// See documentation on [`Token`] for an example showing how to pick
-    /// [`Token`] values.
+    /// Tokens should be created with [`Token::with_generation`], or by
+    /// [`TokenMap`], and the generation bumped every time an index is reused.
+    /// Only registrations made while the mode is enabled are tracked, whether
+    /// they are made through `Poll` or a [`Registry`].
     ///
-    /// `interest: Ready`: Specifies which operations `Poll` should monitor for
-    /// readiness. `Poll` will only return readiness events for operations
-    /// specified by this argument.
+    /// # Examples
     ///
-    /// If a socket is registered with readable interest and the socket becomes
-    /// writable, no event will be returned from [`poll`].
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Poll, PollOpt, Ready, Registration, Token};
+    /// use std::time::Duration;
     ///
-    /// The readiness interest for an `Evented` handle can be changed at any
-    /// time by calling [`reregister`].
+    /// let mut poll = Poll::new()?;
+    /// let mut events = Events::with_capacity(16);
     ///
-    /// `opts: PollOpt`: Specifies the registration options. The most common
-    /// options being [`level`] for level-triggered events, [`edge`] for
-    /// edge-triggered events, and [`oneshot`].
+    /// poll.set_drop_stale(true);
     ///
-    /// The registration options for an `Evented` handle can be changed at any
-    /// time by calling [`reregister`].
+    /// let old = Token::with_generation(0, 0);
+    /// let (registration, set_readiness) = Registration::new2();
+    /// poll.register(&registration, old, Ready::readable(), PollOpt::edge())?;
+    /// set_readiness.set_readiness(Ready::readable())?;
     ///
-    /// # Notes
+    /// // The index is handed to a new registration before the event is seen
+    /// let new = old.next_generation();
+    /// let (registration2, set_readiness2) = Registration::new2();
+    /// poll.register(&registration2, new, Ready::readable(), PollOpt::edge())?;
+    /// set_readiness2.set_readiness(Ready::readable())?;
     ///
-    /// Unless otherwise specified, the caller should assume that once an
-    /// `Evented` handle is registered with a `Poll` instance, it is bound to
-    /// that `Poll` instance for the lifetime of the `Evented` handle. This
-    /// remains true even if the `Evented` handle is deregistered from the poll
-    /// instance using [`deregister`].
+    /// poll.poll(&mut events, Some(Duration::from_millis(0)))?;
     ///
-    /// This function is **thread safe**. It can be called concurrently from
-    /// multiple threads.
+    /// assert_eq!(events.len(), 1);
+    /// assert_eq!(events.get(0).unwrap().token(), new);
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
     ///
-    /// [`struct`]: #
-    /// [`reregister`]: #method.reregister
-    /// [`deregister`]: #method.deregister
+    /// [generation]: struct.Token.html#method.generation
+    /// [index]: struct.Token.html#method.index
     /// [`poll`]: #method.poll
-    /// [`level`]: struct.PollOpt.html#method.level
-    /// [`edge`]: struct.PollOpt.html#method.edge
-    /// [`oneshot`]: struct.PollOpt.html#method.oneshot
-    /// [`Token`]: struct.Token.html
+    /// [`Token::with_generation`]: struct.Token.html#method.with_generation
+    /// [`TokenMap`]: struct.TokenMap.html
+    /// [`Registry`]: struct.Registry.html
+    pub fn set_drop_stale(&mut self, enable: bool) {
+        let mut generations = self.registry.generations.lock().unwrap();
+
+        if !enable {
+            *generations = None;
+        } else if generations.is_none() {
+            *generations = Some(HashMap::new());
+        }
+
+        self.drop_stale = enable;
+    }
+
+    /// Let [`poll`] grow an [`Events`] that fills up.
+    ///
+    /// By default, once `Events` is full, any other ready events are left
+    /// for the next call to [`poll`] and [`Events::is_truncated`] is set.
+    /// With `Some(max)`, `poll` instead doubles the capacity of `Events` as
+    /// needed, until it holds `max` events. The grown capacity is kept for
+    /// later calls.
     ///
     /// # Examples
     ///
     /// ```
     /// # use std::error::Error;
-    /// # fn try_main() -> Result<(), Box<Error>> {
-    /// use mio::{Events, Poll, Ready, PollOpt, Token};
-    /// use mio::net::TcpStream;
-    /// use std::time::{Duration, Instant};
-    ///
-    /// let poll = Poll::new()?;
-    /// let socket = TcpStream::connect(&"216.58.193.100:80".parse()?)?;
-    ///
-    /// // Register the socket with `poll`
-    /// poll.register(&socket, Token(0), Ready::readable() | Ready::writable(), PollOpt::edge())?;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Poll, PollOpt, Ready, Registration, Token};
+    /// use std::time::Duration;
     ///
-    /// let mut events = Events::with_capacity(1024);
-    /// let start = Instant::now();
-    /// let timeout = Duration::from_millis(500);
+    /// let mut poll = Poll::new()?;
+    /// let mut events = Events::with_capacity(2);
+    /// let mut regs = vec![];
     ///
-    /// loop {
-    ///     let elapsed = start.elapsed();
+    /// poll.set_max_events(Some(8));
     ///
-    ///     if elapsed >= timeout {
-    ///         // Connection timed out
-    ///         return Ok(());
-    ///     }
+    /// for i in 0..16 {
+    ///     let (registration, set_readiness) = Registration::new2();
+    ///     poll.register(&registration, Token(i), Ready::readable(), PollOpt::edge())?;
+    ///     set_readiness.set_readiness(Ready::readable())?;
+    ///     regs.push((registration, set_readiness));
+    /// }
     ///
-    ///     let remaining = timeout - elapsed;
-    ///     poll.poll(&mut events, Some(remaining))?;
+    /// poll.poll(&mut events, Some(Duration::from_millis(0)))?;
     ///
-    ///     for event in &events {
-    ///         if event.token() == Token(0) {
-    ///             // Something (probably) happened on the socket.
-    ///             return Ok(());
-    ///         }
-    ///     }
-    /// }
+    /// assert_eq!(events.len(), 8);
+    /// assert!(events.is_truncated());
     /// #     Ok(())
     /// # }
     /// #
@@ -768,65 +1051,90 @@ impl Poll {
     /// #     try_main().unwrap();
     /// # }
     /// ```
-    pub fn register<E: ?Sized>(&self, handle: &E, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>
-        where E: Evented
-    {
-        validate_args(token)?;
+    ///
+    /// [`poll`]: #method.poll
+    /// [`Events`]: struct.Events.html
+    /// [`Events::is_truncated`]: struct.Events.html#method.is_truncated
+    pub fn set_max_events(&mut self, max: Option<usize>) {
+        self.max_events = max.unwrap_or(0);
+    }
 
-        /*
-         * Undefined behavior:
-         * - Reusing a token with a different `Evented` without deregistering
-         * (or closing) the original `Evented`.
-         */
-        trace!("registering with poller");
+    /// Set the number of released registration nodes kept for reuse.
+    ///
+    /// Every [`Registration`] and [`Waker`] is backed by a node shared with the
+    /// readiness queue. Once a handle associated with this `Poll` instance is
+    /// dropped, and [`poll`] has seen it go, its node is kept in a free list
+    /// and reused by the next [`Registration::with_registry`] or [`Waker`]
+    /// instead of being freed. At most `cap` nodes are kept, 64 by default.
+    ///
+    /// Passing `0` disables the pool and frees the nodes it holds.
+    ///
+    /// [`poll`]: #method.poll
+    /// [`Registration`]: struct.Registration.html
+    /// [`Registration::with_registry`]: struct.Registration.html#method.with_registry
+    /// [`Waker`]: struct.Waker.html
+    pub fn set_registration_pool(&mut self, cap: usize) {
+        let mut pool = self.readiness_queue.inner.pool.lock().unwrap();
 
-        // Register interests for this socket
-        handle.register(self, token, interest, opts)?;
+        pool.cap = cap;
 
-        Ok(())
+        while pool.nodes.len() > cap {
+            let ptr = pool.nodes.pop().unwrap();
+            drop(unsafe { Box::from_raw(ptr) });
+        }
     }
 
-    /// Re-register an `Evented` handle with the `Poll` instance.
-    ///
-    /// Re-registering an `Evented` handle allows changing the details of the
-    /// registration. Specifically, it allows updating the associated `token`,
-    /// `interest`, and `opts` specified in previous `register` and `reregister`
-    /// calls.
-    ///
-    /// The `reregister` arguments fully override the previous values. In other
-    /// words, if a socket is registered with [`readable`] interest and the call
-    /// to `reregister` specifies [`writable`], then read interest is no longer
-    /// requested for the handle.
+    /// Allow up to `pollers` threads to call [`poll`] at the same time.
     ///
-    /// The `Evented` handle must have previously been registered with this
-    /// instance of `Poll` otherwise the call to `reregister` will return with
-    /// an error.
+    /// By default, concurrent calls to [`poll`] are serialized: one thread
+    /// waits in the system selector while the others wait for it to return.
+    /// With more than one poller, the registered handles are split between
+    /// the pollers, and each thread waits only for its own share. A readiness
+    /// change wakes up the one thread waiting for the handle instead of all
+    /// of them, and no event is returned to more than one thread.
     ///
-    /// `token` cannot be `Token(usize::MAX)` as it is reserved for internal
-    /// usage.
+    /// Events from [`SetReadiness`] are returned to a single thread at a time,
+    /// the first one to find the readiness queue free. When that thread
+    /// returns, another waiting poller is woken up to take the queue over.
+    /// Threads beyond `pollers` wait for a poller to return, as in the
+    /// serialized mode.
     ///
-    /// See the [`register`] documentation for details about the function
-    /// arguments and see the [`struct`] docs for a high level overview of
-    /// polling.
+    /// Passing `1` goes back to serialized polling. An error is returned if
+    /// the system resources needed to wake up the pollers cannot be created.
     ///
     /// # Examples
     ///
     /// ```
     /// # use std::error::Error;
-    /// # fn try_main() -> Result<(), Box<Error>> {
-    /// use mio::{Poll, Ready, PollOpt, Token};
-    /// use mio::net::TcpStream;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Poll, PollOpt, Ready, Token};
+    /// use mio::net::UdpSocket;
+    /// use std::sync::Arc;
+    /// use std::thread;
+    /// use std::time::Duration;
     ///
-    /// let poll = Poll::new()?;
-    /// let socket = TcpStream::connect(&"216.58.193.100:80".parse()?)?;
+    /// let mut poll = Poll::new()?;
+    /// poll.set_concurrent_pollers(4)?;
     ///
-    /// // Register the socket with `poll`, requesting readable
-    /// poll.register(&socket, Token(0), Ready::readable(), PollOpt::edge())?;
+    /// let socket = UdpSocket::bind(&"127.0.0.1:0".parse()?)?;
+    /// poll.register(&socket, Token(0), Ready::writable(), PollOpt::level())?;
     ///
-    /// // Reregister the socket specifying a different token and write interest
-    /// // instead. `PollOpt::edge()` must be specified even though that value
-    /// // is not being changed.
-    /// poll.reregister(&socket, Token(2), Ready::writable(), PollOpt::edge())?;
+    /// let poll = Arc::new(poll);
+    ///
+    /// let handles: Vec<_> = (0..4).map(|_| {
+    ///     let poll = poll.clone();
+    ///
+    ///     thread::spawn(move || {
+    ///         let mut events = Events::with_capacity(16);
+    ///
+    ///         // Each thread only waits for its share of the handles
+    ///         poll.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+    ///     })
+    /// }).collect();
+    ///
+    /// for handle in handles {
+    ///     handle.join().unwrap();
+    /// }
     /// #     Ok(())
     /// # }
     /// #
@@ -835,59 +1143,136 @@ impl Poll {
     /// # }
     /// ```
     ///
-    /// [`struct`]: #
-    /// [`register`]: #method.register
-    /// [`readable`]: struct.Ready.html#method.readable
-    /// [`writable`]: struct.Ready.html#method.writable
-    pub fn reregister<E: ?Sized>(&self, handle: &E, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>
-        where E: Evented
-    {
-        validate_args(token)?;
+    /// [`poll`]: #method.poll
+    /// [`SetReadiness`]: struct.SetReadiness.html
+    pub fn set_concurrent_pollers(&mut self, pollers: usize) -> io::Result<()> {
+        let pollers = cmp::max(pollers, 1);
 
-        trace!("registering with poller");
+        self.registry.selector.set_partitions(pollers)?;
 
-        // Register interests for this socket
-        handle.reregister(self, token, interest, opts)?;
+        self.pollers = pollers;
+        self.slots = Mutex::new(if pollers > 1 { vec![false; pollers] } else { vec![] });
 
         Ok(())
     }
 
-    /// Deregister an `Evented` handle with the `Poll` instance.
+    /// Returns the features supported by the system selector backing this
+    /// `Poll` instance.
     ///
-    /// When an `Evented` handle is deregistered, the `Poll` instance will
-    /// no longer monitor it for readiness state changes. Unlike disabling
-    /// handles with oneshot, deregistering clears up any internal resources
-    /// needed to track the handle.
+    /// Code running on several platforms can use this to pick the options it
+    /// registers handles with. See [`Capabilities`] for the reported flags.
     ///
-    /// A handle can be passed back to `register` after it has been
-    /// deregistered; however, it must be passed back to the **same** `Poll`
-    /// instance.
+    /// # Examples
     ///
-    /// `Evented` handles are automatically deregistered when they are dropped.
-    /// It is common to never need to explicitly call `deregister`.
+    /// ```
+    /// use mio::{Poll, PollOpt};
+    ///
+    /// let poll = Poll::new().unwrap();
+    ///
+    /// // Fall back to level-triggered notifications where edge-triggered
+    /// // ones would be rejected.
+    /// let opts = if poll.capabilities().is_native_edge() {
+    ///     PollOpt::edge()
+    /// } else {
+    ///     PollOpt::level()
+    /// };
+    /// # drop(opts);
+    /// ```
+    ///
+    /// [`Capabilities`]: struct.Capabilities.html
+    pub fn capabilities(&self) -> Capabilities {
+        self.registry.selector.capabilities()
+    }
+
+    /// Reject registrations the system selector can only emulate.
+    ///
+    /// Strict registration is enabled by default: registering a system handle
+    /// with options or readiness the selector does not support natively makes
+    /// [`register`] and [`reregister`] fail with
+    /// [`RegisterError::Unsupported`]. For instance, the poll(2) selector has
+    /// no edge-triggered or oneshot mode, and only reports
+    /// [`UnixReady::read_closed`] separately from a hang up on some platforms,
+    /// see [`Poll::capabilities`].
+    ///
+    /// Passing `false` opts out: such handles then get the closest semantics
+    /// available, edge-triggered and oneshot registrations are reported like
+    /// level-triggered ones and read closed readiness only with a hang up.
+    ///
+    /// Registrations of readiness the selector cannot report at all always
+    /// fail, whether strict registration is enabled or not. [`Registration`]
+    /// handles are not affected: they support every option and every
+    /// readiness, including [`UnixReady::read_closed`].
     ///
     /// # Examples
     ///
     /// ```
     /// # use std::error::Error;
-    /// # fn try_main() -> Result<(), Box<Error>> {
-    /// use mio::{Events, Poll, Ready, PollOpt, Token};
-    /// use mio::net::TcpStream;
-    /// use std::time::Duration;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Poll, PollOpt, Ready, RegisterError, Token};
+    /// use mio::net::UdpSocket;
+    ///
+    /// let mut poll = Poll::new()?;
+    /// let socket = UdpSocket::bind(&"127.0.0.1:0".parse()?)?;
+    ///
+    /// match poll.register(&socket, Token(0), Ready::readable(), PollOpt::edge()) {
+    ///     Err(ref e) if RegisterError::from_io_error(e).is_some() => {
+    ///         // Emulated edge-triggered notifications are good enough
+    ///         poll.set_strict_registration(false);
+    ///         poll.register(&socket, Token(0), Ready::readable(), PollOpt::edge())?;
+    ///     }
+    ///     res => res?,
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
     ///
-    /// let poll = Poll::new()?;
-    /// let socket = TcpStream::connect(&"216.58.193.100:80".parse()?)?;
+    /// [`register`]: #method.register
+    /// [`reregister`]: #method.reregister
+    /// [`RegisterError::Unsupported`]: enum.RegisterError.html#variant.Unsupported
+    /// [`UnixReady::read_closed`]: unix/struct.UnixReady.html#method.read_closed
+    /// [`Poll::capabilities`]: #method.capabilities
+    /// [`Registration`]: struct.Registration.html
+    pub fn set_strict_registration(&mut self, strict: bool) {
+        self.registry.selector.set_strict(strict);
+    }
+
+    /// Panic when a registered handle turns out to be closed.
     ///
-    /// // Register the socket with `poll`
-    /// poll.register(&socket, Token(0), Ready::readable(), PollOpt::edge())?;
+    /// A handle has to be deregistered before its file descriptor is closed.
+    /// Otherwise the poll(2) selector keeps polling the file descriptor until
+    /// it notices, then drops the registration, after reporting it with
+    /// [`UnixReady::invalid`] readiness if the handle was registered with that
+    /// interest. If the number is reused in the meantime, the new file is
+    /// polled with the token of the closed handle instead.
     ///
-    /// poll.deregister(&socket)?;
+    /// With debug assertions enabled, [`poll`] panics instead as soon as it
+    /// finds a registered file descriptor closed or reused, and so does
+    /// [`register`] when it is handed a reused one. This is meant to track
+    /// down handles that are dropped while still registered.
     ///
-    /// let mut events = Events::with_capacity(1024);
+    /// # Examples
     ///
-    /// // Set a timeout because this poll should never receive any events.
-    /// let n = poll.poll(&mut events, Some(Duration::from_secs(1)))?;
-    /// assert_eq!(0, n);
+    /// ```should_panic
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Poll, PollOpt, Ready, Token};
+    /// use mio::net::UdpSocket;
+    ///
+    /// let mut poll = Poll::new()?;
+    /// poll.set_debug_assertions(true);
+    ///
+    /// let socket = UdpSocket::bind(&"127.0.0.1:0".parse()?)?;
+    /// poll.register(&socket, Token(0), Ready::writable(), PollOpt::level())?;
+    ///
+    /// // Closed without being deregistered
+    /// drop(socket);
+    ///
+    /// let mut events = Events::with_capacity(16);
+    /// poll.poll(&mut events, None)?;
     /// #     Ok(())
     /// # }
     /// #
@@ -895,98 +1280,199 @@ impl Poll {
     /// #     try_main().unwrap();
     /// # }
     /// ```
-    pub fn deregister<E: ?Sized>(&self, handle: &E) -> io::Result<()>
-        where E: Evented
-    {
-        trace!("deregistering handle with poller");
+    ///
+    /// [`UnixReady::invalid`]: unix/struct.UnixReady.html#method.invalid
+    /// [`poll`]: #method.poll
+    /// [`register`]: #method.register
+    pub fn set_debug_assertions(&mut self, enabled: bool) {
+        self.registry.selector.set_assert_open(enabled);
+    }
 
-        // Deregister interests for this socket
-        handle.deregister(self)?;
+    /// Separate the `Poll` instance of a forked child from its parent.
+    ///
+    /// A child process created with `fork()` inherits a copy of the `Poll`
+    /// instance, but the sockets `Poll` uses to wake itself up are shared
+    /// with the parent: each process could then be woken up by, and swallow
+    /// the wakeups of, the other one. Calling `after_fork_child` in the child
+    /// gives it sockets of its own, and forgets about the threads of the
+    /// parent that were blocked in [`poll`] at the time of the fork.
+    ///
+    /// The poll(2) selector keeps the table of file descriptors it polls in
+    /// the memory of the process, and the child gets its own copy of it.
+    /// Nothing is rebuilt or registered again: only the wakeup sockets are
+    /// replaced. Handles registered before the fork stay registered in the
+    /// child, with the same tokens. The sockets they wrap are still shared
+    /// with the parent, so both processes get readiness events for them.
+    /// [`Registration`] handles are only ever notified by [`SetReadiness`]
+    /// calls made in the same process.
+    ///
+    /// This has to be called in the child before the `Poll` instance is used.
+    /// Threads of the parent do not exist in the child, and the locks they
+    /// held at the time of the fork are never released. The lock sequencing
+    /// calls to [`poll`] is replaced. The fork must not happen while another
+    /// thread of the parent registers, reregisters or deregisters a handle,
+    /// creates or drops a [`Registration`] or a [`Waker`], or calls
+    /// [`SetReadiness::set_readiness`]: these lock the selector or the
+    /// readiness queue. If one of them turns out to be locked,
+    /// `after_fork_child` fails instead of blocking, and the `Poll` instance
+    /// cannot be used in the child.
+    ///
+    /// Only this `Poll` instance is separated. Other `Poll` instances
+    /// registered with it, directly or not, still share their sockets with
+    /// the parent, and each needs its own call to `after_fork_child`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Poll};
+    ///
+    /// let mut poll = Poll::new()?;
+    ///
+    /// match unsafe { libc::fork() } {
+    ///     -1 => return Err(std::io::Error::last_os_error().into()),
+    ///     0 => {
+    ///         // In the child
+    ///         poll.after_fork_child()?;
+    ///     }
+    ///     _ => {}
+    /// }
+    ///
+    /// let mut events = Events::with_capacity(1024);
+    /// poll.poll(&mut events, None)?;
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`poll`]: #method.poll
+    /// [`Registration`]: struct.Registration.html
+    /// [`SetReadiness`]: struct.SetReadiness.html
+    /// [`SetReadiness::set_readiness`]: struct.SetReadiness.html#method.set_readiness
+    /// [`Waker`]: struct.Waker.html
+    pub fn after_fork_child(&mut self) -> io::Result<()> {
+        self.readiness_queue.inner.check_unlocked()?;
+        self.registry.selector.after_fork(&self.readiness_queue.inner.awakener)?;
+
+        // Only the forking thread exists in the child. A thread blocked in
+        // `poll` may have held `lock`, which is leaked rather than dropped
+        // while locked.
+        mem::forget(mem::replace(&mut self.lock, Mutex::new(())));
+        self.condvar = Condvar::new();
+        self.slot_freed = Condvar::new();
+        self.lock_state = AtomicUsize::new(0);
+        self.draining = AtomicBool::new(false);
+
+        for s in self.slots.get_mut().unwrap_or_else(|e| e.into_inner()).iter_mut() {
+            *s = false;
+        }
 
         Ok(())
     }
 
-    /// Wait for readiness events
+    /// Register an `Evented` handle with the `Poll` instance.
     ///
-    /// Blocks the current thread and waits for readiness events for any of the
-    /// `Evented` handles that have been registered with this `Poll` instance.
-    /// The function will block until either at least one readiness event has
-    /// been received or `timeout` has elapsed. A `timeout` of `None` means that
-    /// `poll` will block until a readiness event has been received.
+    /// Once registered, the `Poll` instance will monitor the `Evented` handle
+    /// for readiness state changes. When it notices a state change, it will
+    /// return a readiness event for the handle the next time [`poll`] is
+    /// called.
     ///
-    /// The supplied `events` will be cleared and newly received readiness events
-    /// will be pushed onto the end. At most `events.capacity()` events will be
-    /// returned. If there are further pending readiness events, they will be
-    /// returned on the next call to `poll`.
+    /// See the [`struct`] docs for a high level overview.
     ///
-    /// A single call to `poll` may result in multiple readiness events being
-    /// returned for a single `Evented` handle. For example, if a TCP socket
-    /// becomes both readable and writable, it may be possible for a single
-    /// readiness event to be returned with both [`readable`] and [`writable`]
-    /// readiness **OR** two separate events may be returned, one with
-    /// [`readable`] set and one with [`writable`] set.
+    /// # Arguments
     ///
-    /// Note that the `timeout` will be rounded up to the system clock
-    /// granularity (usually 1ms), and kernel scheduling delays mean that
-    /// the blocking interval may be overrun by a small amount.
+    /// `handle: &E: Evented`: This is the handle that the `Poll` instance
+    /// should monitor for readiness state changes.
     ///
-    /// `poll` returns the number of readiness events that have been pushed into
-    /// `events` or `Err` when an error has been encountered with the system
-    /// selector.  The value returned is deprecated and will be removed in 0.7.0.
-    /// Accessing the events by index is also deprecated.  Events can be
-    /// inserted by other events triggering, thus making sequential access
-    /// problematic.  Use the iterator API instead.  See [`iter`].
+    /// `token: Token`: The caller picks a token to associate with the socket.
+    /// When [`poll`] returns an event for the handle, this token is included.
+    /// This allows the caller to map the event to its handle. The token
+    /// associated with the `Evented` handle can be changed at any time by
+    /// calling [`reregister`].
     ///
-    /// See the [struct] level documentation for a higher level discussion of
-    /// polling.
+    /// `token` cannot be `Token(usize::MAX)` as it is reserved for internal
+    /// usage.
     ///
-    /// [`readable`]: struct.Ready.html#method.readable
-    /// [`writable`]: struct.Ready.html#method.writable
-    /// [struct]: #
-    /// [`iter`]: struct.Events.html#method.iter
+    /// See documentation on [`Token`] for an example showing how to pick
+    /// [`Token`] values.
     ///
-    /// # Examples
+    /// `interest: Ready`: Specifies which operations `Poll` should monitor for
+    /// readiness. `Poll` will only return readiness events for operations
+    /// specified by this argument.
     ///
-    /// A basic example -- establishing a `TcpStream` connection.
+    /// If a socket is registered with readable interest and the socket becomes
+    /// writable, no event will be returned from [`poll`].
+    ///
+    /// The readiness interest for an `Evented` handle can be changed at any
+    /// time by calling [`reregister`].
+    ///
+    /// `opts: PollOpt`: Specifies the registration options. The most common
+    /// options being [`level`] for level-triggered events, [`edge`] for
+    /// edge-triggered events, and [`oneshot`].
+    ///
+    /// The registration options for an `Evented` handle can be changed at any
+    /// time by calling [`reregister`].
+    ///
+    /// # Notes
+    ///
+    /// Unless otherwise specified, the caller should assume that once an
+    /// `Evented` handle is registered with a `Poll` instance, it is bound to
+    /// that `Poll` instance for the lifetime of the `Evented` handle. This
+    /// remains true even if the `Evented` handle is deregistered from the poll
+    /// instance using [`deregister`].
+    ///
+    /// This function is **thread safe**. It can be called concurrently from
+    /// multiple threads. It is equivalent to calling [`Registry::register`]
+    /// on the [`registry`] of this `Poll` instance.
+    ///
+    /// [`struct`]: #
+    /// [`Registry::register`]: struct.Registry.html#method.register
+    /// [`registry`]: #method.registry
+    /// [`reregister`]: #method.reregister
+    /// [`deregister`]: #method.deregister
+    /// [`poll`]: #method.poll
+    /// [`level`]: struct.PollOpt.html#method.level
+    /// [`edge`]: struct.PollOpt.html#method.edge
+    /// [`oneshot`]: struct.PollOpt.html#method.oneshot
+    /// [`Token`]: struct.Token.html
+    ///
+    /// # Examples
     ///
     /// ```
     /// # use std::error::Error;
     /// # fn try_main() -> Result<(), Box<Error>> {
     /// use mio::{Events, Poll, Ready, PollOpt, Token};
     /// use mio::net::TcpStream;
+    /// use std::time::{Duration, Instant};
     ///
-    /// use std::net::{TcpListener, SocketAddr};
-    /// use std::thread;
-    ///
-    /// // Bind a server socket to connect to.
-    /// let addr: SocketAddr = "127.0.0.1:0".parse()?;
-    /// let server = TcpListener::bind(&addr)?;
-    /// let addr = server.local_addr()?.clone();
+    /// let poll = Poll::new()?;
+    /// let socket = TcpStream::connect(&"216.58.193.100:80".parse()?)?;
     ///
-    /// // Spawn a thread to accept the socket
-    /// thread::spawn(move || {
-    ///     let _ = server.accept();
-    /// });
+    /// // Register the socket with `poll`
+    /// poll.register(&socket, Token(0), Ready::readable() | Ready::writable(), PollOpt::level())?;
     ///
-    /// // Construct a new `Poll` handle as well as the `Events` we'll store into
-    /// let poll = Poll::new()?;
     /// let mut events = Events::with_capacity(1024);
+    /// let start = Instant::now();
+    /// let timeout = Duration::from_millis(500);
     ///
-    /// // Connect the stream
-    /// let stream = TcpStream::connect(&addr)?;
+    /// loop {
+    ///     let elapsed = start.elapsed();
     ///
-    /// // Register the stream with `Poll`
-    /// poll.register(&stream, Token(0), Ready::readable() | Ready::writable(), PollOpt::edge())?;
+    ///     if elapsed >= timeout {
+    ///         // Connection timed out
+    ///         return Ok(());
+    ///     }
     ///
-    /// // Wait for the socket to become ready. This has to happens in a loop to
-    /// // handle spurious wakeups.
-    /// loop {
-    ///     poll.poll(&mut events, None)?;
+    ///     let remaining = timeout - elapsed;
+    ///     poll.poll(&mut events, Some(remaining))?;
     ///
     ///     for event in &events {
-    ///         if event.token() == Token(0) && event.readiness().is_writable() {
-    ///             // The socket connected (probably, it could still be a spurious
-    ///             // wakeup)
+    ///         if event.token() == Token(0) {
+    ///             // Something (probably) happened on the socket.
     ///             return Ok(());
     ///         }
     ///     }
@@ -998,24 +1484,547 @@ impl Poll {
     /// #     try_main().unwrap();
     /// # }
     /// ```
-    ///
-    /// [struct]: #
-    pub fn poll(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<usize> {
-        self.poll1(events, timeout, false)
+    pub fn register<E: ?Sized>(&self, handle: &E, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>
+        where E: Evented
+    {
+        self.registry.register(handle, token, interest, opts)
     }
 
-    /// Like `poll`, but may be interrupted by a signal
+    /// Register an `Evented` handle with separate tokens for its readable and
+    /// writable readiness.
     ///
-    /// If `poll` is inturrupted while blocking, it will transparently retry the syscall.  If you
-    /// want to handle signals yourself, however, use `poll_interruptible`.
-    pub fn poll_interruptible(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<usize> {
-        self.poll1(events, timeout, true)
-    }
-
-    fn poll1(&self, events: &mut Events, mut timeout: Option<Duration>, interruptible: bool) -> io::Result<usize> {
-        let zero = Some(Duration::from_millis(0));
-
-        // At a high level, the synchronization strategy is to acquire access to
+    /// The handle is registered with both readable and writable interest.
+    /// Readable readiness is reported with `read_token` and writable readiness
+    /// with `write_token`, as two events when both are ready at once. Errors
+    /// and hang ups are reported with both tokens. This lets a reader and a
+    /// writer wait for the same socket independently.
+    ///
+    /// Use [`reregister_split`] to change the interest while keeping both
+    /// tokens, for instance to stop waking up a writer with nothing to send.
+    /// Calling [`reregister`] afterwards goes back to a single token, and
+    /// [`deregister`] removes both. Handles not backed by a single system
+    /// handle, such as [`Registration`], fail with
+    /// [`RegisterError::Unsupported`].
+    ///
+    /// Neither token can be `Token(usize::MAX)` as it is reserved for internal
+    /// usage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Poll, PollOpt, Token};
+    /// use mio::net::TcpStream;
+    /// use std::net::TcpListener;
+    ///
+    /// const READER: Token = Token(0);
+    /// const WRITER: Token = Token(1);
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:0")?;
+    /// let socket = TcpStream::connect(&listener.local_addr()?)?;
+    ///
+    /// let poll = Poll::new()?;
+    /// poll.register_split(&socket, READER, WRITER, PollOpt::level())?;
+    ///
+    /// let mut events = Events::with_capacity(16);
+    ///
+    /// // Once connected, the writer is notified
+    /// loop {
+    ///     poll.poll(&mut events, None)?;
+    ///
+    ///     if events.iter().any(|event| event.token() == WRITER) {
+    ///         break;
+    ///     }
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`reregister_split`]: #method.reregister_split
+    /// [`reregister`]: #method.reregister
+    /// [`deregister`]: #method.deregister
+    /// [`Registration`]: struct.Registration.html
+    /// [`RegisterError::Unsupported`]: enum.RegisterError.html#variant.Unsupported
+    pub fn register_split<E: ?Sized>(&self, handle: &E, read_token: Token, write_token: Token, opts: PollOpt) -> io::Result<()>
+        where E: Evented
+    {
+        self.registry.register_split(handle, read_token, write_token, opts)
+    }
+
+    /// Re-register an `Evented` handle with separate tokens for its readable
+    /// and writable readiness.
+    ///
+    /// Works like [`register_split`], except that the handle must already be
+    /// registered, and only the readiness in `interest` is watched. Readable
+    /// interest enables `read_token`, writable interest enables `write_token`.
+    /// Errors and hang ups are still reported with both tokens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Poll, PollOpt, Ready, Token};
+    /// use mio::net::TcpStream;
+    /// use std::net::TcpListener;
+    ///
+    /// const READER: Token = Token(0);
+    /// const WRITER: Token = Token(1);
+    ///
+    /// let listener = TcpListener::bind("127.0.0.1:0")?;
+    /// let socket = TcpStream::connect(&listener.local_addr()?)?;
+    ///
+    /// let poll = Poll::new()?;
+    /// poll.register_split(&socket, READER, WRITER, PollOpt::level())?;
+    ///
+    /// // Nothing to send for now
+    /// poll.reregister_split(&socket, READER, WRITER, Ready::readable(), PollOpt::level())?;
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`register_split`]: #method.register_split
+    pub fn reregister_split<E: ?Sized>(&self, handle: &E, read_token: Token, write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>
+        where E: Evented
+    {
+        self.registry.reregister_split(handle, read_token, write_token, interest, opts)
+    }
+
+    /// Re-register an `Evented` handle with the `Poll` instance.
+    ///
+    /// Re-registering an `Evented` handle allows changing the details of the
+    /// registration. Specifically, it allows updating the associated `token`,
+    /// `interest`, and `opts` specified in previous `register` and `reregister`
+    /// calls.
+    ///
+    /// The `reregister` arguments fully override the previous values. In other
+    /// words, if a socket is registered with [`readable`] interest and the call
+    /// to `reregister` specifies [`writable`], then read interest is no longer
+    /// requested for the handle.
+    ///
+    /// The `Evented` handle must have previously been registered with this
+    /// instance of `Poll` otherwise the call to `reregister` will return with
+    /// an error.
+    ///
+    /// `token` cannot be `Token(usize::MAX)` as it is reserved for internal
+    /// usage.
+    ///
+    /// See the [`register`] documentation for details about the function
+    /// arguments and see the [`struct`] docs for a high level overview of
+    /// polling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// use mio::{Poll, Ready, PollOpt, Token};
+    /// use mio::net::TcpStream;
+    ///
+    /// let poll = Poll::new()?;
+    /// let socket = TcpStream::connect(&"216.58.193.100:80".parse()?)?;
+    ///
+    /// // Register the socket with `poll`, requesting readable
+    /// poll.register(&socket, Token(0), Ready::readable(), PollOpt::level())?;
+    ///
+    /// // Reregister the socket specifying a different token and write interest
+    /// // instead. `PollOpt::level()` must be specified even though that value
+    /// // is not being changed.
+    /// poll.reregister(&socket, Token(2), Ready::writable(), PollOpt::level())?;
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`struct`]: #
+    /// [`register`]: #method.register
+    /// [`readable`]: struct.Ready.html#method.readable
+    /// [`writable`]: struct.Ready.html#method.writable
+    pub fn reregister<E: ?Sized>(&self, handle: &E, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>
+        where E: Evented
+    {
+        self.registry.reregister(handle, token, interest, opts)
+    }
+
+    /// Deregister an `Evented` handle with the `Poll` instance.
+    ///
+    /// When an `Evented` handle is deregistered, the `Poll` instance will
+    /// no longer monitor it for readiness state changes. Unlike disabling
+    /// handles with oneshot, deregistering clears up any internal resources
+    /// needed to track the handle.
+    ///
+    /// A handle can be passed back to `register` after it has been
+    /// deregistered; however, it must be passed back to the **same** `Poll`
+    /// instance.
+    ///
+    /// `Evented` handles are automatically deregistered when they are dropped.
+    /// It is common to never need to explicitly call `deregister`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// use mio::{Events, Poll, Ready, PollOpt, Token};
+    /// use mio::net::TcpStream;
+    /// use std::time::Duration;
+    ///
+    /// let poll = Poll::new()?;
+    /// let socket = TcpStream::connect(&"216.58.193.100:80".parse()?)?;
+    ///
+    /// // Register the socket with `poll`
+    /// poll.register(&socket, Token(0), Ready::readable(), PollOpt::level())?;
+    ///
+    /// poll.deregister(&socket)?;
+    ///
+    /// let mut events = Events::with_capacity(1024);
+    ///
+    /// // Set a timeout because this poll should never receive any events.
+    /// let n = poll.poll(&mut events, Some(Duration::from_secs(1)))?;
+    /// assert_eq!(0, n);
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    pub fn deregister<E: ?Sized>(&self, handle: &E) -> io::Result<()>
+        where E: Evented
+    {
+        self.registry.deregister(handle)
+    }
+
+    /// Move an `Evented` handle to another `Poll` instance.
+    ///
+    /// `handle` must be registered with this `Poll` instance. It is
+    /// deregistered, then registered with `other` using the given `token`,
+    /// `interest`, and `opts`, as if passed to [`register`]. This lifts the
+    /// restriction that types such as [`TcpStream`] or [`Registration`] can
+    /// only be registered with the first `Poll` instance they were registered
+    /// with, for example to rebalance connections between event loops running
+    /// on different threads.
+    ///
+    /// Readiness set on a [`Registration`] that `Poll::poll` has not yet
+    /// returned is delivered by `other`. If this instance is blocked in
+    /// [`poll`] at that time, the move only completes when it returns.
+    ///
+    /// If registering with `other` fails, the handles provided by mio stay
+    /// registered with this instance as they were. Handles relying on the
+    /// default [`Evented::transfer`] are left deregistered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Poll, PollOpt, Ready, Token};
+    /// use mio::net::UdpSocket;
+    /// use std::time::Duration;
+    ///
+    /// let poll = Poll::new()?;
+    /// let other = Poll::new()?;
+    ///
+    /// let socket = UdpSocket::bind(&"127.0.0.1:0".parse()?)?;
+    /// poll.register(&socket, Token(0), Ready::writable(), PollOpt::level())?;
+    ///
+    /// poll.transfer(&socket, &other, Token(1), Ready::writable(), PollOpt::level())?;
+    ///
+    /// let mut events = Events::with_capacity(16);
+    /// other.poll(&mut events, Some(Duration::from_secs(1)))?;
+    ///
+    /// assert_eq!(events.iter().next().unwrap().token(), Token(1));
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`register`]: #method.register
+    /// [`poll`]: #method.poll
+    /// [`TcpStream`]: net/struct.TcpStream.html
+    /// [`Registration`]: struct.Registration.html
+    /// [`Evented::transfer`]: event/trait.Evented.html#method.transfer
+    pub fn transfer<E: ?Sized>(&self, handle: &E, other: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>
+        where E: Evented
+    {
+        self.registry.transfer(handle, &other.registry, token, interest, opts)
+    }
+
+    /// Close the `Poll` instance.
+    ///
+    /// Every thread blocked in [`poll`], or waiting for another thread to
+    /// return from it, is woken up and returns an error. From then on,
+    /// [`poll`], [`register`], and [`reregister`] fail, including when called
+    /// through a [`Registry`], and so do [`SetReadiness::set_readiness`] and
+    /// [`Waker::wake`] for handles associated with this `Poll` instance. The
+    /// error is a [`PollClosed`] wrapped in an `io::Error`.
+    ///
+    /// Handles can still be deregistered. Resources are released once the
+    /// `Poll` instance is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// use mio::{Events, Poll, PollClosed};
+    /// use std::sync::Arc;
+    /// use std::thread;
+    ///
+    /// let poll = Arc::new(Poll::new()?);
+    /// let poll2 = poll.clone();
+    ///
+    /// let handle = thread::spawn(move || {
+    ///     let mut events = Events::with_capacity(16);
+    ///
+    ///     // Blocks until the `Poll` instance is closed
+    ///     poll2.poll(&mut events, None)
+    /// });
+    ///
+    /// poll.close();
+    ///
+    /// let err = handle.join().unwrap().unwrap_err();
+    /// assert!(PollClosed::matches(&err));
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`poll`]: #method.poll
+    /// [`register`]: #method.register
+    /// [`reregister`]: #method.reregister
+    /// [`Registry`]: struct.Registry.html
+    /// [`SetReadiness::set_readiness`]: struct.SetReadiness.html#method.set_readiness
+    /// [`Waker::wake`]: struct.Waker.html#method.wake
+    /// [`PollClosed`]: struct.PollClosed.html
+    pub fn close(&self) {
+        let queue = &self.readiness_queue.inner;
+
+        if queue.poll_closed.swap(true, SeqCst) {
+            return;
+        }
+
+        // Wake up the threads in the selector
+        let _ = queue.wakeup();
+        let _ = self.registry.selector.wakeup_partitions();
+
+        // Threads waiting for their turn check the flag with the mutex held
+        {
+            let _lock = self.lock.lock().unwrap();
+            self.condvar.notify_all();
+        }
+
+        let _slots = self.slots.lock().unwrap();
+        self.slot_freed.notify_all();
+    }
+
+    /// Wait for readiness events
+    ///
+    /// Blocks the current thread and waits for readiness events for any of the
+    /// `Evented` handles that have been registered with this `Poll` instance.
+    /// The function will block until either at least one readiness event has
+    /// been received or `timeout` has elapsed. A `timeout` of `None` means that
+    /// `poll` will block until a readiness event has been received.
+    ///
+    /// The supplied `events` will be cleared and newly received readiness events
+    /// will be pushed onto the end. At most `events.capacity()` events will be
+    /// returned. If there are further pending readiness events, they will be
+    /// returned on the next call to `poll`.
+    ///
+    /// A single call to `poll` may result in multiple readiness events being
+    /// returned for a single `Evented` handle. For example, if a TCP socket
+    /// becomes both readable and writable, it may be possible for a single
+    /// readiness event to be returned with both [`readable`] and [`writable`]
+    /// readiness **OR** two separate events may be returned, one with
+    /// [`readable`] set and one with [`writable`] set.
+    ///
+    /// Note that the `timeout` will be rounded up to the system clock
+    /// granularity (usually 1ms), and kernel scheduling delays mean that
+    /// the blocking interval may be overrun by a small amount.
+    ///
+    /// `poll` returns the number of readiness events that have been pushed into
+    /// `events` or `Err` when an error has been encountered with the system
+    /// selector.  The value returned is deprecated and will be removed in 0.7.0.
+    /// Accessing the events by index is also deprecated.  Events can be
+    /// inserted by other events triggering, thus making sequential access
+    /// problematic.  Use the iterator API instead.  See [`iter`].
+    ///
+    /// See the [struct] level documentation for a higher level discussion of
+    /// polling.
+    ///
+    /// [`readable`]: struct.Ready.html#method.readable
+    /// [`writable`]: struct.Ready.html#method.writable
+    /// [struct]: #
+    /// [`iter`]: struct.Events.html#method.iter
+    ///
+    /// # Examples
+    ///
+    /// A basic example -- establishing a `TcpStream` connection.
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// use mio::{Events, Poll, Ready, PollOpt, Token};
+    /// use mio::net::TcpStream;
+    ///
+    /// use std::net::{TcpListener, SocketAddr};
+    /// use std::thread;
+    ///
+    /// // Bind a server socket to connect to.
+    /// let addr: SocketAddr = "127.0.0.1:0".parse()?;
+    /// let server = TcpListener::bind(&addr)?;
+    /// let addr = server.local_addr()?.clone();
+    ///
+    /// // Spawn a thread to accept the socket
+    /// thread::spawn(move || {
+    ///     let _ = server.accept();
+    /// });
+    ///
+    /// // Construct a new `Poll` handle as well as the `Events` we'll store into
+    /// let poll = Poll::new()?;
+    /// let mut events = Events::with_capacity(1024);
+    ///
+    /// // Connect the stream
+    /// let stream = TcpStream::connect(&addr)?;
+    ///
+    /// // Register the stream with `Poll`
+    /// poll.register(&stream, Token(0), Ready::readable() | Ready::writable(), PollOpt::level())?;
+    ///
+    /// // Wait for the socket to become ready. This has to happens in a loop to
+    /// // handle spurious wakeups.
+    /// loop {
+    ///     poll.poll(&mut events, None)?;
+    ///
+    ///     for event in &events {
+    ///         if event.token() == Token(0) && event.readiness().is_writable() {
+    ///             // The socket connected (probably, it could still be a spurious
+    ///             // wakeup)
+    ///             return Ok(());
+    ///         }
+    ///     }
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [struct]: #
+    pub fn poll(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<usize> {
+        events.inner.clear();
+        events.inner.set_limit(self.max_events);
+        self.poll1(&mut events.inner, timeout, false)
+    }
+
+    /// Like `poll`, but may be interrupted by a signal
+    ///
+    /// If `poll` is inturrupted while blocking, it will transparently retry the syscall.  If you
+    /// want to handle signals yourself, however, use `poll_interruptible`.
+    pub fn poll_interruptible(&self, events: &mut Events, timeout: Option<Duration>) -> io::Result<usize> {
+        events.inner.clear();
+        events.inner.set_limit(self.max_events);
+        self.poll1(&mut events.inner, timeout, true)
+    }
+
+    /// Wait for readiness events, handing each one to `f`.
+    ///
+    /// This behaves like [`poll`], but instead of filling an [`Events`]
+    /// buffer, `f` is called for every event as it is read from the system
+    /// selector or the readiness queue. Returning `false` from `f` stops
+    /// polling: no further events are collected by this call, and the ones
+    /// left behind are returned by the next call.
+    ///
+    /// Events of [`urgent`] registrations are handed out before the other
+    /// events collected while `Poll` was not blocked in the selector. Urgent
+    /// events that arrive while blocked follow the events of the system
+    /// selector, as they cannot be moved in front of events already handed
+    /// to `f`.
+    ///
+    /// Returns the number of times `f` was called.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Poll, PollOpt, Ready, Registration, Token};
+    /// use std::time::Duration;
+    ///
+    /// let poll = Poll::new()?;
+    /// let mut regs = vec![];
+    ///
+    /// for i in 0..4 {
+    ///     let (registration, set_readiness) = Registration::new2();
+    ///     poll.register(&registration, Token(i), Ready::readable(), PollOpt::edge())?;
+    ///     set_readiness.set_readiness(Ready::readable())?;
+    ///     regs.push((registration, set_readiness));
+    /// }
+    ///
+    /// // Only handle a single event per call
+    /// let n = poll.poll_with(Some(Duration::from_millis(0)), |event| {
+    ///     assert!(event.readiness().is_readable());
+    ///     false
+    /// })?;
+    /// assert_eq!(n, 1);
+    ///
+    /// // The remaining events are returned next
+    /// let n = poll.poll_with(Some(Duration::from_millis(0)), |_| true)?;
+    /// assert_eq!(n, 3);
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`poll`]: #method.poll
+    /// [`Events`]: struct.Events.html
+    /// [`urgent`]: struct.PollOpt.html#method.urgent
+    pub fn poll_with<F>(&self, timeout: Option<Duration>, f: F) -> io::Result<usize>
+        where F: FnMut(Event) -> bool
+    {
+        let mut visit = Visit {
+            f: f,
+            count: 0,
+            done: false,
+        };
+
+        self.poll1(&mut visit, timeout, false)
+    }
+
+    fn poll1<S: Sink>(&self, events: &mut S, mut timeout: Option<Duration>, interruptible: bool) -> io::Result<usize> {
+        let zero = Some(Duration::from_millis(0));
+
+        if self.readiness_queue.inner.is_closed() {
+            return Err(PollClosed.into());
+        }
+
+        if self.pollers > 1 {
+            return self.poll_concurrent(events, timeout, interruptible);
+        }
+
+        // At a high level, the synchronization strategy is to acquire access to
         // the critical section by transitioning the atomic from unlocked ->
         // locked. If the attempt fails, the thread will wait on the condition
         // variable.
@@ -1043,173 +2052,949 @@ impl Poll {
         // If there are any waiters, which is atomically determined while
         // unsetting the locked flag, then the condvar is notified.
 
-        let mut curr = self.lock_state.compare_and_swap(0, 1, SeqCst);
+        let mut curr = self.lock_state.compare_and_swap(0, 1, SeqCst);
+
+        if 0 != curr {
+            // Enter slower path
+            let mut lock = self.lock.lock().unwrap();
+            let mut inc = false;
+
+            loop {
+                if self.readiness_queue.inner.is_closed() {
+                    if inc {
+                        self.lock_state.fetch_sub(2, SeqCst);
+                    }
+
+                    return Err(PollClosed.into());
+                }
+
+                if curr & 1 == 0 {
+                    // The lock is currently free, attempt to grab it
+                    let mut next = curr | 1;
+
+                    if inc {
+                        // The waiter count has previously been incremented, so
+                        // decrement it here
+                        next -= 2;
+                    }
+
+                    let actual = self.lock_state.compare_and_swap(curr, next, SeqCst);
+
+                    if actual != curr {
+                        curr = actual;
+                        continue;
+                    }
+
+                    // Lock acquired, break from the loop
+                    break;
+                }
+
+                if timeout == zero {
+                    if inc {
+                        self.lock_state.fetch_sub(2, SeqCst);
+                    }
+
+                    return Ok(0);
+                }
+
+                // The lock is currently held, so wait for it to become
+                // free. If the waiter count hasn't been incremented yet, do
+                // so now
+                if !inc {
+                    let next = curr.checked_add(2).expect("overflow");
+                    let actual = self.lock_state.compare_and_swap(curr, next, SeqCst);
+
+                    if actual != curr {
+                        curr = actual;
+                        continue;
+                    }
+
+                    // Track that the waiter count has been incremented for
+                    // this thread and fall through to the condvar waiting
+                    inc = true;
+                }
+
+                lock = match timeout {
+                    Some(to) => {
+                        let now = Instant::now();
+
+                        // Wait to be notified
+                        let (l, _) = self.condvar.wait_timeout(lock, to).unwrap();
+
+                        // See how much time was elapsed in the wait
+                        let elapsed = now.elapsed();
+
+                        // Update `timeout` to reflect how much time is left to
+                        // wait.
+                        if elapsed >= to {
+                            timeout = zero;
+                        } else {
+                            // Update the timeout
+                            timeout = Some(to - elapsed);
+                        }
+
+                        l
+                    }
+                    None => {
+                        self.condvar.wait(lock).unwrap()
+                    }
+                };
+
+                // Reload the state
+                curr = self.lock_state.load(SeqCst);
+
+                // Try to lock again...
+            }
+        }
+
+        let ret = self.poll_sink(events, timeout, interruptible, None);
+
+        // Release the lock
+        if 1 != self.lock_state.fetch_and(!1, Release) {
+            // Acquire the mutex
+            let _lock = self.lock.lock().unwrap();
+
+            // There is at least one waiting thread, so notify one
+            self.condvar.notify_one();
+        }
+
+        ret
+    }
+
+    /// Polls the partition of the selector matching a free slot, see
+    /// `set_concurrent_pollers`.
+    fn poll_concurrent<S: Sink>(&self, events: &mut S, mut timeout: Option<Duration>, interruptible: bool) -> io::Result<usize> {
+        let slot = {
+            let mut slots = self.slots.lock().unwrap();
+
+            loop {
+                if self.readiness_queue.inner.is_closed() {
+                    return Err(PollClosed.into());
+                }
+
+                if let Some(slot) = slots.iter().position(|&taken| !taken) {
+                    slots[slot] = true;
+                    break slot;
+                }
+
+                // All partitions are being polled, wait for one to be freed
+                slots = match timeout {
+                    Some(to) => {
+                        if to == Duration::from_millis(0) {
+                            return Ok(0);
+                        }
+
+                        let now = Instant::now();
+                        let (l, _) = self.slot_freed.wait_timeout(slots, to).unwrap();
+                        let elapsed = now.elapsed();
+
+                        timeout = Some(if elapsed >= to {
+                            Duration::from_millis(0)
+                        } else {
+                            to - elapsed
+                        });
+
+                        l
+                    }
+                    None => self.slot_freed.wait(slots).unwrap(),
+                };
+            }
+        };
+
+        let ret = self.poll_sink(events, timeout, interruptible, Some(slot));
+
+        // Release the slot
+        let mut slots = self.slots.lock().unwrap();
+        slots[slot] = false;
+
+        // Hand the readiness queue over if no poller drains it. This is done
+        // under the lock, so the poller woken up either still holds its slot
+        // and takes over, or hands it over in turn when releasing the slot.
+        if !self.draining.load(SeqCst) {
+            if let Some(other) = slots.iter().position(|&taken| taken) {
+                let _ = self.registry.selector.wakeup_partition(other);
+            }
+        }
+
+        self.slot_freed.notify_one();
+
+        ret
+    }
+
+    fn poll_sink<S: Sink>(&self, events: &mut S, timeout: Option<Duration>, interruptible: bool, slot: Option<usize>) -> io::Result<usize> {
+        if self.drop_stale {
+            let mut events = DropStale {
+                inner: events,
+                generations: &self.registry.generations,
+                guard: None,
+            };
+
+            self.poll2(&mut events, timeout, interruptible, slot)
+        } else {
+            self.poll2(events, timeout, interruptible, slot)
+        }
+    }
+
+    #[inline]
+    fn poll2<S: Sink>(&self, events: &mut S, mut timeout: Option<Duration>, interruptible: bool, slot: Option<usize>) -> io::Result<usize> {
+        // Concurrent pollers take turns draining the readiness queue, which
+        // only supports a single consumer. The others only wait for their
+        // partition of the selector, until they are woken up to take over.
+        let _draining = match slot {
+            Some(slot) => loop {
+                if let Some(draining) = Draining::acquire(&self.draining) {
+                    break Some(draining);
+                }
+
+                let now = Instant::now();
+
+                self.select(events, timeout, interruptible, Some(slot), false)?;
+
+                if self.readiness_queue.inner.is_closed() {
+                    return Err(PollClosed.into());
+                }
+
+                if events.len() != 0 {
+                    return Ok(events.len());
+                }
+
+                if let Some(to) = timeout {
+                    let elapsed = now.elapsed();
+
+                    if elapsed >= to {
+                        return Ok(0);
+                    }
+
+                    timeout = Some(to - elapsed);
+                }
+            },
+            None => None,
+        };
+
+        // Urgent nodes are collected first so that they are not crowded out by
+        // other events.
+        let mut until = ptr::null_mut();
+        self.readiness_queue.poll_urgent(events, &mut until);
+
+        // Compute the timeout value passed to the system selector. If the
+        // readiness queue has pending nodes, we still want to poll the system
+        // selector for new events, but we don't want to block the thread to
+        // wait for new events.
+        if timeout == Some(Duration::from_millis(0)) {
+            // If blocking is not requested, then there is no need to prepare
+            // the queue for sleep
+            //
+            // The sleep_marker should be removed by readiness_queue.poll().
+        } else if events.len() != 0 {
+            // Urgent events are already available, so do not block the thread.
+            timeout = Some(Duration::from_millis(0));
+        } else if self.readiness_queue.prepare_for_sleep() {
+            // The readiness queue is empty. The call to `prepare_for_sleep`
+            // inserts `sleep_marker` into the queue. This signals to any
+            // threads setting readiness that the `Poll::poll` is going to
+            // sleep, so the awakener should be used.
+            //
+            // An urgent node may have been queued before the marker was
+            // inserted, in which case the producer did not wake us up.
+            if self.readiness_queue.has_urgent() {
+                timeout = Some(Duration::from_millis(0));
+            }
+        } else {
+            // The readiness queue is not empty, so do not block the thread.
+            timeout = Some(Duration::from_millis(0));
+        }
+
+        let selected = events.len();
+
+        // First get selector events
+        self.select(events, timeout, interruptible, slot, true)?;
+
+        if self.readiness_queue.inner.is_closed() {
+            // Woken up by `close`
+            return Err(PollClosed.into());
+        }
+
+        // Urgent nodes queued while blocked in the selector go in front of
+        // the selector events.
+        let urgent = events.len();
+        self.readiness_queue.poll_urgent(events, &mut until);
+        events.promote(selected, urgent);
+
+        // Poll custom event queue
+        self.readiness_queue.poll(events);
+
+        // Parent `Poll` instances only see nodes left in the queue through
+        // the awakener, which was cleared by the selector.
+        if self.readiness_queue.inner.is_nested() {
+            let pending = unsafe { !self.readiness_queue.inner.is_empty() };
+
+            if pending || self.readiness_queue.has_urgent() {
+                self.readiness_queue.inner.wakeup()?;
+            }
+        }
+
+        // Return number of polled events
+        Ok(events.len())
+    }
+
+    /// Waits for selector events, retrying when interrupted by a signal
+    /// unless `interruptible` is set.
+    fn select<S: Sink>(&self, events: &mut S, mut timeout: Option<Duration>, interruptible: bool, partition: Option<usize>, with_awakener: bool) -> io::Result<()> {
+        loop {
+            let now = Instant::now();
+            let selected = events.len();
+            let res = match partition {
+                Some(partition) => {
+                    self.registry.selector.select_partition(events, AWAKEN, timeout, partition, with_awakener)
+                }
+                None => self.registry.selector.select(events, AWAKEN, timeout),
+            };
+            match res {
+                Ok(true) => {
+                    // Some awakeners require reading from a FD.
+                    self.readiness_queue.inner.awakener.cleanup();
+                    return Ok(());
+                }
+                Ok(false) => {
+                    // Concurrent pollers are also woken up to pick up changes
+                    // to their partition, and go back to waiting afterwards,
+                    // unless no poller drains the readiness queue anymore.
+                    if partition.is_none() || events.len() != selected || self.readiness_queue.inner.is_closed() ||
+                        !self.draining.load(SeqCst)
+                    {
+                        return Ok(());
+                    }
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted && !interruptible => {
+                    // Interrupted by a signal; update timeout if necessary and retry
+                }
+                Err(e) => return Err(e),
+            }
+
+            if let Some(to) = timeout {
+                let elapsed = now.elapsed();
+                if elapsed >= to {
+                    return Ok(());
+                } else {
+                    timeout = Some(to - elapsed);
+                }
+            }
+        }
+    }
+}
+
+/// Exclusive access to the readiness queue for one of the threads polling
+/// concurrently, released on drop.
+struct Draining<'a> {
+    flag: &'a AtomicBool,
+}
+
+impl<'a> Draining<'a> {
+    fn acquire(flag: &'a AtomicBool) -> Option<Draining<'a>> {
+        if flag.swap(true, Acquire) {
+            None
+        } else {
+            Some(Draining { flag: flag })
+        }
+    }
+}
+
+impl<'a> Drop for Draining<'a> {
+    fn drop(&mut self) {
+        self.flag.store(false, Release);
+    }
+}
+
+/// Hands the events collected by `Poll::poll_with` to the closure.
+struct Visit<F> {
+    f: F,
+    count: usize,
+    done: bool,
+}
+
+impl<F: FnMut(Event) -> bool> Sink for Visit<F> {
+    fn len(&self) -> usize {
+        self.count
+    }
+
+    fn is_full(&self) -> bool {
+        self.done
+    }
+
+    fn push_event(&mut self, event: Event) {
+        self.count += 1;
+        self.done = !(self.f)(event);
+    }
+
+    fn promote(&mut self, _: usize, _: usize) {
+        // The events were already handed out
+    }
+
+    fn set_truncated(&mut self) {
+        // Only happens when the closure asked to stop
+    }
+
+    fn runs_user_code(&self) -> bool {
+        true
+    }
+}
+
+/// Drops events carrying a stale token generation, see `Poll::set_drop_stale`.
+///
+/// The generations are locked at the first event and stay locked until the
+/// call to `poll` returns, which no longer blocks by then. A sink running
+/// user code gets each event after the lock is released, as the code may
+/// register handles.
+struct DropStale<'a, S: 'a> {
+    inner: &'a mut S,
+    generations: &'a Mutex<Option<HashMap<usize, usize>>>,
+    guard: Option<MutexGuard<'a, Option<HashMap<usize, usize>>>>,
+}
+
+impl<'a, S: Sink> Sink for DropStale<'a, S> {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    fn is_full(&self) -> bool {
+        self.inner.is_full()
+    }
+
+    fn push_event(&mut self, event: Event) {
+        let token = event.token();
+
+        let generations = self.generations;
+        let guard = self.guard.get_or_insert_with(|| generations.lock().unwrap());
+
+        let stale = match **guard {
+            Some(ref generations) => match generations.get(&token.index()) {
+                Some(&generation) => generation != token.generation(),
+                None => false,
+            },
+            None => false,
+        };
+
+        if !stale {
+            if self.inner.runs_user_code() {
+                self.guard = None;
+            }
+
+            self.inner.push_event(event);
+        }
+    }
+
+    fn promote(&mut self, at: usize, from: usize) {
+        self.inner.promote(at, from)
+    }
+
+    fn set_truncated(&mut self) {
+        self.inner.set_truncated()
+    }
+}
+
+/// Error returned by a `Poll` instance that was closed.
+///
+/// Once [`Poll::close`] is called, operations on the `Poll` instance and its
+/// handles fail with an `io::Error` of kind `Other` wrapping a `PollClosed`.
+/// Use [`matches`] to tell it apart from other errors.
+///
+/// [`Poll::close`]: struct.Poll.html#method.close
+/// [`matches`]: #method.matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PollClosed;
+
+impl PollClosed {
+    /// Returns true if `err` reports a closed `Poll` instance.
+    pub fn matches(err: &io::Error) -> bool {
+        err.get_ref().map(|e| e.is::<PollClosed>()).unwrap_or(false)
+    }
+}
+
+impl fmt::Display for PollClosed {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("poll instance closed")
+    }
+}
+
+impl error::Error for PollClosed {}
+
+impl From<PollClosed> for io::Error {
+    fn from(err: PollClosed) -> io::Error {
+        io::Error::new(io::ErrorKind::Other, err)
+    }
+}
+
+/// Error returned when registering, reregistering or deregistering a handle
+/// fails.
+///
+/// The error is wrapped in an `io::Error`, use [`from_io_error`] to get it
+/// back. Errors caused by the system selector or by a closed `Poll` instance
+/// carry no `RegisterError`.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn try_main() -> Result<(), Box<dyn Error>> {
+/// use mio::{Poll, PollOpt, Ready, RegisterError, Token};
+/// use mio::net::TcpListener;
+///
+/// let poll = Poll::new()?;
+/// let listener = TcpListener::bind(&"127.0.0.1:0".parse()?)?;
+///
+/// poll.register(&listener, Token(0), Ready::readable(), PollOpt::level())?;
+///
+/// let err = poll.register(&listener, Token(0), Ready::readable(), PollOpt::level()).unwrap_err();
+/// assert_eq!(RegisterError::from_io_error(&err), Some(RegisterError::AlreadyRegistered));
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`from_io_error`]: #method.from_io_error
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterError {
+    /// The token is reserved for internal use by `Poll`.
+    ReservedToken(Token),
+    /// The handle is already registered with the `Poll` instance.
+    AlreadyRegistered,
+    /// The handle is associated with another `Poll` instance.
+    ForeignSelector,
+    /// The handle is not registered with the `Poll` instance.
+    NotRegistered,
+    /// The `Poll` instance would be nested in itself, directly or through
+    /// the instances nested in it.
+    NestingCycle,
+    /// The system selector does not support the requested interest or
+    /// options, see [`Poll::set_strict_registration`].
+    ///
+    /// [`Poll::set_strict_registration`]: struct.Poll.html#method.set_strict_registration
+    Unsupported {
+        /// Interest of the rejected registration
+        interest: Ready,
+        /// Options of the rejected registration
+        opts: PollOpt,
+    },
+}
+
+impl RegisterError {
+    /// Returns the `RegisterError` wrapped by `err`, if any.
+    pub fn from_io_error(err: &io::Error) -> Option<RegisterError> {
+        err.get_ref()
+            .and_then(|e| e.downcast_ref::<RegisterError>())
+            .cloned()
+    }
+}
+
+impl fmt::Display for RegisterError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RegisterError::ReservedToken(token) => write!(fmt, "reserved token ({:?})", token),
+            RegisterError::AlreadyRegistered => fmt.write_str("handle already registered"),
+            RegisterError::ForeignSelector => fmt.write_str("handle associated with another `Poll` instance"),
+            RegisterError::NotRegistered => fmt.write_str("handle not registered"),
+            RegisterError::NestingCycle => fmt.write_str("`Poll` instance nested in itself"),
+            RegisterError::Unsupported { interest, opts } => {
+                write!(fmt, "unsupported registration (interest: {:?}, opts: {:?})", interest, opts)
+            }
+        }
+    }
+}
+
+impl error::Error for RegisterError {}
+
+impl From<RegisterError> for io::Error {
+    fn from(err: RegisterError) -> io::Error {
+        // Keep the kinds of the errors the system selectors used to return
+        let kind = match err {
+            RegisterError::AlreadyRegistered => io::ErrorKind::AlreadyExists,
+            RegisterError::NotRegistered => io::ErrorKind::NotFound,
+            RegisterError::NestingCycle => io::ErrorKind::InvalidInput,
+            _ => io::ErrorKind::Other,
+        };
+
+        io::Error::new(kind, err)
+    }
+}
+
+/// Features supported by the system selector, as returned by
+/// [`Poll::capabilities`].
+///
+/// [`Poll::capabilities`]: struct.Poll.html#method.capabilities
+#[derive(Copy, PartialEq, Eq, Clone, PartialOrd, Ord)]
+pub struct Capabilities(usize);
+
+impl Capabilities {
+    /// Returns a `Capabilities` set with no features.
+    #[inline]
+    pub fn empty() -> Capabilities {
+        Capabilities(0)
+    }
+
+    /// Edge-triggered notifications are supported by the selector.
+    #[inline]
+    pub fn native_edge() -> Capabilities {
+        Capabilities(0b00_0001)
+    }
+
+    /// Oneshot notifications are supported by the selector.
+    #[inline]
+    pub fn native_oneshot() -> Capabilities {
+        Capabilities(0b00_0010)
+    }
+
+    /// Hang up readiness is reported separately from readable readiness.
+    #[inline]
+    pub fn hup() -> Capabilities {
+        Capabilities(0b00_0100)
+    }
+
+    /// Priority readiness can be registered.
+    #[inline]
+    pub fn priority() -> Capabilities {
+        Capabilities(0b00_1000)
+    }
+
+    /// Timeouts shorter than a millisecond are honored instead of being
+    /// rounded up.
+    #[inline]
+    pub fn sub_millisecond_timeout() -> Capabilities {
+        Capabilities(0b01_0000)
+    }
+
+    /// Several threads can wait in the selector at once, see
+    /// [`Poll::set_concurrent_pollers`].
+    ///
+    /// [`Poll::set_concurrent_pollers`]: struct.Poll.html#method.set_concurrent_pollers
+    #[inline]
+    pub fn concurrent_pollers() -> Capabilities {
+        Capabilities(0b10_0000)
+    }
+
+    /// Read closed readiness is reported as soon as the peer shuts down the
+    /// writing half of a connection, not only once it fully hangs up. See
+    /// [`UnixReady::read_closed`].
+    ///
+    /// [`UnixReady::read_closed`]: unix/struct.UnixReady.html#method.read_closed
+    #[inline]
+    pub fn read_closed() -> Capabilities {
+        Capabilities(0b100_0000)
+    }
+
+    /// Returns true if edge-triggered notifications are supported natively.
+    #[inline]
+    pub fn is_native_edge(&self) -> bool {
+        self.contains(Capabilities::native_edge())
+    }
+
+    /// Returns true if oneshot notifications are supported natively.
+    #[inline]
+    pub fn is_native_oneshot(&self) -> bool {
+        self.contains(Capabilities::native_oneshot())
+    }
+
+    /// Returns true if hang up readiness is reported.
+    #[inline]
+    pub fn is_hup(&self) -> bool {
+        self.contains(Capabilities::hup())
+    }
+
+    /// Returns true if priority readiness is supported.
+    #[inline]
+    pub fn is_priority(&self) -> bool {
+        self.contains(Capabilities::priority())
+    }
+
+    /// Returns true if sub-millisecond timeouts are honored.
+    #[inline]
+    pub fn is_sub_millisecond_timeout(&self) -> bool {
+        self.contains(Capabilities::sub_millisecond_timeout())
+    }
+
+    /// Returns true if concurrent pollers are supported.
+    #[inline]
+    pub fn is_concurrent_pollers(&self) -> bool {
+        self.contains(Capabilities::concurrent_pollers())
+    }
+
+    /// Returns true if half-closed connections are reported.
+    #[inline]
+    pub fn is_read_closed(&self) -> bool {
+        self.contains(Capabilities::read_closed())
+    }
+
+    /// Returns true if `self` is a superset of `other`.
+    #[inline]
+    pub fn contains(&self, other: Capabilities) -> bool {
+        (*self & other) == other
+    }
+}
+
+impl ops::BitOr for Capabilities {
+    type Output = Capabilities;
+
+    #[inline]
+    fn bitor(self, other: Capabilities) -> Capabilities {
+        Capabilities(self.0 | other.0)
+    }
+}
+
+impl ops::BitAnd for Capabilities {
+    type Output = Capabilities;
+
+    #[inline]
+    fn bitand(self, other: Capabilities) -> Capabilities {
+        Capabilities(self.0 & other.0)
+    }
+}
+
+impl fmt::Debug for Capabilities {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut one = false;
+        let flags = [
+            (Capabilities::native_edge(), "NativeEdge"),
+            (Capabilities::native_oneshot(), "NativeOneShot"),
+            (Capabilities::hup(), "Hup"),
+            (Capabilities::priority(), "Priority"),
+            (Capabilities::sub_millisecond_timeout(), "SubMillisecondTimeout"),
+            (Capabilities::concurrent_pollers(), "ConcurrentPollers"),
+            (Capabilities::read_closed(), "ReadClosed")];
+
+        for &(flag, msg) in &flags {
+            if self.contains(flag) {
+                if one { write!(fmt, " | ")? }
+                write!(fmt, "{}", msg)?;
+
+                one = true
+            }
+        }
+
+        if !one {
+            fmt.write_str("(empty)")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_args(token: Token) -> io::Result<()> {
+    if token == AWAKEN {
+        return Err(RegisterError::ReservedToken(token).into());
+    }
+
+    Ok(())
+}
+
+impl fmt::Debug for Poll {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Poll")
+            .finish()
+    }
+}
+
+impl Registry {
+    /// Register an `Evented` handle with the `Poll` instance this `Registry`
+    /// belongs to.
+    ///
+    /// See [`Poll::register`] for details.
+    ///
+    /// [`Poll::register`]: struct.Poll.html#method.register
+    pub fn register<E: ?Sized>(&self, handle: &E, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>
+        where E: Evented
+    {
+        validate_args(token)?;
+        self.ensure_open()?;
+
+        /*
+         * Undefined behavior:
+         * - Reusing a token with a different `Evented` without deregistering
+         * (or closing) the original `Evented`.
+         */
+        trace!("registering with poller");
+
+        // Register interests for this socket
+        handle.register(self, token, interest, opts)?;
 
-        if 0 != curr {
-            // Enter slower path
-            let mut lock = self.lock.lock().unwrap();
-            let mut inc = false;
+        self.track_generation(token);
+        self.notify_selecting()
+    }
 
-            loop {
-                if curr & 1 == 0 {
-                    // The lock is currently free, attempt to grab it
-                    let mut next = curr | 1;
+    /// Register an `Evented` handle with separate tokens for its readable and
+    /// writable readiness.
+    ///
+    /// See [`Poll::register_split`] for details.
+    ///
+    /// [`Poll::register_split`]: struct.Poll.html#method.register_split
+    pub fn register_split<E: ?Sized>(&self, handle: &E, read_token: Token, write_token: Token, opts: PollOpt) -> io::Result<()>
+        where E: Evented
+    {
+        validate_args(read_token)?;
+        validate_args(write_token)?;
+        self.ensure_open()?;
 
-                    if inc {
-                        // The waiter count has previously been incremented, so
-                        // decrement it here
-                        next -= 2;
-                    }
+        trace!("registering split handle with poller");
 
-                    let actual = self.lock_state.compare_and_swap(curr, next, SeqCst);
+        handle.register_split(self, read_token, write_token, opts)?;
 
-                    if actual != curr {
-                        curr = actual;
-                        continue;
-                    }
+        self.track_generation(read_token);
+        self.track_generation(write_token);
+        self.notify_selecting()
+    }
 
-                    // Lock acquired, break from the loop
-                    break;
-                }
+    /// Re-register an `Evented` handle with separate tokens for its readable
+    /// and writable readiness.
+    ///
+    /// See [`Poll::reregister_split`] for details.
+    ///
+    /// [`Poll::reregister_split`]: struct.Poll.html#method.reregister_split
+    pub fn reregister_split<E: ?Sized>(&self, handle: &E, read_token: Token, write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>
+        where E: Evented
+    {
+        validate_args(read_token)?;
+        validate_args(write_token)?;
+        self.ensure_open()?;
 
-                if timeout == zero {
-                    if inc {
-                        self.lock_state.fetch_sub(2, SeqCst);
-                    }
+        trace!("registering split handle with poller");
 
-                    return Ok(0);
-                }
+        handle.reregister_split(self, read_token, write_token, interest, opts)?;
 
-                // The lock is currently held, so wait for it to become
-                // free. If the waiter count hasn't been incremented yet, do
-                // so now
-                if !inc {
-                    let next = curr.checked_add(2).expect("overflow");
-                    let actual = self.lock_state.compare_and_swap(curr, next, SeqCst);
+        self.track_generation(read_token);
+        self.track_generation(write_token);
+        self.notify_selecting()
+    }
 
-                    if actual != curr {
-                        curr = actual;
-                        continue;
-                    }
+    /// Re-register an `Evented` handle with the `Poll` instance this
+    /// `Registry` belongs to.
+    ///
+    /// See [`Poll::reregister`] for details.
+    ///
+    /// [`Poll::reregister`]: struct.Poll.html#method.reregister
+    pub fn reregister<E: ?Sized>(&self, handle: &E, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>
+        where E: Evented
+    {
+        validate_args(token)?;
+        self.ensure_open()?;
 
-                    // Track that the waiter count has been incremented for
-                    // this thread and fall through to the condvar waiting
-                    inc = true;
-                }
+        trace!("registering with poller");
 
-                lock = match timeout {
-                    Some(to) => {
-                        let now = Instant::now();
+        // Register interests for this socket
+        handle.reregister(self, token, interest, opts)?;
 
-                        // Wait to be notified
-                        let (l, _) = self.condvar.wait_timeout(lock, to).unwrap();
+        self.track_generation(token);
+        self.notify_selecting()
+    }
 
-                        // See how much time was elapsed in the wait
-                        let elapsed = now.elapsed();
+    /// Deregister an `Evented` handle from the `Poll` instance this
+    /// `Registry` belongs to.
+    ///
+    /// See [`Poll::deregister`] for details.
+    ///
+    /// [`Poll::deregister`]: struct.Poll.html#method.deregister
+    pub fn deregister<E: ?Sized>(&self, handle: &E) -> io::Result<()>
+        where E: Evented
+    {
+        trace!("deregistering handle with poller");
 
-                        // Update `timeout` to reflect how much time is left to
-                        // wait.
-                        if elapsed >= to {
-                            timeout = zero;
-                        } else {
-                            // Update the timeout
-                            timeout = Some(to - elapsed);
-                        }
+        // Deregister interests for this socket
+        handle.deregister(self)?;
 
-                        l
-                    }
-                    None => {
-                        self.condvar.wait(lock).unwrap()
-                    }
-                };
+        self.notify_selecting()
+    }
 
-                // Reload the state
-                curr = self.lock_state.load(SeqCst);
+    /// Move an `Evented` handle from the `Poll` instance this `Registry`
+    /// belongs to, to the one `to` belongs to.
+    ///
+    /// See [`Poll::transfer`] for details.
+    ///
+    /// [`Poll::transfer`]: struct.Poll.html#method.transfer
+    pub fn transfer<E: ?Sized>(&self, handle: &E, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()>
+        where E: Evented
+    {
+        validate_args(token)?;
+        to.ensure_open()?;
 
-                // Try to lock again...
-            }
-        }
+        trace!("transferring handle to another poller");
 
-        let ret = self.poll2(events, timeout, interruptible);
+        handle.transfer(self, to, token, interest, opts)?;
 
-        // Release the lock
-        if 1 != self.lock_state.fetch_and(!1, Release) {
-            // Acquire the mutex
-            let _lock = self.lock.lock().unwrap();
+        to.track_generation(token);
+        self.notify_selecting()?;
+        to.notify_selecting()
+    }
 
-            // There is at least one waiting thread, so notify one
-            self.condvar.notify_one();
+    fn ensure_open(&self) -> io::Result<()> {
+        if self.readiness_queue.is_closed() {
+            return Err(PollClosed.into());
         }
 
-        ret
+        Ok(())
     }
 
-    #[inline]
-    fn poll2(&self, events: &mut Events, mut timeout: Option<Duration>, interruptible: bool) -> io::Result<usize> {
-        // Compute the timeout value passed to the system selector. If the
-        // readiness queue has pending nodes, we still want to poll the system
-        // selector for new events, but we don't want to block the thread to
-        // wait for new events.
-        if timeout == Some(Duration::from_millis(0)) {
-            // If blocking is not requested, then there is no need to prepare
-            // the queue for sleep
-            //
-            // The sleep_marker should be removed by readiness_queue.poll().
-        } else if self.readiness_queue.prepare_for_sleep() {
-            // The readiness queue is empty. The call to `prepare_for_sleep`
-            // inserts `sleep_marker` into the queue. This signals to any
-            // threads setting readiness that the `Poll::poll` is going to
-            // sleep, so the awakener should be used.
-        } else {
-            // The readiness queue is not empty, so do not block the thread.
-            timeout = Some(Duration::from_millis(0));
+    /// Wakes up threads blocked in the selector, which only pick up changes
+    /// to the registrations on their next call.
+    fn notify_selecting(&self) -> io::Result<()> {
+        if self.selector.is_selecting() {
+            self.readiness_queue.wakeup()?;
         }
 
-        loop {
-            let now = Instant::now();
-            // First get selector events
-            let res = self.selector.select(&mut events.inner, AWAKEN, timeout);
-            match res {
-                Ok(true) => {
-                    // Some awakeners require reading from a FD.
-                    self.readiness_queue.inner.awakener.cleanup();
-                    break;
-                }
-                Ok(false) => break,
-                Err(ref e) if e.kind() == io::ErrorKind::Interrupted && !interruptible => {
-                    // Interrupted by a signal; update timeout if necessary and retry
-                    if let Some(to) = timeout {
-                        let elapsed = now.elapsed();
-                        if elapsed >= to {
-                            break;
-                        } else {
-                            timeout = Some(to - elapsed);
-                        }
-                    }
-                }
-                Err(e) => return Err(e),
-            }
-        }
+        Ok(())
+    }
 
-        // Poll custom event queue
-        self.readiness_queue.poll(&mut events.inner);
+    /// Remembers the generation of `token` when stale events are dropped.
+    fn track_generation(&self, token: Token) {
+        if let Some(ref mut generations) = *self.generations.lock().unwrap() {
+            generations.insert(token.index(), token.generation());
+        }
+    }
 
-        // Return number of polled events
-        Ok(events.inner.len())
+    /// Forgets the generation of `token` once its handle is deregistered,
+    /// unless the index was registered again since.
+    fn forget_generation(&self, token: Token) {
+        if let Some(ref mut generations) = *self.generations.lock().unwrap() {
+            if generations.get(&token.index()) == Some(&token.generation()) {
+                generations.remove(&token.index());
+            }
+        }
     }
 }
 
-fn validate_args(token: Token) -> io::Result<()> {
-    if token == AWAKEN {
-        return Err(io::Error::new(io::ErrorKind::Other, "invalid token"));
+/// A `Poll` instance can be registered with another one. It is then reported
+/// readable while events are pending for it, either from the system selector
+/// or from its readiness queue, until it is polled.
+///
+/// The nested instance is level-triggered. Its options are checked like the
+/// ones of any handle backed by the system selector, see
+/// [`Poll::set_strict_registration`].
+///
+/// [`Poll::set_strict_registration`]: struct.Poll.html#method.set_strict_registration
+impl Evented for Poll {
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        registry.selector.register_selector(&self.registry.selector, token, interest, opts)?;
+        self.readiness_queue.inner.nested.fetch_add(1, SeqCst);
+
+        // Nodes may already be queued
+        self.readiness_queue.inner.wakeup()
     }
 
-    Ok(())
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        registry.selector.reregister_selector(&self.registry.selector, token, interest, opts)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        let token = registry.selector.deregister_selector(&self.registry.selector)?;
+        self.readiness_queue.inner.nested.fetch_sub(1, SeqCst);
+        registry.forget_generation(token);
+
+        Ok(())
+    }
 }
 
-impl fmt::Debug for Poll {
+impl fmt::Debug for Registry {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt.debug_struct("Poll")
+        fmt.debug_struct("Registry")
             .finish()
     }
 }
@@ -1389,6 +3174,19 @@ impl Events {
         self.inner.is_empty()
     }
 
+    /// Returns `true` if the last call to [`Poll::poll`] filled `self` while
+    /// more events were ready.
+    ///
+    /// The events that did not fit are returned by the next call to
+    /// [`Poll::poll`], which can be made right away with a zero timeout. See
+    /// [`Poll::set_max_events`] to grow `Events` instead.
+    ///
+    /// [`Poll::poll`]: struct.Poll.html#method.poll
+    /// [`Poll::set_max_events`]: struct.Poll.html#method.set_max_events
+    pub fn is_truncated(&self) -> bool {
+        self.inner.is_truncated()
+    }
+
     /// Returns an iterator over the `Event` values.
     ///
     /// # Examples
@@ -1508,10 +3306,15 @@ impl fmt::Debug for Events {
 
 // ===== Accessors for internal usage =====
 
-pub fn selector(poll: &Poll) -> &sys::Selector {
-    &poll.selector
+pub fn selector(registry: &Registry) -> &sys::Selector {
+    &registry.selector
+}
+
+pub fn forget_generation(registry: &Registry, token: Token) {
+    registry.forget_generation(token)
 }
 
+
 /*
  *
  * ===== Registration =====
@@ -1520,10 +3323,10 @@ pub fn selector(poll: &Poll) -> &sys::Selector {
 
 // TODO: get rid of this, windows depends on it for now
 #[allow(dead_code)]
-pub fn new_registration(poll: &Poll, token: Token, ready: Ready, opt: PollOpt)
+pub fn new_registration(registry: &Registry, token: Token, ready: Ready, opt: PollOpt)
         -> (Registration, SetReadiness)
 {
-    Registration::new_priv(poll, token, ready, opt)
+    Registration::new_priv(registry, token, ready, opt)
 }
 
 impl Registration {
@@ -1594,17 +3397,61 @@ impl Registration {
         (registration, set_readiness)
     }
 
+    /// Create a new `Registration` and the associated `SetReadiness`, tied to
+    /// the `Poll` instance of `registry`.
+    ///
+    /// Unlike [`new2`], the node backing the pair is taken from the pool of
+    /// nodes released by previous registrations when possible, which avoids
+    /// an allocation. The `Registration` still has to be registered, and can
+    /// only be registered with the same `Poll` instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Poll, PollOpt, Ready, Registration, Token};
+    /// use std::time::Duration;
+    ///
+    /// let poll = Poll::new()?;
+    /// let mut events = Events::with_capacity(16);
+    ///
+    /// for i in 0..4 {
+    ///     let (registration, set_readiness) = Registration::with_registry(poll.registry());
+    ///     poll.register(&registration, Token(i), Ready::readable(), PollOpt::edge())?;
+    ///
+    ///     set_readiness.set_readiness(Ready::readable())?;
+    ///     poll.poll(&mut events, Some(Duration::from_millis(0)))?;
+    ///
+    ///     assert_eq!(events.iter().next().unwrap().token(), Token(i));
+    ///
+    ///     // The node is returned to the pool once `poll` sees the
+    ///     // registration is gone.
+    /// }
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`new2`]: #method.new2
+    pub fn with_registry(registry: &Registry) -> (Registration, SetReadiness) {
+        Registration::new_priv(registry, Token(0), Ready::empty(), PollOpt::empty())
+    }
+
     #[deprecated(since = "0.6.5", note = "use `new2` instead")]
     #[cfg(feature = "with-deprecated")]
     #[doc(hidden)]
     pub fn new(poll: &Poll, token: Token, interest: Ready, opt: PollOpt)
         -> (Registration, SetReadiness)
     {
-        Registration::new_priv(poll, token, interest, opt)
+        Registration::new_priv(&poll.registry, token, interest, opt)
     }
 
     // TODO: Get rid of this (windows depends on it for now)
-    fn new_priv(poll: &Poll, token: Token, interest: Ready, opt: PollOpt)
+    fn new_priv(registry: &Registry, token: Token, interest: Ready, opt: PollOpt)
         -> (Registration, SetReadiness)
     {
         is_send::<Registration>();
@@ -1612,16 +3459,9 @@ impl Registration {
         is_send::<SetReadiness>();
         is_sync::<SetReadiness>();
 
-        // Clone handle to the readiness queue, this bumps the ref count
-        let queue = poll.readiness_queue.inner.clone();
-
-        // Convert to a *mut () pointer
-        let queue: *mut () = unsafe { mem::transmute(queue) };
-
         // Allocate the registration node. The new node will have `ref_count`
         // set to 3: one SetReadiness, one Registration, and one Poll handle.
-        let node = Box::into_raw(Box::new(ReadinessNode::new(
-                    queue, token, interest, opt, 3)));
+        let node = alloc_node(&registry.readiness_queue, token, interest, opt, 3);
 
         let registration = Registration {
             inner: RegistrationInner {
@@ -1642,28 +3482,36 @@ impl Registration {
     #[cfg(feature = "with-deprecated")]
     #[doc(hidden)]
     pub fn update(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.inner.update(poll, token, interest, opts)
+        self.inner.update(&poll.registry, token, interest, opts)
     }
 
     #[deprecated(since = "0.6.5", note = "use `Evented` impl")]
     #[cfg(feature = "with-deprecated")]
     #[doc(hidden)]
     pub fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.inner.update(poll, Token(0), Ready::empty(), PollOpt::empty())
+        self.inner.update(&poll.registry, Token(0), Ready::empty(), PollOpt::empty())
     }
 }
 
 impl Evented for Registration {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.inner.update(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.inner.update(registry, token, interest, opts)
+    }
+
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.inner.update(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.inner.update(poll, token, interest, opts)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        if let Some(token) = self.inner.replace(registry, Token(0), Ready::empty(), PollOpt::empty())? {
+            registry.forget_generation(token);
+        }
+
+        Ok(())
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.inner.update(poll, Token(0), Ready::empty(), PollOpt::empty())
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.inner.transfer(from, to, token, interest, opts)
     }
 }
 
@@ -1775,17 +3623,71 @@ impl SetReadiness {
     /// A simple example, for a more elaborate example, see the [`Evented`]
     /// documentation.
     ///
-    /// ```
-    /// # use std::error::Error;
-    /// # fn try_main() -> Result<(), Box<Error>> {
-    /// use mio::{Registration, Ready};
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<Error>> {
+    /// use mio::{Registration, Ready};
+    ///
+    /// let (registration, set_readiness) = Registration::new2();
+    ///
+    /// assert!(set_readiness.readiness().is_empty());
+    ///
+    /// set_readiness.set_readiness(Ready::readable())?;
+    /// assert!(set_readiness.readiness().is_readable());
+    /// #     Ok(())
+    /// # }
+    /// #
+    /// # fn main() {
+    /// #     try_main().unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [`Registration`]: struct.Registration.html
+    /// [`Evented`]: event/trait.Evented.html#examples
+    /// [`Poll`]: struct.Poll.html
+    /// [`Poll::poll`]: struct.Poll.html#method.poll
+    pub fn set_readiness(&self, ready: Ready) -> io::Result<()> {
+        self.inner.set_readiness(ready)
+    }
+
+    /// Set the readiness of many `Registration`s at once.
+    ///
+    /// This is equivalent to calling [`set_readiness`] for each pair in
+    /// `batch`, except that every `Poll` instance the handles are registered
+    /// with is woken up at most once, after all the nodes have been queued.
+    /// Producers marking many registrations ready at a time save a wakeup per
+    /// handle.
+    ///
+    /// The readiness of every handle is set even if some of them fail. The
+    /// first error is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::error::Error;
+    /// # fn try_main() -> Result<(), Box<dyn Error>> {
+    /// use mio::{Events, Poll, PollOpt, Ready, Registration, SetReadiness, Token};
+    /// use std::time::Duration;
+    ///
+    /// let poll = Poll::new()?;
+    /// let mut regs = vec![];
+    ///
+    /// for i in 0..8 {
+    ///     let (registration, set_readiness) = Registration::new2();
+    ///     poll.register(&registration, Token(i), Ready::readable(), PollOpt::edge())?;
+    ///     regs.push((registration, set_readiness));
+    /// }
+    ///
+    /// let batch: Vec<_> = regs.iter()
+    ///     .map(|&(_, ref set_readiness)| (set_readiness, Ready::readable()))
+    ///     .collect();
     ///
-    /// let (registration, set_readiness) = Registration::new2();
+    /// SetReadiness::set_readiness_batch(&batch)?;
     ///
-    /// assert!(set_readiness.readiness().is_empty());
+    /// let mut events = Events::with_capacity(16);
+    /// poll.poll(&mut events, Some(Duration::from_millis(0)))?;
     ///
-    /// set_readiness.set_readiness(Ready::readable())?;
-    /// assert!(set_readiness.readiness().is_readable());
+    /// assert_eq!(events.len(), 8);
     /// #     Ok(())
     /// # }
     /// #
@@ -1794,12 +3696,51 @@ impl SetReadiness {
     /// # }
     /// ```
     ///
-    /// [`Registration`]: struct.Registration.html
-    /// [`Evented`]: event/trait.Evented.html#examples
-    /// [`Poll`]: struct.Poll.html
-    /// [`Poll::poll`]: struct.Poll.html#method.poll
-    pub fn set_readiness(&self, ready: Ready) -> io::Result<()> {
-        self.inner.set_readiness(ready)
+    /// [`set_readiness`]: #method.set_readiness
+    pub fn set_readiness_batch(batch: &[(&SetReadiness, Ready)]) -> io::Result<()> {
+        let mut wakeups: Vec<*mut ()> = vec![];
+        let mut ret = Ok(());
+
+        for &(set_readiness, ready) in batch {
+            let node = &set_readiness.inner;
+
+            match node.mark_ready(ready) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    if ret.is_ok() {
+                        ret = Err(e);
+                    }
+                    continue;
+                }
+            }
+
+            let queue = node.readiness_queue.load(Acquire);
+
+            if queue.is_null() {
+                // Not associated with a queue, nothing to do
+                continue;
+            }
+
+            // This is ugly... but we don't want to bump the ref count.
+            let inner: &Arc<ReadinessQueueInner> = unsafe { mem::transmute(&queue) };
+
+            if inner.enqueue_node_batched(node) && !wakeups.contains(&queue) {
+                wakeups.push(queue);
+            }
+        }
+
+        for queue in wakeups {
+            let inner: &Arc<ReadinessQueueInner> = unsafe { mem::transmute(&queue) };
+
+            if let Err(e) = inner.wakeup() {
+                if ret.is_ok() {
+                    ret = Err(e);
+                }
+            }
+        }
+
+        ret
     }
 }
 
@@ -1810,6 +3751,59 @@ impl fmt::Debug for SetReadiness {
     }
 }
 
+impl Waker {
+    /// Create a new `Waker` delivering events for `token` to `poll`.
+    pub fn new(poll: &Poll, token: Token) -> io::Result<Waker> {
+        is_send::<Waker>();
+        is_sync::<Waker>();
+
+        validate_args(token)?;
+        poll.registry.ensure_open()?;
+
+        // The node is associated with the queue right away. It has `ref_count`
+        // set to 2: one for the `Waker` and one for the Poll handle.
+        //
+        // Readiness is never cleared, and edge triggering ensures the node is
+        // only queued again once `Poll::poll` has dequeued it.
+        let node = alloc_node(&poll.readiness_queue.inner, token, Ready::readable(), PollOpt::edge(), 2);
+
+        poll.registry.track_generation(token);
+
+        Ok(Waker {
+            inner: RegistrationInner {
+                node: node,
+            },
+        })
+    }
+
+    /// Wake up the `Poll` instance associated with this `Waker`.
+    ///
+    /// This only pushes the waker into the readiness queue if it isn't queued
+    /// already, and only signals the system selector if `Poll::poll` is
+    /// blocked. Calling `wake` repeatedly is therefore cheap.
+    pub fn wake(&self) -> io::Result<()> {
+        self.inner.set_readiness(Ready::readable())
+    }
+}
+
+impl Drop for Waker {
+    fn drop(&mut self) {
+        // Same as dropping a `Registration`: notify `Poll::poll` to release
+        // its handle.
+        if self.inner.state.flag_as_dropped() {
+            // Can't do anything if the queuing fails
+            let _ = self.inner.enqueue_with_wakeup();
+        }
+    }
+}
+
+impl fmt::Debug for Waker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Waker")
+            .finish()
+    }
+}
+
 impl RegistrationInner {
     /// Get the registration's readiness.
     fn readiness(&self) -> Ready {
@@ -1821,6 +3815,22 @@ impl RegistrationInner {
     /// This function can be called concurrently by an arbitrary number of
     /// SetReadiness handles.
     fn set_readiness(&self, ready: Ready) -> io::Result<()> {
+        if self.mark_ready(ready)? {
+            // We toggled the queued flag, making us responsible for queuing the
+            // node in the MPSC readiness queue.
+            self.enqueue_with_wakeup()?;
+        }
+
+        Ok(())
+    }
+
+    /// Update the readiness without queuing the node. Returns true if the
+    /// caller is responsible for pushing the node into the readiness queue.
+    fn mark_ready(&self, ready: Ready) -> io::Result<bool> {
+        if is_closed(self.readiness_queue.load(Acquire)) {
+            return Err(PollClosed.into());
+        }
+
         // Load the current atomic state.
         let mut state = self.state.load(Acquire);
         let mut next;
@@ -1830,7 +3840,7 @@ impl RegistrationInner {
 
             if state.is_dropped() {
                 // Node is dropped, no more notifications
-                return Ok(());
+                return Ok(false);
             }
 
             // Update the readiness
@@ -1851,24 +3861,91 @@ impl RegistrationInner {
             state = actual;
         }
 
-        if !state.is_queued() && next.is_queued() {
-            // We toggled the queued flag, making us responsible for queuing the
-            // node in the MPSC readiness queue.
-            self.enqueue_with_wakeup()?;
+        Ok(!state.is_queued() && next.is_queued())
+    }
+
+    /// Move the node to the readiness queue of `to`. The move is completed by
+    /// `finish_move`, right away if the node is not queued, otherwise by the
+    /// `Poll` instance it is queued with once it dequeues the node.
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opt: PollOpt) -> io::Result<()> {
+        let queue = self.readiness_queue.load(Acquire);
+        let from_queue: &*mut () = unsafe { mem::transmute(&from.readiness_queue) };
+        let to_queue: &*mut () = unsafe { mem::transmute(&to.readiness_queue) };
+
+        if queue.is_null() || queue == *to_queue {
+            // Nothing to move
+            return self.update(to, token, interest, opt);
         }
 
-        Ok(())
+        if queue != *from_queue {
+            return Err(RegisterError::ForeignSelector.into());
+        }
+
+        // Hand a handle to the new queue over to the node, replacing the one
+        // of a previous move that is still pending.
+        let next: *mut () = unsafe { mem::transmute(to.readiness_queue.clone()) };
+        let prev = self.moved_to.swap(next, AcqRel);
+
+        if !prev.is_null() {
+            let _: Arc<ReadinessQueueInner> = unsafe { mem::transmute(prev) };
+        }
+
+        // If the node is not queued, claiming the queued flag keeps it out of
+        // the queue while it is moved.
+        let mut state = self.state.load(Acquire);
+
+        while !state.is_queued() {
+            let mut next = state;
+            next.set_queued();
+
+            let actual = self.state.compare_and_swap(state, next, AcqRel);
+
+            if actual == state {
+                finish_move(self.node);
+                break;
+            }
+
+            state = actual;
+        }
+
+        // Apply the registration details with whichever queue the node is
+        // associated with by now.
+        loop {
+            let queue = self.readiness_queue.load(Acquire);
+            let registry = if queue == *to_queue { to } else { from };
+
+            match self.replace(registry, token, interest, opt) {
+                Err(_) if self.readiness_queue.load(Acquire) != queue => continue,
+                Ok(replaced) => {
+                    // The node is no longer registered with `from`, like
+                    // after `deregister`
+                    if let Some(replaced) = replaced {
+                        from.forget_generation(replaced);
+                    }
+
+                    return Ok(());
+                }
+                Err(e) => return Err(e),
+            }
+        }
     }
 
     /// Update the registration details associated with the node
-    fn update(&self, poll: &Poll, token: Token, interest: Ready, opt: PollOpt) -> io::Result<()> {
+    fn update(&self, registry: &Registry, token: Token, interest: Ready, opt: PollOpt) -> io::Result<()> {
+        self.replace(registry, token, interest, opt).map(|_| ())
+    }
+
+    /// Like `update`, but returns the token the node was registered with, or
+    /// `None` if the update was discarded.
+    fn replace(&self, registry: &Registry, token: Token, interest: Ready, opt: PollOpt) -> io::Result<Option<Token>> {
         // First, ensure poll instances match
         //
         // Load the queue pointer, `Relaxed` is sufficient here as only the
         // pointer is being operated on. The actual memory is guaranteed to be
-        // visible the `poll: &Poll` ref passed as an argument to the function.
+        // visible the `registry: &Registry` ref passed as an argument to the
+        // function.
         let mut queue = self.readiness_queue.load(Relaxed);
-        let other: &*mut () = unsafe { mem::transmute(&poll.readiness_queue.inner) };
+        let other: &*mut () = unsafe { mem::transmute(&registry.readiness_queue) };
         let other = *other;
 
         debug_assert!(mem::size_of::<Arc<ReadinessQueueInner>>() == mem::size_of::<*mut ()>());
@@ -1896,22 +3973,22 @@ impl RegistrationInner {
                 // Down below in `release_node` when we deallocate this
                 // `RegistrationInner` is where we'll transmute this back to an
                 // arc and decrement the reference count.
-                mem::forget(poll.readiness_queue.clone());
+                mem::forget(registry.readiness_queue.clone());
             } else {
                 // The CAS failed, another thread set the queue pointer, so ensure
                 // that the pointer and `other` match
                 if actual != other {
-                    return Err(io::Error::new(io::ErrorKind::Other, "registration handle associated with another `Poll` instance"));
+                    return Err(RegisterError::ForeignSelector.into());
                 }
             }
 
             queue = other;
         } else if queue != other {
-            return Err(io::Error::new(io::ErrorKind::Other, "registration handle associated with another `Poll` instance"));
+            return Err(RegisterError::ForeignSelector.into());
         }
 
         unsafe {
-            let actual = &poll.readiness_queue.inner as *const _ as *const usize;
+            let actual = &registry.readiness_queue as *const _ as *const usize;
             debug_assert_eq!(queue as usize, *actual);
         }
 
@@ -1927,7 +4004,7 @@ impl RegistrationInner {
         // Acquire the update lock.
         if self.update_lock.compare_and_swap(false, true, Acquire) {
             // The lock is already held. Discard the update
-            return Ok(());
+            return Ok(None);
         }
 
         // Relaxed ordering is acceptable here as the only memory that needs to
@@ -2015,7 +4092,7 @@ impl RegistrationInner {
             enqueue_with_wakeup(queue, self)?;
         }
 
-        Ok(())
+        Ok(Some(curr_token))
     }
 }
 
@@ -2096,26 +4173,36 @@ impl ReadinessQueue {
                 end_marker: end_marker,
                 sleep_marker: sleep_marker,
                 closed_marker: closed_marker,
+                urgent: Mutex::new(UrgentQueue {
+                    nodes: VecDeque::new(),
+                    closed: false,
+                }),
+                urgent_pending: AtomicBool::new(false),
+                nested: AtomicUsize::new(0),
+                poll_closed: AtomicBool::new(false),
+                pool: Mutex::new(NodePool {
+                    nodes: vec![],
+                    cap: POOL_CAP,
+                }),
             })
         })
     }
 
     /// Poll the queue for new events
-    fn poll(&self, dst: &mut sys::Events) {
+    fn poll<S: Sink>(&self, dst: &mut S) {
         // `until` is set with the first node that gets re-enqueued due to being
         // set to have level-triggered notifications. This prevents an infinite
         // loop where `Poll::poll` will keep dequeuing nodes it enqueues.
         let mut until = ptr::null_mut();
 
-        if dst.len() == dst.capacity() {
+        if dst.is_full() {
             // If `dst` is already full, the readiness queue won't be drained.
             // This might result in `sleep_marker` staying in the queue and
             // unecessary pipe writes occuring.
             self.inner.clear_sleep_marker();
         }
 
-        'outer:
-        while dst.len() < dst.capacity() {
+        while !dst.is_full() {
             // Dequeue a node. If the queue is in an inconsistent state, then
             // stop polling. `Poll::poll` will be called again shortly and enter
             // a syscall, which should be enough to enable the other thread to
@@ -2125,84 +4212,150 @@ impl ReadinessQueue {
                 Dequeue::Data(ptr) => ptr,
             };
 
-            let node = unsafe { &*ptr };
+            if self.poll_node(ptr, dst) && until.is_null() {
+                // We never want to see the node again
+                until = ptr;
+            }
+        }
 
-            // Read the node state with Acquire ordering. This allows reading
-            // the token variables.
-            let mut state = node.state.load(Acquire);
-            let mut next;
-            let mut readiness;
-            let mut opt;
+        if dst.is_full() && unsafe { !self.inner.is_empty() } {
+            dst.set_truncated();
+        }
+    }
 
-            loop {
-                // Build up any changes to the readiness node's state and
-                // attempt the CAS at the end
-                next = state;
-
-                // Given that the node was just read from the queue, the
-                // `queued` flag should still be set.
-                debug_assert!(state.is_queued());
-
-                // The dropped flag means we need to release the node and
-                // perform no further processing on it.
-                if state.is_dropped() {
-                    // Release the node and continue
-                    release_node(ptr);
-                    continue 'outer;
-                }
+    /// Poll the urgent queue for new events. `until` plays the same role as
+    /// in `poll` and is shared by all calls made during one `Poll::poll`, so
+    /// that a level-triggered node is only returned once.
+    fn poll_urgent<S: Sink>(&self, dst: &mut S, until: &mut *mut ReadinessNode) {
+        if !self.inner.urgent_pending.load(Acquire) {
+            return;
+        }
 
-                // Process the node
-                readiness = state.effective_readiness();
-                opt = state.poll_opt();
+        let mut nodes = {
+            let mut urgent = self.inner.urgent.lock().unwrap();
+            self.inner.urgent_pending.store(false, Relaxed);
+            mem::replace(&mut urgent.nodes, VecDeque::new())
+        };
 
-                if opt.is_edge() {
-                    // Mark the node as dequeued
-                    next.set_dequeued();
+        while !dst.is_full() {
+            match nodes.front() {
+                Some(&ptr) if ptr != *until => {}
+                _ => break,
+            }
 
-                    if opt.is_oneshot() && !readiness.is_empty() {
-                        next.disarm();
-                    }
-                } else if readiness.is_empty() {
-                    next.set_dequeued();
-                }
+            let ptr = nodes.pop_front().unwrap();
 
-                // Ensure `token_read_pos` is set to `token_write_pos` so that
-                // we read the most up to date token value.
-                next.update_token_read_pos();
+            if self.poll_node(ptr, dst) && until.is_null() {
+                *until = ptr;
+            }
+        }
 
-                if state == next {
-                    break;
-                }
+        if !nodes.is_empty() {
+            if dst.is_full() {
+                dst.set_truncated();
+            }
 
-                let actual = node.state.compare_and_swap(state, next, AcqRel);
+            // Put the remaining nodes back, ahead of the ones queued in the
+            // meantime.
+            let mut urgent = self.inner.urgent.lock().unwrap();
+            nodes.extend(urgent.nodes.drain(..));
+            urgent.nodes = nodes;
+            self.inner.urgent_pending.store(true, Relaxed);
+        }
+    }
 
-                if actual == state {
-                    break;
-                }
+    /// Returns true if urgent nodes are pending. Must be called after
+    /// `prepare_for_sleep` returned true.
+    fn has_urgent(&self) -> bool {
+        // Pairs with the fence in `ReadinessQueueInner::enqueue_urgent`
+        atomic::fence(SeqCst);
+        self.inner.urgent_pending.load(Relaxed)
+    }
+
+    /// Process a node taken out of one of the queues, pushing an event into
+    /// `dst` if it is ready. Returns true if the node was requeued.
+    fn poll_node<S: Sink>(&self, ptr: *mut ReadinessNode, dst: &mut S) -> bool {
+        let node = unsafe { &*ptr };
+
+        // Read the node state with Acquire ordering. This allows reading
+        // the token variables.
+        let mut state = node.state.load(Acquire);
+        let mut next;
+        let mut readiness;
+        let mut opt;
 
-                state = actual;
+        loop {
+            // Build up any changes to the readiness node's state and
+            // attempt the CAS at the end
+            next = state;
+
+            // Given that the node was just read from the queue, the
+            // `queued` flag should still be set.
+            debug_assert!(state.is_queued());
+
+            // The dropped flag means we need to release the node and
+            // perform no further processing on it.
+            if state.is_dropped() {
+                // Release the node
+                release_node(ptr);
+                return false;
             }
 
-            // If the queued flag is still set, then the node must be requeued.
-            // This typically happens when using level-triggered notifications.
-            if next.is_queued() {
-                if until.is_null() {
-                    // We never want to see the node again
-                    until = ptr;
+            // The node moved to another `Poll` instance, which is now
+            // responsible for it
+            if finish_move(ptr) {
+                return false;
+            }
+
+            // Process the node
+            readiness = state.effective_readiness();
+            opt = state.poll_opt();
+
+            if opt.is_edge() {
+                // Mark the node as dequeued
+                next.set_dequeued();
+
+                if opt.is_oneshot() && !readiness.is_empty() {
+                    next.disarm();
                 }
+            } else if readiness.is_empty() {
+                next.set_dequeued();
+            }
 
-                // Requeue the node
-                self.inner.enqueue_node(node);
+            // Ensure `token_read_pos` is set to `token_write_pos` so that
+            // we read the most up to date token value.
+            next.update_token_read_pos();
+
+            if state == next {
+                break;
             }
 
-            if !readiness.is_empty() {
-                // Get the token
-                let token = unsafe { token(node, next.token_read_pos()) };
+            let actual = node.state.compare_and_swap(state, next, AcqRel);
 
-                // Push the event
-                dst.push_event(Event::new(readiness, token));
+            if actual == state {
+                break;
             }
+
+            state = actual;
+        }
+
+        // If the queued flag is still set, then the node must be requeued.
+        // This typically happens when using level-triggered notifications.
+        let requeued = next.is_queued();
+
+        if requeued {
+            self.inner.enqueue_node(node);
+        }
+
+        if !readiness.is_empty() {
+            // Get the token
+            let token = unsafe { token(node, next.token_read_pos()) };
+
+            // Push the event
+            dst.push_event(Event::new(readiness, token));
         }
+
+        requeued
     }
 
     /// Prepare the queue for the `Poll::poll` thread to block in the system
@@ -2263,6 +4416,19 @@ impl ReadinessQueue {
 
 impl Drop for ReadinessQueue {
     fn drop(&mut self) {
+        // Close the urgent queue and free the nodes left in it
+        let nodes = {
+            let mut urgent = self.inner.urgent.lock().unwrap();
+            urgent.closed = true;
+            mem::replace(&mut urgent.nodes, VecDeque::new())
+        };
+
+        for ptr in nodes {
+            if !finish_move(ptr) {
+                release_node(ptr);
+            }
+        }
+
         // Close the queue by enqueuing the closed node
         self.inner.enqueue_node(&*self.inner.closed_marker);
 
@@ -2285,7 +4451,9 @@ impl Drop for ReadinessQueue {
 
             debug_assert!(state.is_queued());
 
-            release_node(ptr);
+            if !finish_move(ptr) {
+                release_node(ptr);
+            }
         }
     }
 }
@@ -2295,18 +4463,52 @@ impl ReadinessQueueInner {
         self.awakener.wakeup()
     }
 
+    /// Fails if the node pool or the urgent queue is locked, which in a
+    /// forked child means a thread of the parent held it at the time of the
+    /// fork and never releases it.
+    fn check_unlocked(&self) -> io::Result<()> {
+        if self.pool.try_lock().is_err() || self.urgent.try_lock().is_err() {
+            return Err(io::Error::new(io::ErrorKind::Other,
+                                      "readiness queue in use by another thread at the time of the fork"));
+        }
+
+        Ok(())
+    }
+
+    fn is_nested(&self) -> bool {
+        self.nested.load(Relaxed) != 0
+    }
+
+    fn is_closed(&self) -> bool {
+        self.poll_closed.load(SeqCst)
+    }
+
     /// Prepend the given node to the head of the readiness queue. This is done
     /// with relaxed ordering. Returns true if `Poll` needs to be woken up.
     fn enqueue_node_with_wakeup(&self, node: &ReadinessNode) -> io::Result<()> {
-        if self.enqueue_node(node) {
+        if self.enqueue_node_batched(node) {
             self.wakeup()?;
         }
 
         Ok(())
     }
 
+    /// Like `enqueue_node_with_wakeup`, but leaves waking up `Poll` to the
+    /// caller. Returns true if it needs to be woken up.
+    fn enqueue_node_batched(&self, node: &ReadinessNode) -> bool {
+        // Read first, pushing into a closed queue may release the last handle
+        // to it.
+        let nested = self.is_nested();
+
+        self.enqueue_node(node) || nested
+    }
+
     /// Push the node into the readiness queue
     fn enqueue_node(&self, node: &ReadinessNode) -> bool {
+        if node.state.load(Relaxed).poll_opt().is_urgent() {
+            return self.enqueue_urgent(node);
+        }
+
         // This is the 1024cores.net intrusive MPSC queue [1] "push" function.
         let node_ptr = node as *const _ as *mut _;
 
@@ -2328,7 +4530,10 @@ impl ReadinessQueueInner {
                         // set. This means that we are responsible for decrementing
                         // the ready queue's ref count
                         debug_assert!(node.ref_count.load(Relaxed) >= 2);
-                        release_node(node_ptr);
+
+                        if !finish_move(node_ptr) {
+                            release_node(node_ptr);
+                        }
                     }
 
                     return false;
@@ -2351,6 +4556,48 @@ impl ReadinessQueueInner {
         }
     }
 
+    /// Push the node into the urgent queue. Returns true if `Poll` needs to be
+    /// woken up.
+    fn enqueue_urgent(&self, node: &ReadinessNode) -> bool {
+        let node_ptr = node as *const _ as *mut _;
+
+        let closed = {
+            let mut urgent = self.urgent.lock().unwrap();
+
+            if !urgent.closed {
+                urgent.nodes.push_back(node_ptr);
+                self.urgent_pending.store(true, Relaxed);
+            }
+
+            urgent.closed
+        };
+
+        if closed {
+            // Same as pushing into the closed MPSC queue. The node may hold the
+            // last handle to the queue, so the lock is released first.
+            debug_assert!(node.ref_count.load(Relaxed) >= 2);
+
+            if !finish_move(node_ptr) {
+                release_node(node_ptr);
+            }
+
+            return false;
+        }
+
+        // Pairs with the fence in `ReadinessQueue::has_urgent`. Either `Poll`
+        // sees the node before going to sleep, or we see `sleep_marker`.
+        atomic::fence(SeqCst);
+        self.head_readiness.load(Relaxed) == self.sleep_marker()
+    }
+
+    /// Returns true if no node is queued. Must only be called in `poll`.
+    unsafe fn is_empty(&self) -> bool {
+        let tail = *self.tail_readiness.get();
+
+        (tail == self.end_marker() || tail == self.sleep_marker()) &&
+            (*tail).next_readiness.load(Acquire).is_null()
+    }
+
     fn clear_sleep_marker(&self) {
         let end_marker = self.end_marker();
         let sleep_marker = self.sleep_marker();
@@ -2467,6 +4714,7 @@ impl ReadinessNode {
             update_lock: AtomicBool::new(false),
             readiness_queue: AtomicPtr::new(queue),
             ref_count: AtomicUsize::new(ref_count),
+            moved_to: AtomicPtr::new(ptr::null_mut()),
         }
     }
 
@@ -2480,6 +4728,7 @@ impl ReadinessNode {
             update_lock: AtomicBool::new(false),
             readiness_queue: AtomicPtr::new(ptr::null_mut()),
             ref_count: AtomicUsize::new(0),
+            moved_to: AtomicPtr::new(ptr::null_mut()),
         }
     }
 
@@ -2502,6 +4751,16 @@ fn enqueue_with_wakeup(queue: *mut (), node: &ReadinessNode) -> io::Result<()> {
     queue.enqueue_node_with_wakeup(node)
 }
 
+/// Returns true if the node's queue belongs to a closed `Poll` instance.
+fn is_closed(queue: *mut ()) -> bool {
+    if queue.is_null() {
+        return false;
+    }
+
+    let queue: &Arc<ReadinessQueueInner> = unsafe { mem::transmute(&queue) };
+    queue.is_closed()
+}
+
 unsafe fn token(node: &ReadinessNode, pos: usize) -> Token {
     match pos {
         0 => *node.token_0.get(),
@@ -2511,6 +4770,81 @@ unsafe fn token(node: &ReadinessNode, pos: usize) -> Token {
     }
 }
 
+/// Returns a node associated with `queue`, taken from its pool if possible.
+fn alloc_node(queue: &Arc<ReadinessQueueInner>,
+              token: Token,
+              interest: Ready,
+              opt: PollOpt,
+              ref_count: usize) -> *mut ReadinessNode
+{
+    let pooled = queue.pool.lock().unwrap().nodes.pop();
+
+    // Clone handle to the readiness queue, this bumps the ref count
+    let queue: *mut () = unsafe { mem::transmute(queue.clone()) };
+
+    let node = ReadinessNode::new(queue, token, interest, opt, ref_count);
+
+    match pooled {
+        Some(ptr) => {
+            unsafe { ptr::write(ptr, node) };
+            ptr
+        }
+        None => Box::into_raw(Box::new(node)),
+    }
+}
+
+/// Completes a move started by `RegistrationInner::transfer`. Called by the
+/// holder of the node's queued flag in place of queuing or releasing the node
+/// for its current queue. Returns false if no move is pending.
+fn finish_move(ptr: *mut ReadinessNode) -> bool {
+    let node = unsafe { &*ptr };
+
+    if node.moved_to.load(Acquire).is_null() {
+        return false;
+    }
+
+    let next = node.moved_to.swap(ptr::null_mut(), AcqRel);
+
+    if next.is_null() {
+        return false;
+    }
+
+    // The handle `Poll` has on the node now belongs to the new queue
+    let prev = node.readiness_queue.swap(next, AcqRel);
+    let _: Arc<ReadinessQueueInner> = unsafe { mem::transmute(prev) };
+
+    let mut state = node.state.load(Acquire);
+
+    loop {
+        if state.is_dropped() {
+            release_node(ptr);
+            return true;
+        }
+
+        let mut next = state;
+
+        // Queue the node again if it is ready, this time with the new queue
+        next.set_dequeued();
+
+        if !next.effective_readiness().is_empty() {
+            next.set_queued();
+        }
+
+        let actual = node.state.compare_and_swap(state, next, AcqRel);
+
+        if actual == state {
+            if next.is_queued() {
+                // Can't do anything if the queuing fails
+                let _ = node.enqueue_with_wakeup();
+            }
+
+            return true;
+        }
+
+        state = actual;
+    }
+}
+
 fn release_node(ptr: *mut ReadinessNode) {
     unsafe {
         // `AcqRel` synchronizes with other `release_node` functions and ensures
@@ -2519,16 +4853,31 @@ fn release_node(ptr: *mut ReadinessNode) {
             return;
         }
 
-        let node = Box::from_raw(ptr);
+        // A move may have been left pending by a closed `Poll` instance
+        let moved_to = (*ptr).moved_to.swap(ptr::null_mut(), Acquire);
+
+        if !moved_to.is_null() {
+            let _: Arc<ReadinessQueueInner> = mem::transmute(moved_to);
+        }
 
         // Decrement the readiness_queue Arc
-        let queue = node.readiness_queue.load(Acquire);
+        let queue = (*ptr).readiness_queue.swap(ptr::null_mut(), Acquire);
 
         if queue.is_null() {
+            drop(Box::from_raw(ptr));
             return;
         }
 
-        let _: Arc<ReadinessQueueInner> = mem::transmute(queue);
+        let queue: Arc<ReadinessQueueInner> = mem::transmute(queue);
+
+        // Keep the node for the next registration instead of freeing it
+        let mut pool = queue.pool.lock().unwrap();
+
+        if pool.nodes.len() < pool.cap {
+            pool.nodes.push(ptr);
+        } else {
+            drop(Box::from_raw(ptr));
+        }
     }
 }
 
@@ -2568,7 +4917,7 @@ impl ReadinessState {
         let interest = event::ready_as_usize(interest);
         let opt = event::opt_as_usize(opt);
 
-        debug_assert!(interest <= MASK_4);
+        debug_assert!(interest <= MASK_9);
         debug_assert!(opt <= MASK_4);
 
         let mut val = interest << INTEREST_SHIFT;
@@ -2584,13 +4933,13 @@ impl ReadinessState {
 
     #[inline]
     fn set(&mut self, val: usize, mask: usize, shift: usize) {
-        self.0 = (self.0 & !(mask << shift)) | (val << shift)
+        self.0 = (self.0 & !(mask << shift)) | ((val & mask) << shift)
     }
 
     /// Get the readiness
     #[inline]
     fn readiness(&self) -> Ready {
-        let v = self.get(MASK_4, READINESS_SHIFT);
+        let v = self.get(MASK_9, READINESS_SHIFT);
         event::ready_from_usize(v)
     }
 
@@ -2602,20 +4951,20 @@ impl ReadinessState {
     /// Set the readiness
     #[inline]
     fn set_readiness(&mut self, v: Ready) {
-        self.set(event::ready_as_usize(v), MASK_4, READINESS_SHIFT);
+        self.set(event::ready_as_usize(v), MASK_9, READINESS_SHIFT);
     }
 
     /// Get the interest
     #[inline]
     fn interest(&self) -> Ready {
-        let v = self.get(MASK_4, INTEREST_SHIFT);
+        let v = self.get(MASK_9, INTEREST_SHIFT);
         event::ready_from_usize(v)
     }
 
     /// Set the interest
     #[inline]
     fn set_interest(&mut self, v: Ready) {
-        self.set(event::ready_as_usize(v), MASK_4, INTEREST_SHIFT);
+        self.set(event::ready_as_usize(v), MASK_9, INTEREST_SHIFT);
     }
 
     #[inline]
@@ -2738,16 +5087,26 @@ impl SelectorId {
         }
     }
 
-    pub fn associate_selector(&self, poll: &Poll) -> io::Result<()> {
+    pub fn associate_selector(&self, registry: &Registry) -> io::Result<()> {
         let selector_id = self.id.load(Ordering::SeqCst);
 
-        if selector_id != 0 && selector_id != poll.selector.id() {
-            Err(io::Error::new(io::ErrorKind::Other, "socket already registered"))
+        if selector_id != 0 && selector_id != registry.selector.id() {
+            Err(RegisterError::ForeignSelector.into())
         } else {
-            self.id.store(poll.selector.id(), Ordering::SeqCst);
+            self.id.store(registry.selector.id(), Ordering::SeqCst);
             Ok(())
         }
     }
+
+    pub fn transfer(&self, from: &Registry, to: &Registry) -> io::Result<()> {
+        let from_id = from.selector.id();
+
+        match self.id.compare_exchange(from_id, to.selector.id(), Ordering::SeqCst, Ordering::SeqCst) {
+            Ok(_) => Ok(()),
+            Err(0) => Err(RegisterError::NotRegistered.into()),
+            Err(_) => Err(RegisterError::ForeignSelector.into()),
+        }
+    }
 }
 
 impl Clone for SelectorId {
@@ -2763,3 +5122,23 @@ pub fn as_raw_fd() {
     let poll = Poll::new().unwrap();
     assert!(poll.as_raw_fd() > 0);
 }
+
+#[test]
+pub fn registration_pool_reuses_nodes() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+    let pooled = || poll.readiness_queue.inner.pool.lock().unwrap().nodes.len();
+
+    let (r, set) = Registration::with_registry(poll.registry());
+    poll.register(&r, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
+    drop(r);
+    drop(set);
+    assert_eq!(pooled(), 0);
+
+    // Releases the handle `poll` has on the node
+    poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+    assert_eq!(pooled(), 1);
+
+    let (_r, _set) = Registration::with_registry(poll.registry());
+    assert_eq!(pooled(), 0);
+}
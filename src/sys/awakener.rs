@@ -1,11 +1,13 @@
-pub use self::pipe::Awakener;
+pub use self::udp::Awakener;
 
-/// Default awakener backed by a pipe
-mod pipe {
-    use crate::sys as unix;
-    use crate::{io, Ready, Poll, PollOpt, Token};
+/// Awakener backed by a loopback UDP socket connected to itself. Horizon has
+/// no pipes, but BSD sockets work on every supported target.
+mod udp {
+    use crate::sys::{cvt, EventedFd};
+    use crate::{io, Ready, PollOpt, Registry, Token};
     use crate::event::Evented;
-    use std::io::{Read, Write};
+    use std::net::{self, Ipv4Addr, SocketAddr};
+    use std::os::unix::io::{AsRawFd, RawFd};
 
     /*
      *
@@ -14,42 +16,79 @@ mod pipe {
      */
 
     pub struct Awakener {
-        //reader: unix::Io,
-        //writer: unix::Io,
+        sock: net::UdpSocket,
     }
 
     impl Awakener {
         pub fn new() -> io::Result<Awakener> {
-            Ok(Awakener {})
+            let sock = net::UdpSocket::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)))?;
+            sock.connect(sock.local_addr()?)?;
+            sock.set_nonblocking(true)?;
+
+            Ok(Awakener { sock: sock })
+        }
+
+        /// Replaces the socket with a new one that keeps the same fd, so
+        /// that registrations of the fd stay valid. A forked child shares the
+        /// socket with its parent until then.
+        pub fn reset(&self) -> io::Result<()> {
+            let fresh = Awakener::new()?;
+            let fd = self.sock.as_raw_fd();
+
+            unsafe {
+                cvt(libc::dup2(fresh.sock.as_raw_fd(), fd))?;
+                cvt(libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC))?;
+            }
+
+            // `fresh` closes its own fd, the description lives on under `fd`
+            Ok(())
         }
 
         pub fn wakeup(&self) -> io::Result<()> {
-            unimplemented!("not supported by horizon")
+            match self.sock.send(&[1]) {
+                Ok(_) => Ok(()),
+                Err(e) => {
+                    if e.kind() == io::ErrorKind::WouldBlock {
+                        // The socket buffer is full, so a wakeup is already
+                        // pending.
+                        Ok(())
+                    } else {
+                        Err(e)
+                    }
+                }
+            }
         }
 
         pub fn cleanup(&self) {
-            unimplemented!("not supported by horizon")
+            let mut buf = [0; 128];
+
+            loop {
+                // Consume data until all bytes are purged
+                match self.sock.recv(&mut buf) {
+                    Ok(_) => {}
+                    Err(_) => return,
+                }
+            }
         }
+    }
 
-        fn reader(&self) -> &unix::Io {
-            unimplemented!("not supported by horizon")
+    impl AsRawFd for Awakener {
+        fn as_raw_fd(&self) -> RawFd {
+            self.sock.as_raw_fd()
         }
     }
 
     impl Evented for Awakener {
-        fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-            //self.reader().register(poll, token, interest, opts)
-            Ok(())
+        fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+            EventedFd(&self.sock.as_raw_fd()).register(registry, token, interest, opts)
         }
 
-        fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-            //self.reader().reregister(poll, token, interest, opts)
-            Ok(())
+        fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+            EventedFd(&self.sock.as_raw_fd()).reregister(registry, token, interest, opts)
         }
 
-        fn deregister(&self, poll: &Poll) -> io::Result<()> {
-            //self.reader().deregister(poll)
-            Ok(())
+        fn deregister(&self, registry: &Registry) -> io::Result<()> {
+            EventedFd(&self.sock.as_raw_fd()).deregister(registry)
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::{io, poll, Ready, Poll, PollOpt, Token};
+use crate::{io, poll, Ready, PollOpt, Registry, Token};
 use crate::event::Evented;
 use std::os::unix::io::RawFd;
 
@@ -44,7 +44,7 @@ use std::os::unix::io::RawFd;
 ///
 /// // Register the listener
 /// poll.register(&EventedFd(&listener.as_raw_fd()),
-///              Token(0), Ready::readable(), PollOpt::edge())?;
+///              Token(0), Ready::readable(), PollOpt::level())?;
 /// #     Ok(())
 /// # }
 /// #
@@ -56,7 +56,7 @@ use std::os::unix::io::RawFd;
 /// Implementing `Evented` for a custom type backed by a `RawFd`.
 ///
 /// ```
-/// use mio::{Ready, Poll, PollOpt, Token};
+/// use mio::{Ready, PollOpt, Registry, Token};
 /// use mio::event::Evented;
 /// use mio::unix::EventedFd;
 ///
@@ -68,20 +68,20 @@ use std::os::unix::io::RawFd;
 /// }
 ///
 /// impl Evented for MyIo {
-///     fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt)
+///     fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt)
 ///         -> io::Result<()>
 ///     {
-///         EventedFd(&self.fd).register(poll, token, interest, opts)
+///         EventedFd(&self.fd).register(registry, token, interest, opts)
 ///     }
 ///
-///     fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt)
+///     fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt)
 ///         -> io::Result<()>
 ///     {
-///         EventedFd(&self.fd).reregister(poll, token, interest, opts)
+///         EventedFd(&self.fd).reregister(registry, token, interest, opts)
 ///     }
 ///
-///     fn deregister(&self, poll: &Poll) -> io::Result<()> {
-///         EventedFd(&self.fd).deregister(poll)
+///     fn deregister(&self, registry: &Registry) -> io::Result<()> {
+///         EventedFd(&self.fd).deregister(registry)
 ///     }
 /// }
 /// ```
@@ -92,15 +92,43 @@ use std::os::unix::io::RawFd;
 pub struct EventedFd<'a>(pub &'a RawFd);
 
 impl<'a> Evented for EventedFd<'a> {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        poll::selector(poll).register(*self.0, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        poll::selector(registry).register(*self.0, token, interest, opts)
+    }
+
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        poll::selector(registry).reregister(*self.0, token, interest, opts)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        let (token, write_token) = poll::selector(registry).deregister(*self.0)?;
+
+        poll::forget_generation(registry, token);
+
+        if let Some(write_token) = write_token {
+            poll::forget_generation(registry, write_token);
+        }
+
+        Ok(())
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        let (old_token, write_token) = poll::selector(from).transfer(*self.0, poll::selector(to), token, interest, opts)?;
+
+        poll::forget_generation(from, old_token);
+
+        if let Some(write_token) = write_token {
+            poll::forget_generation(from, write_token);
+        }
+
+        Ok(())
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        poll::selector(poll).reregister(*self.0, token, interest, opts)
+    fn register_split(&self, registry: &Registry, read_token: Token, write_token: Token, opts: PollOpt) -> io::Result<()> {
+        poll::selector(registry).register_split(*self.0, read_token, write_token, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        poll::selector(poll).deregister(*self.0)
+    fn reregister_split(&self, registry: &Registry, read_token: Token, write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        poll::selector(registry).reregister_split(*self.0, read_token, write_token, interest, opts)
     }
 }
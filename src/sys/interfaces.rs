@@ -0,0 +1,74 @@
+use std::ffi::CStr;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::ptr;
+
+use crate::io;
+use crate::net::{Interface, InterfaceAddr};
+
+use super::cvt;
+
+pub fn interfaces() -> io::Result<Vec<Interface>> {
+    let mut head = ptr::null_mut();
+    unsafe { cvt(libc::getifaddrs(&mut head))?; }
+
+    let mut interfaces: Vec<Interface> = vec![];
+    let mut cur = head;
+
+    while !cur.is_null() {
+        let ifa = unsafe { &*cur };
+        cur = ifa.ifa_next;
+
+        // getifaddrs returns one entry per address, plus one without an
+        // address or with a link layer address for each interface.
+        let pos = match interfaces.iter().position(|i| unsafe { name_eq(&i.name, ifa.ifa_name) }) {
+            Some(pos) => pos,
+            None => {
+                let flags = ifa.ifa_flags as libc::c_int;
+
+                interfaces.push(Interface {
+                    name: unsafe { CStr::from_ptr(ifa.ifa_name) }.to_string_lossy().into_owned(),
+                    index: unsafe { libc::if_nametoindex(ifa.ifa_name) },
+                    up: flags & libc::IFF_UP != 0,
+                    loopback: flags & libc::IFF_LOOPBACK != 0,
+                    multicast: flags & libc::IFF_MULTICAST != 0,
+                    addrs: vec![],
+                });
+
+                interfaces.len() - 1
+            }
+        };
+
+        if let Some(addr) = unsafe { to_ip(ifa.ifa_addr) } {
+            interfaces[pos].addrs.push(InterfaceAddr {
+                addr: addr,
+                netmask: unsafe { to_ip(ifa.ifa_netmask) },
+            });
+        }
+    }
+
+    unsafe { libc::freeifaddrs(head); }
+
+    Ok(interfaces)
+}
+
+unsafe fn name_eq(name: &str, other: *const libc::c_char) -> bool {
+    CStr::from_ptr(other).to_bytes() == name.as_bytes()
+}
+
+unsafe fn to_ip(addr: *const libc::sockaddr) -> Option<IpAddr> {
+    if addr.is_null() {
+        return None;
+    }
+
+    match (*addr).sa_family as libc::c_int {
+        libc::AF_INET => {
+            let addr = &*(addr as *const libc::sockaddr_in);
+            Some(IpAddr::V4(Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr))))
+        }
+        libc::AF_INET6 => {
+            let addr = &*(addr as *const libc::sockaddr_in6);
+            Some(IpAddr::V6(Ipv6Addr::from(addr.sin6_addr.s6_addr)))
+        }
+        _ => None,
+    }
+}
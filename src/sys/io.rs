@@ -4,7 +4,7 @@ use std::os::unix::io::{IntoRawFd, AsRawFd, FromRawFd, RawFd};
 
 use libc;
 
-use crate::{io, Ready, Poll, PollOpt, Token};
+use crate::{io, Ready, PollOpt, Registry, Token};
 use crate::event::Evented;
 use super::EventedFd;
 use super::cvt;
@@ -61,16 +61,28 @@ impl AsRawFd for Io {
 }
 
 impl Evented for Io {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        EventedFd(&self.as_raw_fd()).register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        EventedFd(&self.as_raw_fd()).reregister(poll, token, interest, opts)
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        EventedFd(&self.as_raw_fd()).deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(registry)
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).transfer(from, to, token, interest, opts)
+    }
+
+    fn register_split(&self, registry: &Registry, read_token: Token, write_token: Token, opts: PollOpt) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register_split(registry, read_token, write_token, opts)
+    }
+
+    fn reregister_split(&self, registry: &Registry, read_token: Token, write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister_split(registry, read_token, write_token, interest, opts)
     }
 }
 
@@ -0,0 +1,141 @@
+use std::env;
+use std::mem;
+use std::net;
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::os::unix::net::UnixListener;
+
+use crate::io;
+use crate::net::{TcpListener, UdpSocket};
+
+use super::cvt;
+
+/// The first fd passed by the service manager, see `sd_listen_fds(3)`.
+const LISTEN_FDS_START: RawFd = 3;
+
+/// A socket inherited from the service manager through socket activation.
+///
+/// Each value is non-blocking and ready to be registered with `Poll`. Unix
+/// domain listeners are returned as `std` listeners, register them through
+/// [`EventedFd`].
+///
+/// [`EventedFd`]: struct.EventedFd.html
+#[derive(Debug)]
+pub enum ListenFd {
+    /// A listening TCP socket.
+    Tcp(TcpListener),
+    /// A bound UDP socket.
+    Udp(UdpSocket),
+    /// A listening Unix domain stream socket.
+    Unix(UnixListener),
+}
+
+#[derive(Copy, Clone)]
+enum Kind {
+    Tcp,
+    Udp,
+    Unix,
+}
+
+/// Adopts the sockets passed by a service manager such as systemd.
+///
+/// Follows the `sd_listen_fds(3)` protocol: if `LISTEN_PID` names the current
+/// process, `LISTEN_FDS` sockets starting at fd 3 are returned along with
+/// their names from `LISTEN_FDNAMES`, or `"unknown"` if no name was given.
+/// An empty list is returned if the process was not socket activated.
+///
+/// On success the three variables are removed from the environment so the
+/// sockets cannot be adopted twice, or inherited by child processes, and the
+/// fds are marked close-on-exec.
+///
+/// Fails with `InvalidInput` if any of the fds is not a TCP or Unix domain
+/// listener or a UDP socket. Nothing is adopted in that case.
+pub fn listen_fds() -> io::Result<Vec<(String, ListenFd)>> {
+    let pid = match env::var("LISTEN_PID") {
+        Ok(pid) => pid,
+        Err(..) => return Ok(vec![]),
+    };
+
+    match pid.parse::<libc::pid_t>() {
+        Ok(pid) if pid == unsafe { libc::getpid() } => {}
+        Ok(..) => return Ok(vec![]),
+        Err(..) => return Err(invalid("invalid LISTEN_PID")),
+    }
+
+    let count = env::var("LISTEN_FDS").ok()
+        .and_then(|n| n.parse::<RawFd>().ok())
+        .filter(|&n| (0..=RawFd::MAX - LISTEN_FDS_START).contains(&n))
+        .ok_or_else(|| invalid("invalid LISTEN_FDS"))?;
+
+    let mut names: Vec<String> = env::var("LISTEN_FDNAMES")
+        .map(|names| names.split(':').map(String::from).collect())
+        .unwrap_or_default();
+
+    if names.len() != count as usize {
+        names = vec!["unknown".to_string(); count as usize];
+    }
+
+    let fds = (LISTEN_FDS_START..LISTEN_FDS_START + count)
+        .map(|fd| kind(fd).map(|kind| (fd, kind)))
+        .collect::<io::Result<Vec<_>>>()?;
+
+    env::remove_var("LISTEN_PID");
+    env::remove_var("LISTEN_FDS");
+    env::remove_var("LISTEN_FDNAMES");
+
+    names.into_iter().zip(fds).map(|(name, (fd, kind))| {
+        unsafe { cvt(libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC))?; }
+
+        let socket = match kind {
+            Kind::Tcp => {
+                ListenFd::Tcp(TcpListener::from_std(unsafe { net::TcpListener::from_raw_fd(fd) })?)
+            }
+            Kind::Udp => {
+                ListenFd::Udp(UdpSocket::from_socket(unsafe { net::UdpSocket::from_raw_fd(fd) })?)
+            }
+            Kind::Unix => {
+                let listener = unsafe { UnixListener::from_raw_fd(fd) };
+                listener.set_nonblocking(true)?;
+                ListenFd::Unix(listener)
+            }
+        };
+
+        Ok((name, socket))
+    }).collect()
+}
+
+fn kind(fd: RawFd) -> io::Result<Kind> {
+    let ty = getsockopt(fd, libc::SO_TYPE)?;
+    let listening = getsockopt(fd, libc::SO_ACCEPTCONN)? != 0;
+
+    let family = unsafe {
+        let mut addr: libc::sockaddr_storage = mem::zeroed();
+        let mut len = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+        cvt(libc::getsockname(fd, &mut addr as *mut _ as *mut libc::sockaddr, &mut len))?;
+        addr.ss_family as libc::c_int
+    };
+
+    match (family, ty, listening) {
+        (libc::AF_INET, libc::SOCK_STREAM, true) |
+        (libc::AF_INET6, libc::SOCK_STREAM, true) => Ok(Kind::Tcp),
+        (libc::AF_INET, libc::SOCK_DGRAM, _) |
+        (libc::AF_INET6, libc::SOCK_DGRAM, _) => Ok(Kind::Udp),
+        (libc::AF_UNIX, libc::SOCK_STREAM, true) => Ok(Kind::Unix),
+        _ => Err(invalid(&format!("fd {} is not a supported socket", fd))),
+    }
+}
+
+fn getsockopt(fd: RawFd, opt: libc::c_int) -> io::Result<libc::c_int> {
+    let mut val: libc::c_int = 0;
+    let mut len = mem::size_of::<libc::c_int>() as libc::socklen_t;
+
+    unsafe {
+        cvt(libc::getsockopt(fd, libc::SOL_SOCKET, opt,
+                             &mut val as *mut _ as *mut libc::c_void, &mut len))?;
+    }
+
+    Ok(val)
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, msg.to_string())
+}
@@ -1,11 +1,16 @@
 mod poll;
 
-pub use self::poll::{Events, Selector};
+pub use self::poll::{Events, Selector, Sink};
 
 mod awakener;
 mod eventedfd;
+#[cfg(not(target_os = "horizon"))]
+mod interfaces;
 mod io;
+#[cfg(target_os = "linux")]
+mod listen_fds;
 mod ready;
+mod source;
 mod tcp;
 mod udp;
 
@@ -14,9 +19,14 @@ mod uds;
 
 pub use self::awakener::Awakener;
 pub use self::eventedfd::EventedFd;
+#[cfg(not(target_os = "horizon"))]
+pub use self::interfaces::interfaces;
 pub use self::io::{Io, set_nonblock};
+#[cfg(target_os = "linux")]
+pub use self::listen_fds::{listen_fds, ListenFd};
 pub use self::ready::{UnixReady, READY_ALL};
-pub use self::tcp::{TcpStream, TcpListener};
+pub use self::source::Source;
+pub use self::tcp::{TcpStream, TcpListener, set_bind_address_no_port};
 pub use self::udp::UdpSocket;
 
 #[cfg(feature = "with-deprecated")]
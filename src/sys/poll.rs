@@ -1,15 +1,15 @@
-use std::os::unix::io::RawFd;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::Duration;
-use std::{cmp, i32};
+use std::{cmp, i32, mem};
 use std::io;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex, TryLockError, Weak};
 
-use libc::{POLLERR, POLLHUP, POLLIN, POLLOUT, POLLPRI};
+use libc::{POLLERR, POLLHUP, POLLIN, POLLNVAL, POLLOUT, POLLPRI};
 
 use crate::event_imp::Event;
-use super::{cvt, UnixReady};
-use crate::{PollOpt, Ready, Token};
+use super::{cvt, Awakener, UnixReady};
+use crate::{Capabilities, PollOpt, Ready, RegisterError, Token};
 
 /// Each Selector has a globally unique(ish) ID associated with it. This ID
 /// gets tracked by `TcpStream`, `TcpListener`, etc... when they are first
@@ -18,9 +18,209 @@ use crate::{PollOpt, Ready, Token};
 /// operation will return with an error. This matches windows behavior.
 static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
+/// Held while a selector is nested in another one, so that concurrent calls
+/// to `Selector::register_selector` cannot nest two selectors in each other.
+static NESTING: Mutex<()> = Mutex::new(());
+
 pub struct Selector {
     id: usize,
-    events: Mutex<Vec<libc::pollfd>>,
+    registrations: Mutex<Registrations>,
+    // Number of threads blocked in `poll(2)` on the registered fds, directly
+    // or through a selector this one is nested in.
+    selecting: AtomicUsize,
+    // Reject the options `poll(2)` can only emulate, see `Selector::set_strict`
+    strict: AtomicBool,
+    // Panic when a registered fd is closed, see `Selector::set_assert_open`
+    assert_open: AtomicBool,
+}
+
+/// The `pollfd` set handed to `poll(2)` along with the token and file each
+/// entry was registered with. All vectors are kept in the same order.
+///
+/// The first `urgent` entries are the ones registered with
+/// `PollOpt::urgent()`, so their events are reported first.
+struct Registrations {
+    fds: Vec<libc::pollfd>,
+    tokens: Vec<Token>,
+    // Token of the writable readiness, if split from the readable one, see
+    // `Selector::register_split`
+    write_tokens: Vec<Option<Token>>,
+    files: Vec<FileId>,
+    urgent: usize,
+    children: Vec<Child>,
+    // Empty unless the fds are polled by several threads, see
+    // `Selector::set_partitions`.
+    partitions: Vec<Partition>,
+}
+
+/// Share of the registrations polled by one thread. An fd belongs to
+/// partition `fd % partitions.len()`.
+struct Partition {
+    // Wakes up the thread polling the partition when it changes
+    awakener: Awakener,
+    // Number of threads blocked in `poll(2)` on the partition
+    selecting: usize,
+}
+
+/// Selects the entries polled by `Selector::select_partition`.
+#[derive(Clone, Copy)]
+struct Filter {
+    partition: usize,
+    count: usize,
+    awakener: Token,
+    with_awakener: bool,
+}
+
+impl Filter {
+    fn own(&self, fd: RawFd, token: Token) -> bool {
+        if token == self.awakener {
+            self.with_awakener
+        } else {
+            fd as usize % self.count == self.partition
+        }
+    }
+
+    fn child(&self, id: usize) -> bool {
+        id % self.count == self.partition
+    }
+}
+
+/// A selector nested in another one, see `Selector::register_selector`.
+///
+/// The fds of the child are polled along with the ones of its parent, and
+/// any of them being ready is reported as a single event for `token`.
+struct Child {
+    id: usize,
+    selector: Weak<Selector>,
+    token: Token,
+    interests: Ready,
+}
+
+/// Copy of the fds to poll, taken so that the registrations are not locked
+/// while blocked in `poll(2)`.
+struct Snapshot {
+    fds: Vec<libc::pollfd>,
+    origins: Vec<Origin>,
+    // Nested selectors whose `selecting` count was incremented
+    nested: Vec<Arc<Selector>>,
+}
+
+enum Origin {
+    // Entry of the selector itself, with its tokens and file, and whether it
+    // is registered for `UnixReady::invalid()`
+    Own(Token, Option<Token>, FileId, bool),
+    // Entry of a nested selector, reported with the token and interests the
+    // child was registered with
+    Nested(Token, Ready),
+    // Awakener of the polled partition
+    Partition,
+}
+
+/// Device and inode of the file an fd refers to.
+///
+/// Unlike epoll, `poll(2)` does not forget about an fd when it is closed, so
+/// an entry may be left behind by a handle that was dropped without being
+/// deregistered. The file tells such an entry apart from the same handle
+/// being registered twice once the fd number is reused.
+type FileId = (u64, u64);
+
+fn file_id(fd: RawFd) -> FileId {
+    unsafe {
+        let mut stat: libc::stat = mem::zeroed();
+
+        if libc::fstat(fd, &mut stat) < 0 {
+            return (0, 0);
+        }
+
+        (stat.st_dev as u64, stat.st_ino as u64)
+    }
+}
+
+/// Entry removed by `Registrations::take`.
+struct Removed {
+    fd: libc::pollfd,
+    token: Token,
+    write_token: Option<Token>,
+    file: FileId,
+    urgent: bool,
+}
+
+impl Registrations {
+    fn position(&self, fd: RawFd) -> Option<usize> {
+        self.fds.iter().position(|e| e.fd == fd)
+    }
+
+    fn insert(&mut self, fd: RawFd, token: Token, write_token: Option<Token>, interests: Ready, opts: PollOpt) {
+        let pos = if opts.is_urgent() {
+            self.urgent += 1;
+            self.urgent - 1
+        } else {
+            self.fds.len()
+        };
+
+        self.fds.insert(pos, libc::pollfd {
+            fd: fd,
+            events: ready_to_poll(interests, opts),
+            revents: 0,
+        });
+        self.tokens.insert(pos, token);
+        self.write_tokens.insert(pos, write_token);
+        self.files.insert(pos, file_id(fd));
+    }
+
+    /// Wakes up the thread polling the partition of `key`, an fd or selector
+    /// id, so that it picks up a change.
+    fn notify(&self, key: usize) {
+        if self.partitions.is_empty() {
+            return;
+        }
+
+        let partition = &self.partitions[key % self.partitions.len()];
+
+        if partition.selecting != 0 {
+            let _ = partition.awakener.wakeup();
+        }
+    }
+
+    /// Removes the entry at `pos`, keeping what `restore` needs to put it
+    /// back as it was.
+    fn take(&mut self, pos: usize) -> Removed {
+        let removed = Removed {
+            fd: self.fds[pos],
+            token: self.tokens[pos],
+            write_token: self.write_tokens[pos],
+            file: self.files[pos],
+            urgent: pos < self.urgent,
+        };
+
+        self.remove(pos);
+        removed
+    }
+
+    fn restore(&mut self, removed: Removed) {
+        let pos = if removed.urgent {
+            self.urgent += 1;
+            self.urgent - 1
+        } else {
+            self.fds.len()
+        };
+
+        self.fds.insert(pos, removed.fd);
+        self.tokens.insert(pos, removed.token);
+        self.write_tokens.insert(pos, removed.write_token);
+        self.files.insert(pos, removed.file);
+    }
+
+    fn remove(&mut self, pos: usize) {
+        if pos < self.urgent {
+            self.urgent -= 1;
+        }
+
+        self.fds.remove(pos);
+        self.tokens.remove(pos);
+        self.write_tokens.remove(pos);
+        self.files.remove(pos);
+    }
 }
 
 impl Selector {
@@ -28,47 +228,517 @@ impl Selector {
         // offset by 1 to avoid choosing 0 as the id of a selector
         let id = NEXT_ID.fetch_add(1, Ordering::Relaxed) + 1;
 
-        Ok(Selector { id: id, events: Mutex::new(vec![]) })
+        Ok(Selector {
+            id: id,
+            registrations: Mutex::new(Registrations {
+                fds: vec![],
+                tokens: vec![],
+                write_tokens: vec![],
+                files: vec![],
+                urgent: 0,
+                children: vec![],
+                partitions: vec![],
+            }),
+            selecting: AtomicUsize::new(0),
+            strict: AtomicBool::new(true),
+            assert_open: AtomicBool::new(false),
+        })
+    }
+
+    /// `poll(2)` has no edge-triggered or oneshot mode, and its timeout is
+    /// given in milliseconds. Half-closed connections are only reported where
+    /// `POLLRDHUP` exists.
+    pub fn capabilities(&self) -> Capabilities {
+        let caps = Capabilities::hup() | Capabilities::priority() | Capabilities::concurrent_pollers();
+
+        if emulated_interests().is_empty() {
+            caps | Capabilities::read_closed()
+        } else {
+            caps
+        }
+    }
+
+    /// When set, which is the default, registering an fd with options or
+    /// interests `poll(2)` can only emulate fails instead of falling back to
+    /// level-triggered notifications, see `emulated_interests`.
+    pub fn set_strict(&self, strict: bool) {
+        self.strict.store(strict, Ordering::SeqCst);
+    }
+
+    /// When set, finding out that a registered fd was closed without being
+    /// deregistered panics instead of dropping the registration.
+    pub fn set_assert_open(&self, assert_open: bool) {
+        self.assert_open.store(assert_open, Ordering::SeqCst);
+    }
+
+    /// Panics if a registered fd no longer refers to the file it was
+    /// registered with, that is it was closed and its number reused.
+    fn check_open(&self) {
+        let stale = {
+            let registrations = self.registrations.lock().unwrap();
+
+            registrations.fds.iter()
+                .zip(&registrations.files)
+                .zip(&registrations.tokens)
+                .find(|&((pollfd, file), _)| *file != file_id(pollfd.fd))
+                .map(|((pollfd, _), token)| (pollfd.fd, *token))
+        };
+
+        if let Some((fd, token)) = stale {
+            panic_closed(fd, token);
+        }
+    }
+
+    fn check_supported(&self, interests: Ready, opts: PollOpt) -> io::Result<()> {
+        let supported = Ready::readable() | Ready::writable() | UnixReady::error() |
+            UnixReady::hup() | UnixReady::priority() | UnixReady::invalid() |
+            UnixReady::read_closed() | UnixReady::write_closed();
+
+        let emulated = opts.is_edge() || opts.is_oneshot() ||
+            !(interests & emulated_interests()).is_empty();
+
+        if !supported.contains(interests) || (emulated && self.strict.load(Ordering::SeqCst)) {
+            return Err(RegisterError::Unsupported { interest: interests, opts: opts }.into());
+        }
+
+        Ok(())
     }
 
     pub fn id(&self) -> usize {
         self.id
     }
 
-    /// Wait for events from the OS
-    pub fn select(
+    /// Returns true while a thread is blocked in `poll(2)` on the registered
+    /// fds. Changes to the registrations are only picked up by the next call
+    /// to `select`, so the caller has to wake that thread up.
+    pub fn is_selecting(&self) -> bool {
+        self.selecting.load(Ordering::SeqCst) != 0
+    }
+
+    /// Split the registrations into `count` partitions, each polled by one
+    /// thread calling `select_partition`. Only called while no thread is
+    /// polling; a count of 1 goes back to polling all fds in `select`.
+    pub fn set_partitions(&self, count: usize) -> io::Result<()> {
+        let mut partitions = vec![];
+
+        if count > 1 {
+            for _ in 0..count {
+                partitions.push(Partition {
+                    awakener: Awakener::new()?,
+                    selecting: 0,
+                });
+            }
+        }
+
+        self.registrations.lock().unwrap().partitions = partitions;
+
+        Ok(())
+    }
+
+    /// Separates the selector of a forked child from the one of its parent.
+    ///
+    /// The registrations are in the memory of the process, so the child
+    /// keeps polling the fds it inherited. The awakeners however are sockets
+    /// shared with the parent, each process would consume the wakeups of the
+    /// other. `awakener` and the partition awakeners are given new sockets
+    /// under the same fds. Threads of the parent blocked in `poll(2)` do not
+    /// exist in the child, they are no longer counted as selecting.
+    ///
+    /// Fails instead of blocking if a thread of the parent held the lock on
+    /// the registrations at the time of the fork: that thread does not exist
+    /// in the child, so the lock is never released.
+    pub fn after_fork(&self, awakener: &Awakener) -> io::Result<()> {
+        let mut registrations = match self.registrations.try_lock() {
+            Ok(registrations) => registrations,
+            Err(TryLockError::WouldBlock) => {
+                return Err(io::Error::new(io::ErrorKind::Other,
+                                          "selector in use by another thread at the time of the fork"));
+            }
+            Err(TryLockError::Poisoned(_)) => {
+                return Err(io::Error::new(io::ErrorKind::Other,
+                                          "selector poisoned by a panicking thread"));
+            }
+        };
+        let mut reset = vec![awakener.as_raw_fd()];
+
+        awakener.reset()?;
+
+        for partition in &mut registrations.partitions {
+            partition.awakener.reset()?;
+            partition.selecting = 0;
+            reset.push(partition.awakener.as_raw_fd());
+        }
+
+        // The reset awakeners refer to new files
+        for fd in reset {
+            if let Some(pos) = registrations.position(fd) {
+                registrations.files[pos] = file_id(fd);
+            }
+        }
+
+        self.selecting.store(0, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    /// Wakes up the thread blocked in `select_partition` on `partition`, or
+    /// the next one to call it.
+    pub fn wakeup_partition(&self, partition: usize) -> io::Result<()> {
+        let registrations = self.registrations.lock().unwrap();
+
+        match registrations.partitions.get(partition) {
+            Some(partition) => partition.awakener.wakeup(),
+            None => Ok(()),
+        }
+    }
+
+    /// Wakes up every thread blocked in `select_partition`
+    pub fn wakeup_partitions(&self) -> io::Result<()> {
+        let registrations = self.registrations.lock().unwrap();
+
+        for partition in &registrations.partitions {
+            partition.awakener.wakeup()?;
+        }
+
+        Ok(())
+    }
+
+    /// Wait for events from the OS. Events are pushed into `evts`.
+    pub fn select<S: Sink>(
+        &self,
+        evts: &mut S,
+        awakener: Token,
+        timeout: Option<Duration>,
+    ) -> io::Result<bool> {
+        self.select_in(evts, awakener, timeout, None)
+    }
+
+    /// Like `select`, but only waits for the fds of `partition`. The fd
+    /// registered with `awakener` is only polled if `with_awakener` is set.
+    pub fn select_partition<S: Sink>(
+        &self,
+        evts: &mut S,
+        awakener: Token,
+        timeout: Option<Duration>,
+        partition: usize,
+        with_awakener: bool,
+    ) -> io::Result<bool> {
+        let count = self.registrations.lock().unwrap().partitions.len();
+
+        if partition >= count {
+            return Err(io::Error::from_raw_os_error(libc::EINVAL));
+        }
+
+        self.select_in(evts, awakener, timeout, Some(Filter {
+            partition: partition,
+            count: count,
+            awakener: awakener,
+            with_awakener: with_awakener,
+        }))
+    }
+
+    fn select_in<S: Sink>(
         &self,
-        evts: &mut Events,
+        evts: &mut S,
         awakener: Token,
         timeout: Option<Duration>,
+        filter: Option<Filter>,
     ) -> io::Result<bool> {
         let timeout_ms = timeout
             .map(|to| cmp::min(millis(to), i32::MAX as u64) as i32)
             .unwrap_or(-1);
 
-        evts.events.clear();
+        let mut snapshot = Snapshot {
+            fds: vec![],
+            origins: vec![],
+            nested: vec![],
+        };
+
+        if self.assert_open.load(Ordering::SeqCst) {
+            self.check_open();
+        }
+
+        // Counted before the snapshot is taken, so that a registration either
+        // makes it into the snapshot or sees `is_selecting`. Partitions are
+        // counted by `snapshot`.
+        if filter.is_none() {
+            self.selecting.fetch_add(1, Ordering::SeqCst);
+        }
 
-        let mut events = self.events.lock().unwrap();
+        self.snapshot(&mut snapshot, None, filter);
 
-        if unsafe { cvt(libc::poll(
-            events.as_mut_ptr(),
-            events.len() as u32,
+        let res = unsafe { cvt(libc::poll(
+            snapshot.fds.as_mut_ptr(),
+            snapshot.fds.len() as u32,
             timeout_ms,
-        ))? } != 0 {
-            for (i, event) in events.iter_mut().enumerate() {
-                if event.revents == 0 { continue; }
-
-                if i == awakener.into() {
-                    event.revents = 0;
-                    return Ok(true);
-                } else {
-                    evts.events.push(*event);
-                    event.revents = 0;
+        )) };
+
+        for selector in &snapshot.nested {
+            selector.selecting.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        let mut awoken = false;
+        let mut closed = vec![];
+        let mut ready_events = vec![];
+        let mut notified = false;
+
+        for (pollfd, origin) in snapshot.fds.iter().zip(&snapshot.origins) {
+            if pollfd.revents == 0 { continue; }
+
+            match *origin {
+                Origin::Own(token, write_token, file, invalid) => {
+                    if token == awakener {
+                        awoken = true;
+                    } else if (pollfd.revents & POLLNVAL) != 0 {
+                        // The fd was closed without being deregistered
+                        let mut events = vec![];
+
+                        if invalid {
+                            push_events(&mut events, pollfd.revents, token, write_token);
+                        }
+
+                        closed.push((pollfd.fd, file, token, events));
+                    } else {
+                        push_events(&mut ready_events, pollfd.revents, token, write_token);
+                    }
+                }
+                Origin::Nested(token, interests) => {
+                    // Closed fds are dropped by the nested selector itself
+                    if pollfd.revents == POLLNVAL || !interests.is_readable() {
+                        continue;
+                    }
+
+                    if !ready_events.iter().any(|e| e.token() == token) {
+                        ready_events.push(Event::new(Ready::readable(), token));
+                    }
+                }
+                Origin::Partition => notified = true,
+            }
+        }
+
+        match filter {
+            None => {
+                self.selecting.fetch_sub(1, Ordering::SeqCst);
+            }
+            Some(filter) => {
+                let mut registrations = self.registrations.lock().unwrap();
+
+                // The partitions are only changed while no thread is polling
+                let partition = &mut registrations.partitions[filter.partition];
+                partition.selecting -= 1;
+
+                if notified {
+                    partition.awakener.cleanup();
+                }
+            }
+        }
+
+        res?;
+
+        if !closed.is_empty() {
+            let registrations = self.registrations.lock().unwrap();
+
+            // Unless the fd was deregistered, or registered again, in the
+            // meantime
+            closed.retain(|&(fd, file, _, _)| {
+                registrations.position(fd)
+                    .map(|pos| registrations.files[pos] == file)
+                    .unwrap_or(false)
+            });
+        }
+
+        if let Some(&(fd, _, token, _)) = closed.first() {
+            if self.assert_open.load(Ordering::SeqCst) {
+                panic_closed(fd, token);
+            }
+        }
+
+        for event in ready_events {
+            if evts.is_full() {
+                // Entries that do not fit are still ready the next time
+                // `poll(2)` is called.
+                evts.set_truncated();
+                break;
+            }
+
+            evts.push_event(event);
+        }
+
+        let mut dropped = vec![];
+
+        'closed: for &(fd, file, _, ref events) in &closed {
+            for &event in events {
+                if evts.is_full() {
+                    // Reported by the next call instead
+                    evts.set_truncated();
+                    continue 'closed;
+                }
+
+                evts.push_event(event);
+            }
+
+            dropped.push((fd, file));
+        }
+
+        if !dropped.is_empty() {
+            let mut registrations = self.registrations.lock().unwrap();
+
+            for (fd, file) in dropped {
+                if let Some(pos) = registrations.position(fd) {
+                    if registrations.files[pos] == file {
+                        registrations.remove(pos);
+                    }
+                }
+            }
+        }
+
+        Ok(awoken)
+    }
+
+    /// Copies the fds of this selector, and of the ones nested in it, into
+    /// `snapshot`. The entries of nested selectors are reported as `nested`.
+    /// Only the entries of the partition are copied if `filter` is set.
+    fn snapshot(&self, snapshot: &mut Snapshot, nested: Option<(Token, Ready)>, filter: Option<Filter>) {
+        let mut registrations = self.registrations.lock().unwrap();
+
+        for (pos, pollfd) in registrations.fds.iter().enumerate() {
+            let token = registrations.tokens[pos];
+
+            if let Some(filter) = filter {
+                if !filter.own(pollfd.fd, token) {
+                    continue;
+                }
+            }
+
+            // Only kept in the registrations, `POLLNVAL` is reported either way
+            let invalid = (pollfd.events & POLLNVAL) != 0;
+
+            snapshot.fds.push(libc::pollfd {
+                fd: pollfd.fd,
+                events: pollfd.events & !POLLNVAL,
+                revents: 0,
+            });
+            snapshot.origins.push(match nested {
+                Some((token, interests)) => Origin::Nested(token, interests),
+                None => Origin::Own(token, registrations.write_tokens[pos], registrations.files[pos], invalid),
+            });
+        }
+
+        if let Some(filter) = filter {
+            let partition = &mut registrations.partitions[filter.partition];
+            partition.selecting += 1;
+
+            snapshot.fds.push(libc::pollfd {
+                fd: partition.awakener.as_raw_fd(),
+                events: POLLIN,
+                revents: 0,
+            });
+            snapshot.origins.push(Origin::Partition);
+        }
+
+        // Forget the selectors of dropped `Poll` instances
+        registrations.children.retain(|child| child.selector.upgrade().is_some());
+
+        for child in &registrations.children {
+            if let Some(filter) = filter {
+                if !filter.child(child.id) {
+                    continue;
                 }
             }
+
+            if let Some(selector) = child.selector.upgrade() {
+                selector.selecting.fetch_add(1, Ordering::SeqCst);
+
+                // Selectors nested further down are reported as the child
+                let origin = nested.unwrap_or((child.token, child.interests));
+                selector.snapshot(snapshot, Some(origin), None);
+
+                snapshot.nested.push(selector);
+            }
+        }
+    }
+
+    /// Returns true if the selector with the given id is nested in this one,
+    /// directly or not.
+    fn contains(&self, id: usize) -> bool {
+        let registrations = self.registrations.lock().unwrap();
+
+        registrations.children.iter().any(|child| {
+            child.id == id || child.selector.upgrade().map(|s| s.contains(id)).unwrap_or(false)
+        })
+    }
+
+    /// Nest `child` in this selector. Any fd registered with `child` being
+    /// ready is reported as a readable event for `token`.
+    pub fn register_selector(
+        &self,
+        child: &Arc<Selector>,
+        token: Token,
+        interests: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        self.check_supported(interests, opts)?;
+
+        let _nesting = NESTING.lock().unwrap();
+
+        if child.id == self.id || child.contains(self.id) {
+            // Would make the selectors poll each other
+            return Err(RegisterError::NestingCycle.into());
+        }
+
+        let mut registrations = self.registrations.lock().unwrap();
+
+        if registrations.children.iter().any(|c| c.id == child.id) {
+            return Err(RegisterError::AlreadyRegistered.into());
+        }
+
+        registrations.children.push(Child {
+            id: child.id,
+            selector: Arc::downgrade(child),
+            token: token,
+            interests: interests,
+        });
+
+        registrations.notify(child.id);
+
+        Ok(())
+    }
+
+    /// Update the token and interests `child` is nested with
+    pub fn reregister_selector(
+        &self,
+        child: &Selector,
+        token: Token,
+        interests: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        self.check_supported(interests, opts)?;
+
+        let mut registrations = self.registrations.lock().unwrap();
+
+        match registrations.children.iter_mut().find(|c| c.id == child.id) {
+            Some(c) => {
+                c.token = token;
+                c.interests = interests;
+                registrations.notify(child.id);
+                Ok(())
+            }
+            None => Err(RegisterError::NotRegistered.into()),
         }
+    }
 
-        Ok(false)
+    /// Stop polling the fds of `child`
+    pub fn deregister_selector(&self, child: &Selector) -> io::Result<Token> {
+        let mut registrations = self.registrations.lock().unwrap();
+
+        match registrations.children.iter().position(|c| c.id == child.id) {
+            Some(pos) => {
+                let removed = registrations.children.remove(pos);
+                registrations.notify(removed.id);
+                Ok(removed.token)
+            }
+            None => Err(RegisterError::NotRegistered.into()),
+        }
     }
 
     /// Register event interests for the given IO handle with the OS
@@ -79,13 +749,51 @@ impl Selector {
         interests: Ready,
         opts: PollOpt,
     ) -> io::Result<()> {
-        let info = libc::pollfd {
-            fd: usize::from(token) as i32,
-            events: ready_to_poll(interests, opts),
-            revents: 0,
-        };
+        self.add(fd, token, None, interests, opts)
+    }
+
+    /// Register the fd for readable readiness reported with `read_token`, and
+    /// writable readiness reported with `write_token`
+    pub fn register_split(
+        &self,
+        fd: RawFd,
+        read_token: Token,
+        write_token: Token,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        let interests = Ready::readable() | Ready::writable();
+        self.add(fd, read_token, Some(write_token), interests, opts)
+    }
+
+    fn add(
+        &self,
+        fd: RawFd,
+        token: Token,
+        write_token: Option<Token>,
+        interests: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        self.check_supported(interests, opts)?;
+
+        let mut registrations = self.registrations.lock().unwrap();
+
+        if let Some(pos) = registrations.position(fd) {
+            if registrations.files[pos] == file_id(fd) {
+                return Err(RegisterError::AlreadyRegistered.into());
+            }
+
+            // Left behind by a closed fd with the same number
+            if self.assert_open.load(Ordering::SeqCst) {
+                let token = registrations.tokens[pos];
+                drop(registrations);
+                panic_closed(fd, token);
+            }
+
+            registrations.remove(pos);
+        }
 
-        self.events.lock().unwrap().push(info);
+        registrations.insert(fd, token, write_token, interests, opts);
+        registrations.notify(fd as usize);
 
         Ok(())
     }
@@ -98,24 +806,151 @@ impl Selector {
         interests: Ready,
         opts: PollOpt,
     ) -> io::Result<()> {
-        let info = libc::pollfd {
-            fd: fd as i32,
-            events: ready_to_poll(interests, opts),
-            revents: 0,
+        self.update(fd, token, None, interests, opts)
+    }
+
+    /// Update the registration of the fd, with readable readiness reported
+    /// with `read_token` and writable readiness with `write_token`
+    pub fn reregister_split(
+        &self,
+        fd: RawFd,
+        read_token: Token,
+        write_token: Token,
+        interests: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        self.update(fd, read_token, Some(write_token), interests, opts)
+    }
+
+    fn update(
+        &self,
+        fd: RawFd,
+        token: Token,
+        write_token: Option<Token>,
+        interests: Ready,
+        opts: PollOpt,
+    ) -> io::Result<()> {
+        self.check_supported(interests, opts)?;
+
+        let mut registrations = self.registrations.lock().unwrap();
+
+        let pos = match registrations.position(fd) {
+            Some(pos) => pos,
+            None => return Err(RegisterError::NotRegistered.into()),
         };
 
-        self.events.lock().unwrap()[usize::from(token)] = info;
+        if (pos < registrations.urgent) != opts.is_urgent() {
+            // Move the entry to the other end of the set
+            registrations.remove(pos);
+            registrations.insert(fd, token, write_token, interests, opts);
+        } else {
+            registrations.fds[pos].events = ready_to_poll(interests, opts);
+            registrations.tokens[pos] = token;
+            registrations.write_tokens[pos] = write_token;
+        }
+
+        registrations.notify(fd as usize);
 
         Ok(())
     }
 
-    /// Deregister event interests for the given IO handle with the OS
-    pub fn deregister(&self, fd: RawFd) -> io::Result<()> {
-        self.events.lock().unwrap().retain(|e| e.fd != fd);
-        Ok(())
+    /// Deregister event interests for the given IO handle with the OS.
+    /// Returns the token the fd was registered with, and its write token if
+    /// the registration was split.
+    pub fn deregister(&self, fd: RawFd) -> io::Result<(Token, Option<Token>)> {
+        let mut registrations = self.registrations.lock().unwrap();
+
+        let pos = match registrations.position(fd) {
+            Some(pos) => pos,
+            None => return Err(RegisterError::NotRegistered.into()),
+        };
+
+        let tokens = (registrations.tokens[pos], registrations.write_tokens[pos]);
+
+        registrations.remove(pos);
+        registrations.notify(fd as usize);
+
+        Ok(tokens)
+    }
+
+    /// Move the fd to `to`, registered with `token`. If `to` rejects the
+    /// registration, the fd is put back with the tokens and interests it had
+    /// in `self`. Returns the tokens the fd was registered with, like
+    /// `deregister`.
+    pub fn transfer(
+        &self,
+        fd: RawFd,
+        to: &Selector,
+        token: Token,
+        interests: Ready,
+        opts: PollOpt,
+    ) -> io::Result<(Token, Option<Token>)> {
+        let mut registrations = self.registrations.lock().unwrap();
+
+        let pos = match registrations.position(fd) {
+            Some(pos) => pos,
+            None => return Err(RegisterError::NotRegistered.into()),
+        };
+
+        let removed = registrations.take(pos);
+        registrations.notify(fd as usize);
+        drop(registrations);
+
+        if let Err(e) = to.register(fd, token, interests, opts) {
+            let mut registrations = self.registrations.lock().unwrap();
+            registrations.restore(removed);
+            registrations.notify(fd as usize);
+            return Err(e);
+        }
+
+        Ok((removed.token, removed.write_token))
+    }
+}
+
+/// Interests `poll(2)` only approximates. Without `POLLRDHUP`, a connection
+/// the peer shut down for writing is only reported once it fully hangs up.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn emulated_interests() -> Ready {
+    Ready::empty()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn emulated_interests() -> Ready {
+    UnixReady::read_closed().into()
+}
+
+/// `poll(2)` events reported only to the read token of a split registration
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const READ_EVENTS: i16 = POLLIN | POLLPRI | libc::POLLRDHUP;
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+const READ_EVENTS: i16 = POLLIN | POLLPRI;
+
+/// Pushes the events for the `revents` of an fd registered with `token`.
+/// Readiness of a split registration is reported with `token` for reading
+/// and `write_token` for writing, errors and hang ups with both.
+fn push_events(events: &mut Vec<Event>, revents: i16, token: Token, write_token: Option<Token>) {
+    let parts = match write_token {
+        Some(write_token) => [
+            (revents & !POLLOUT, token),
+            (revents & !READ_EVENTS, write_token),
+        ],
+        None => [(revents, token), (0, token)],
+    };
+
+    for &(revents, token) in &parts {
+        let ready = poll_to_ready(revents);
+
+        if !ready.is_empty() {
+            events.push(Event::new(ready, token));
+        }
     }
 }
 
+fn panic_closed(fd: RawFd, token: Token) -> ! {
+    panic!("fd {} registered with {:?} was closed without being deregistered", fd, token);
+}
+
 fn ready_to_poll(interest: Ready, opts: PollOpt) -> i16 {
     let mut kind = 0;
 
@@ -131,20 +966,120 @@ fn ready_to_poll(interest: Ready, opts: PollOpt) -> i16 {
         kind |= POLLPRI;
     }
 
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        if UnixReady::from(interest).is_read_closed() {
+            kind |= libc::POLLRDHUP;
+        }
+    }
+
+    // Never handed to `poll(2)`, see `Selector::snapshot`
+    if UnixReady::from(interest).is_invalid() {
+        kind |= POLLNVAL;
+    }
+
     kind as i16
 }
 
+fn poll_to_ready(revents: i16) -> Ready {
+    let mut kind = Ready::empty();
+
+    if (revents & POLLIN) != 0 {
+        kind = kind | Ready::readable();
+    }
+
+    if (revents & POLLPRI) != 0 {
+        kind = kind | Ready::readable() | UnixReady::priority();
+    }
+
+    if (revents & POLLOUT) != 0 {
+        kind = kind | Ready::writable();
+    }
+
+    // POLLERR - Usually means a socket error happened
+    if (revents & POLLERR) != 0 {
+        kind = kind | UnixReady::error();
+    }
+
+    if (revents & POLLHUP) != 0 {
+        kind = kind | UnixReady::hup() | UnixReady::read_closed() | UnixReady::write_closed();
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        if (revents & libc::POLLRDHUP) != 0 {
+            kind = kind | UnixReady::read_closed();
+        }
+    }
+
+    // Writing fails with the error of the socket. `POLLERR` shares the value
+    // of `POLLOUT` on Horizon.
+    #[cfg(not(target_os = "horizon"))]
+    {
+        if (revents & POLLOUT) != 0 && (revents & POLLERR) != 0 {
+            kind = kind | UnixReady::write_closed();
+        }
+    }
+
+    if (revents & POLLNVAL) != 0 {
+        kind = kind | UnixReady::invalid();
+    }
+
+    kind
+}
+
+/// Receives the events collected by `Selector::select` and the readiness
+/// queue of `Poll`.
+pub trait Sink {
+    /// The number of events received so far.
+    fn len(&self) -> usize;
+
+    /// Returns true once no more events should be pushed.
+    fn is_full(&self) -> bool;
+
+    fn push_event(&mut self, event: Event);
+
+    /// Moves the events starting at `from` to position `at`, keeping their
+    /// order. Sinks that do not store the events ignore this.
+    fn promote(&mut self, at: usize, from: usize);
+
+    /// Called when ready events were left behind because the sink is full.
+    fn set_truncated(&mut self);
+
+    /// Returns true if `push_event` runs user code, which may register
+    /// handles.
+    fn runs_user_code(&self) -> bool {
+        false
+    }
+}
+
 pub struct Events {
-    events: Vec<libc::pollfd>,
+    events: Vec<Event>,
+    // Set when events were left for the next call
+    truncated: bool,
+    // Capacity the vector may grow to when full
+    limit: usize,
 }
 
 impl Events {
     pub fn with_capacity(u: usize) -> Events {
         Events {
             events: Vec::with_capacity(u),
+            truncated: false,
+            limit: 0,
         }
     }
 
+    /// Allows the events vector to grow up to `limit` entries once full.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+    }
+
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
     #[inline]
     pub fn len(&self) -> usize {
         self.events.len()
@@ -162,53 +1097,48 @@ impl Events {
 
     #[inline]
     pub fn get(&self, idx: usize) -> Option<Event> {
-        self.events.get(idx).map(|event| {
-            let epoll = event.revents;
-            let mut kind = Ready::empty();
-
-            if (epoll & POLLIN) != 0 {
-                kind = kind | Ready::readable();
-            }
-
-            if (epoll & POLLPRI) != 0 {
-                kind = kind | Ready::readable() | UnixReady::priority();
-            }
+        self.events.get(idx).cloned()
+    }
 
-            if (epoll & POLLOUT) != 0 {
-                kind = kind | Ready::writable();
-            }
+    pub fn push_event(&mut self, event: Event) {
+        self.events.push(event);
+    }
 
-            // EPOLLHUP - Usually means a socket error happened
-            if (epoll & POLLERR) != 0 {
-                kind = kind | UnixReady::error();
-            }
+    pub fn clear(&mut self) {
+        self.events.clear();
+        self.truncated = false;
+    }
+}
 
-            if (epoll & POLLHUP) != 0 {
-                kind = kind | UnixReady::hup();
-            }
+impl Sink for Events {
+    fn len(&self) -> usize {
+        Events::len(self)
+    }
 
-            Event::new(kind, Token(idx))
-        })
+    fn is_full(&self) -> bool {
+        let len = self.events.len();
+        len == self.events.capacity() && len >= self.limit
     }
 
-    pub fn push_event(&mut self, event: Event) {
-        if self.events.len() < event.token().into() {
-            self.events.resize(event.token().into(), libc::pollfd {
-                fd: 0,
-                events: 0,
-                revents: 0,
-            });
+    fn push_event(&mut self, event: Event) {
+        let len = self.events.len();
+
+        if len == self.events.capacity() {
+            // Double the capacity, without going over the limit
+            let additional = cmp::max(len, 1);
+            self.events.reserve_exact(cmp::min(additional, self.limit - len));
         }
 
-        self.events[usize::from(event.token())] = libc::pollfd {
-            fd: 0,
-            events: ready_to_poll(event.readiness(), PollOpt::empty()),
-            revents: ready_to_poll(event.readiness(), PollOpt::empty()),
-        };
+        Events::push_event(self, event)
     }
 
-    pub fn clear(&mut self) {
-        self.events.clear();
+    fn promote(&mut self, at: usize, from: usize) {
+        let n = self.events.len() - from;
+        self.events[at..].rotate_right(n);
+    }
+
+    fn set_truncated(&mut self) {
+        self.truncated = true;
     }
 }
 
@@ -78,7 +78,7 @@ use std::fmt;
 /// poll.register(&socket,
 ///               Token(0),
 ///               Ready::readable() | UnixReady::error(),
-///               PollOpt::edge())?;
+///               PollOpt::level())?;
 /// #     Ok(())
 /// # }
 /// #
@@ -109,6 +109,10 @@ const LIO: usize   = 0b100000;
 #[cfg(not(any(target_os = "freebsd")))]
 const LIO: usize   = 0b000000;
 
+const INVALID: usize = 0b001000000;
+const READ_CLOSED: usize = 0b010000000;
+const WRITE_CLOSED: usize = 0b100000000;
+
 
 #[cfg(any(target_os = "linux", target_os = "android", target_os = "solaris"))]
 const PRI: usize = ::libc::EPOLLPRI as usize;
@@ -118,7 +122,7 @@ const PRI: usize = ::libc::POLLPRI as usize;
 
 
 // Export to support `Ready::all`
-pub const READY_ALL: usize = ERROR | HUP | AIO | LIO;
+pub const READY_ALL: usize = ERROR | HUP | AIO | LIO | INVALID | READ_CLOSED | WRITE_CLOSED;
 
 impl UnixReady {
     /// Returns a `Ready` representing AIO completion readiness
@@ -206,6 +210,88 @@ impl UnixReady {
         UnixReady(ready_from_usize(HUP))
     }
 
+    /// Returns a `Ready` representing invalid fd readiness.
+    ///
+    /// Reported along with the token of a handle whose file descriptor was
+    /// closed without being deregistered first, if the handle was registered
+    /// with interest in it. The registration is dropped once the event is
+    /// returned, or right away without the interest. Only the poll(2)
+    /// selector reports it, other selectors forget about closed file
+    /// descriptors on their own.
+    ///
+    /// See [`Poll`] for more documentation on polling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mio::unix::UnixReady;
+    ///
+    /// let ready = UnixReady::invalid();
+    ///
+    /// assert!(ready.is_invalid());
+    /// ```
+    ///
+    /// [`Poll`]: ../struct.Poll.html
+    #[inline]
+    pub fn invalid() -> UnixReady {
+        UnixReady(ready_from_usize(INVALID))
+    }
+
+    /// Returns a `Ready` representing read closed readiness.
+    ///
+    /// Signifies that the peer of a stream socket shut down the writing half
+    /// of the connection, or closed it, so that reading only returns the data
+    /// left in the buffer. Writing may still be possible, unlike after
+    /// [`write_closed`] readiness.
+    ///
+    /// Registering interest in it asks the selector to report half-closed
+    /// connections. Where the selector cannot tell them apart, see
+    /// [`Capabilities::read_closed`], the registration fails unless strict
+    /// registration is disabled, and then only a full hang up is reported.
+    ///
+    /// See [`Poll`] for more documentation on polling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mio::unix::UnixReady;
+    ///
+    /// let ready = UnixReady::read_closed();
+    ///
+    /// assert!(ready.is_read_closed());
+    /// ```
+    ///
+    /// [`write_closed`]: #method.write_closed
+    /// [`Capabilities::read_closed`]: ../struct.Capabilities.html#method.read_closed
+    /// [`Poll`]: ../struct.Poll.html
+    #[inline]
+    pub fn read_closed() -> UnixReady {
+        UnixReady(ready_from_usize(READ_CLOSED))
+    }
+
+    /// Returns a `Ready` representing write closed readiness.
+    ///
+    /// Signifies that writing to the handle can no longer succeed, because the
+    /// connection was closed or the peer stopped reading.
+    ///
+    /// See [`Poll`] for more documentation on polling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mio::unix::UnixReady;
+    ///
+    /// let ready = UnixReady::write_closed();
+    ///
+    /// assert!(ready.is_write_closed());
+    /// ```
+    ///
+    /// [`Poll`]: ../struct.Poll.html
+    #[inline]
+    pub fn write_closed() -> UnixReady {
+        UnixReady(ready_from_usize(WRITE_CLOSED))
+    }
+
     /// Returns a `Ready` representing LIO completion readiness
     ///
     /// See [`Poll`] for more documentation on polling.
@@ -335,6 +421,66 @@ impl UnixReady {
         self.contains(ready_from_usize(HUP))
     }
 
+    /// Returns true if `Ready` contains invalid fd readiness
+    ///
+    /// See [`Poll`] for more documentation on polling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mio::unix::UnixReady;
+    ///
+    /// let ready = UnixReady::invalid();
+    ///
+    /// assert!(ready.is_invalid());
+    /// ```
+    ///
+    /// [`Poll`]: ../struct.Poll.html
+    #[inline]
+    pub fn is_invalid(&self) -> bool {
+        self.contains(ready_from_usize(INVALID))
+    }
+
+    /// Returns true if `Ready` contains read closed readiness
+    ///
+    /// See [`Poll`] for more documentation on polling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mio::unix::UnixReady;
+    ///
+    /// let ready = UnixReady::read_closed();
+    ///
+    /// assert!(ready.is_read_closed());
+    /// ```
+    ///
+    /// [`Poll`]: ../struct.Poll.html
+    #[inline]
+    pub fn is_read_closed(&self) -> bool {
+        self.contains(ready_from_usize(READ_CLOSED))
+    }
+
+    /// Returns true if `Ready` contains write closed readiness
+    ///
+    /// See [`Poll`] for more documentation on polling.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mio::unix::UnixReady;
+    ///
+    /// let ready = UnixReady::write_closed();
+    ///
+    /// assert!(ready.is_write_closed());
+    /// ```
+    ///
+    /// [`Poll`]: ../struct.Poll.html
+    #[inline]
+    pub fn is_write_closed(&self) -> bool {
+        self.contains(ready_from_usize(WRITE_CLOSED))
+    }
+
     /// Returns true if `Ready` contains LIO readiness
     ///
     /// See [`Poll`] for more documentation on polling.
@@ -461,6 +607,9 @@ impl fmt::Debug for UnixReady {
             (UnixReady::hup(), "Hup"),
             #[allow(deprecated)]
             (UnixReady::aio(), "Aio"),
+            (UnixReady::invalid(), "Invalid"),
+            (UnixReady::read_closed(), "ReadClosed"),
+            (UnixReady::write_closed(), "WriteClosed"),
             #[cfg(any(target_os = "linux",
                 target_os = "android", target_os = "solaris"))]
             (UnixReady::priority(), "Priority"),
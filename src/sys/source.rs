@@ -0,0 +1,237 @@
+use crate::{io, poll, Ready, PollOpt, RegisterError, Registry, Token};
+use crate::event::Evented;
+use super::EventedFd;
+use std::fmt;
+use std::io::{Read, Write};
+use std::mem::ManuallyDrop;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::ptr;
+use std::sync::Mutex;
+
+/// Owned value backed by a `RawFd`, deregistered when dropped.
+///
+/// Unlike [`EventedFd`], which borrows a `RawFd`, `Source` owns the value the
+/// fd belongs to, such as a serial port or a device file. It remembers the
+/// [`Registry`] and token it is registered with, and deregisters the fd before
+/// the value, and with it the fd, is dropped. The selector is therefore never
+/// left polling a closed fd.
+///
+/// A `Source` is registered with a single `Poll` instance at a time.
+/// Registering it again, or with another `Poll` instance, fails until it is
+/// deregistered or transferred.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn try_main() -> Result<(), Box<dyn Error>> {
+/// use mio::{Events, Poll, PollOpt, Ready, Token};
+/// use mio::unix::Source;
+///
+/// use std::io::Write;
+/// use std::os::unix::net::UnixStream;
+///
+/// let (reader, mut writer) = UnixStream::pair()?;
+/// reader.set_nonblocking(true)?;
+///
+/// let poll = Poll::new()?;
+/// let source = Source::new(reader);
+///
+/// poll.register(&source, Token(0), Ready::readable(), PollOpt::level())?;
+/// assert_eq!(source.token(), Some(Token(0)));
+///
+/// writer.write_all(b"hello")?;
+///
+/// let mut events = Events::with_capacity(16);
+/// poll.poll(&mut events, None)?;
+///
+/// // Deregistered, then closed
+/// drop(source);
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`EventedFd`]: struct.EventedFd.html
+/// [`Registry`]: ../struct.Registry.html
+pub struct Source<T: AsRawFd> {
+    inner: T,
+    // Registry and token `inner` is registered with
+    registered: Mutex<Option<(Registry, Token)>>,
+}
+
+impl<T: AsRawFd> Source<T> {
+    /// Wraps `inner`, which is not registered yet.
+    pub fn new(inner: T) -> Source<T> {
+        Source {
+            inner: inner,
+            registered: Mutex::new(None),
+        }
+    }
+
+    /// Returns a reference to the wrapped value.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped value.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Returns the token the source is registered with, if it is registered.
+    ///
+    /// For a source registered with [`Poll::register_split`], this is the
+    /// read token.
+    ///
+    /// [`Poll::register_split`]: ../struct.Poll.html#method.register_split
+    pub fn token(&self) -> Option<Token> {
+        self.registered.lock().unwrap().as_ref().map(|&(_, token)| token)
+    }
+
+    /// Deregisters the source, if it is registered, and returns the wrapped
+    /// value.
+    pub fn into_inner(self) -> T {
+        let mut this = ManuallyDrop::new(self);
+        this.release();
+
+        unsafe {
+            ptr::drop_in_place(&mut this.registered);
+            ptr::read(&this.inner)
+        }
+    }
+
+    fn release(&mut self) {
+        let registered = self.registered.get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+
+        if let Some((registry, _)) = registered {
+            // Nothing to do about a failure while dropping
+            let _ = registry.deregister(&EventedFd(&self.inner.as_raw_fd()));
+        }
+    }
+
+    fn register_with<F>(&self, registry: &Registry, token: Token, register: F) -> io::Result<()>
+        where F: FnOnce(&EventedFd) -> io::Result<()>
+    {
+        let mut registered = self.registered.lock().unwrap();
+
+        if let Some((ref current, _)) = *registered {
+            return Err(if same_poll(current, registry) {
+                RegisterError::AlreadyRegistered.into()
+            } else {
+                RegisterError::ForeignSelector.into()
+            });
+        }
+
+        register(&EventedFd(&self.inner.as_raw_fd()))?;
+        *registered = Some((registry.clone(), token));
+
+        Ok(())
+    }
+
+    fn check_registered(registered: &Option<(Registry, Token)>, registry: &Registry) -> io::Result<()> {
+        match *registered {
+            Some((ref current, _)) if same_poll(current, registry) => Ok(()),
+            Some(_) => Err(RegisterError::ForeignSelector.into()),
+            None => Err(RegisterError::NotRegistered.into()),
+        }
+    }
+}
+
+fn same_poll(a: &Registry, b: &Registry) -> bool {
+    poll::selector(a).id() == poll::selector(b).id()
+}
+
+impl<T: AsRawFd> Evented for Source<T> {
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.register_with(registry, token, |fd| fd.register(registry, token, interest, opts))
+    }
+
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        let mut registered = self.registered.lock().unwrap();
+
+        Source::<T>::check_registered(&registered, registry)?;
+        EventedFd(&self.inner.as_raw_fd()).reregister(registry, token, interest, opts)?;
+        *registered = Some((registry.clone(), token));
+
+        Ok(())
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        let mut registered = self.registered.lock().unwrap();
+
+        Source::<T>::check_registered(&registered, registry)?;
+        EventedFd(&self.inner.as_raw_fd()).deregister(registry)?;
+        *registered = None;
+
+        Ok(())
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        let mut registered = self.registered.lock().unwrap();
+
+        Source::<T>::check_registered(&registered, from)?;
+        // Left registered with `from` if `to` rejects it
+        EventedFd(&self.inner.as_raw_fd()).transfer(from, to, token, interest, opts)?;
+        *registered = Some((to.clone(), token));
+
+        Ok(())
+    }
+
+    fn register_split(&self, registry: &Registry, read_token: Token, write_token: Token, opts: PollOpt) -> io::Result<()> {
+        self.register_with(registry, read_token, |fd| fd.register_split(registry, read_token, write_token, opts))
+    }
+
+    fn reregister_split(&self, registry: &Registry, read_token: Token, write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        let mut registered = self.registered.lock().unwrap();
+
+        Source::<T>::check_registered(&registered, registry)?;
+        EventedFd(&self.inner.as_raw_fd()).reregister_split(registry, read_token, write_token, interest, opts)?;
+        *registered = Some((registry.clone(), read_token));
+
+        Ok(())
+    }
+}
+
+impl<T: AsRawFd> Drop for Source<T> {
+    fn drop(&mut self) {
+        // `inner`, and its fd, are only dropped after this
+        self.release();
+    }
+}
+
+impl<T: AsRawFd> AsRawFd for Source<T> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+impl<T: AsRawFd + Read> Read for Source<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<T: AsRawFd + Write> Write for Source<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<T: AsRawFd + fmt::Debug> fmt::Debug for Source<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Source")
+            .field("inner", &self.inner)
+            .field("token", &self.token())
+            .finish()
+    }
+}
@@ -8,7 +8,7 @@ use std::time::Duration;
 use net2::TcpStreamExt;
 use iovec::{IoVec, unix};
 
-use crate::{io, Ready, Poll, PollOpt, Token};
+use crate::{io, Ready, PollOpt, Registry, Token};
 use crate::event::Evented;
 
 use super::eventedfd::EventedFd;
@@ -22,6 +22,37 @@ pub struct TcpListener {
     inner: net::TcpListener,
 }
 
+/// Ask the kernel to defer the ephemeral port allocation of a socket bound
+/// with port 0 until `connect` is called.
+///
+/// Older kernels do not know about the option, in which case the port is
+/// allocated on `bind` as usual.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn set_bind_address_no_port(fd: RawFd) -> io::Result<()> {
+    use std::mem;
+
+    let val: libc::c_int = 1;
+
+    let res = unsafe {
+        libc::setsockopt(fd,
+                         libc::IPPROTO_IP,
+                         libc::IP_BIND_ADDRESS_NO_PORT,
+                         &val as *const libc::c_int as *const libc::c_void,
+                         mem::size_of::<libc::c_int>() as libc::socklen_t)
+    };
+
+    match super::cvt(res) {
+        Ok(..) => Ok(()),
+        Err(ref e) if e.raw_os_error() == Some(libc::ENOPROTOOPT) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn set_bind_address_no_port(_fd: RawFd) -> io::Result<()> {
+    Ok(())
+}
+
 impl TcpStream {
     pub fn connect(stream: net::TcpStream, addr: &SocketAddr) -> io::Result<TcpStream> {
         set_nonblock(stream.as_raw_fd())?;
@@ -179,18 +210,33 @@ impl<'a> Write for &'a TcpStream {
 }
 
 impl Evented for TcpStream {
-    fn register(&self, poll: &Poll, token: Token,
+    fn register(&self, registry: &Registry, token: Token,
                 interest: Ready, opts: PollOpt) -> io::Result<()> {
-        EventedFd(&self.as_raw_fd()).register(poll, token, interest, opts)
+        EventedFd(&self.as_raw_fd()).register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token,
+    fn reregister(&self, registry: &Registry, token: Token,
                   interest: Ready, opts: PollOpt) -> io::Result<()> {
-        EventedFd(&self.as_raw_fd()).reregister(poll, token, interest, opts)
+        EventedFd(&self.as_raw_fd()).reregister(registry, token, interest, opts)
+    }
+
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(registry)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        EventedFd(&self.as_raw_fd()).deregister(poll)
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token,
+                interest: Ready, opts: PollOpt) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).transfer(from, to, token, interest, opts)
+    }
+
+    fn register_split(&self, registry: &Registry, read_token: Token,
+                      write_token: Token, opts: PollOpt) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).register_split(registry, read_token, write_token, opts)
+    }
+
+    fn reregister_split(&self, registry: &Registry, read_token: Token,
+                        write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).reregister_split(registry, read_token, write_token, interest, opts)
     }
 }
 
@@ -268,18 +314,23 @@ impl TcpListener {
 }
 
 impl Evented for TcpListener {
-    fn register(&self, poll: &Poll, token: Token,
+    fn register(&self, registry: &Registry, token: Token,
                 interest: Ready, opts: PollOpt) -> io::Result<()> {
-        EventedFd(&self.as_raw_fd()).register(poll, token, interest, opts)
+        EventedFd(&self.as_raw_fd()).register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token,
+    fn reregister(&self, registry: &Registry, token: Token,
                   interest: Ready, opts: PollOpt) -> io::Result<()> {
-        EventedFd(&self.as_raw_fd()).reregister(poll, token, interest, opts)
+        EventedFd(&self.as_raw_fd()).reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        EventedFd(&self.as_raw_fd()).deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).deregister(registry)
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token,
+                interest: Ready, opts: PollOpt) -> io::Result<()> {
+        EventedFd(&self.as_raw_fd()).transfer(from, to, token, interest, opts)
     }
 }
 
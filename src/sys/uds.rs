@@ -6,7 +6,7 @@ use std::path::Path;
 
 use libc;
 
-use crate::{io, Ready, Poll, PollOpt, Token};
+use crate::{io, Ready, PollOpt, Registry, Token};
 use crate::event::Evented;
 use super::{cvt, Io};
 use super::io::{set_nonblock, set_cloexec};
@@ -222,16 +222,28 @@ impl Write for UnixSocket {
 }
 
 impl Evented for UnixSocket {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.io.register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.io.register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.io.reregister(poll, token, interest, opts)
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.io.reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.io.deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.io.deregister(registry)
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.io.transfer(from, to, token, interest, opts)
+    }
+
+    fn register_split(&self, registry: &Registry, read_token: Token, write_token: Token, opts: PollOpt) -> io::Result<()> {
+        self.io.register_split(registry, read_token, write_token, opts)
+    }
+
+    fn reregister_split(&self, registry: &Registry, read_token: Token, write_token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.io.reregister_split(registry, read_token, write_token, interest, opts)
     }
 }
 
@@ -2,7 +2,7 @@
 
 #![allow(deprecated, missing_debug_implementations)]
 
-use crate::{convert, io, Ready, Poll, PollOpt, Registration, SetReadiness, Token};
+use crate::{convert, io, Ready, PollOpt, Registration, Registry, SetReadiness, Token};
 use crate::event::Evented;
 use lazycell::LazyCell;
 use slab::Slab;
@@ -374,12 +374,13 @@ impl<T> Default for Timer<T> {
 }
 
 impl<T> Evented for Timer<T> {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
         if self.inner.borrow().is_some() {
             return Err(io::Error::new(io::ErrorKind::Other, "timer already registered"));
         }
 
-        let (registration, set_readiness) = Registration::new(poll, token, interest, opts);
+        let (registration, set_readiness) = Registration::new2();
+        registry.register(&registration, token, interest, opts)?;
         let wakeup_state = Arc::new(AtomicUsize::new(usize::MAX));
         let thread_handle = spawn_wakeup_thread(
             wakeup_state.clone(),
@@ -400,16 +401,16 @@ impl<T> Evented for Timer<T> {
         Ok(())
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
         match self.inner.borrow() {
-            Some(inner) => inner.registration.update(poll, token, interest, opts),
+            Some(inner) => registry.reregister(&inner.registration, token, interest, opts),
             None => Err(io::Error::new(io::ErrorKind::Other, "receiver not registered")),
         }
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
         match self.inner.borrow() {
-            Some(inner) => inner.registration.deregister(poll),
+            Some(inner) => registry.deregister(&inner.registration),
             None => Err(io::Error::new(io::ErrorKind::Other, "receiver not registered")),
         }
     }
@@ -1,3 +1,11 @@
+use std::mem;
+
+// A generational token is split in two halves: the low half is the index and
+// the high half is the generation.
+const INDEX_BITS: usize = mem::size_of::<usize>() * 4;
+const INDEX_MASK: usize = (1 << INDEX_BITS) - 1;
+const MAX_GENERATION: usize = usize::MAX >> INDEX_BITS;
+
 /// Associates readiness notifications with [`Evented`] handles.
 ///
 /// `Token` is a wrapper around `usize` and is used as an argument to
@@ -9,6 +17,8 @@
 ///
 /// Using `Token` to track which socket generated the notification. In this
 /// example, `HashMap` is used, but usually something like [`slab`] is better.
+/// [`TokenMap`] also makes sure that an event for a handle that is gone is not
+/// mistaken for an event of the handle reusing its token.
 ///
 /// ```
 /// # use std::error::Error;
@@ -43,7 +53,7 @@
 /// poll.register(&listener,
 ///               LISTENER,
 ///               Ready::readable(),
-///               PollOpt::edge())?;
+///               PollOpt::level())?;
 ///
 /// // Spawn a thread that will connect a bunch of sockets then close them
 /// let addr = listener.local_addr()?;
@@ -89,7 +99,7 @@
 ///                             poll.register(&socket,
 ///                                          token,
 ///                                          Ready::readable(),
-///                                          PollOpt::edge())?;
+///                                          PollOpt::level())?;
 ///
 ///                             // Store the socket
 ///                             sockets.insert(token, socket);
@@ -137,9 +147,65 @@
 /// [`Poll::register`]: struct.Poll.html#method.register
 /// [`Poll::reregister`]: struct.Poll.html#method.reregister
 /// [`slab`]: https://crates.io/crates/slab
+/// [`TokenMap`]: struct.TokenMap.html
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Token(pub usize);
 
+impl Token {
+    /// Creates a token out of an index and a generation counter.
+    ///
+    /// The index is stored in the low half of the token and the generation in
+    /// the high half. Bumping the generation whenever an index is reused for a
+    /// new handle lets [`Poll::set_drop_stale`] tell events of the old handle
+    /// apart from events of the new one. [`TokenMap`] hands out tokens built
+    /// this way.
+    ///
+    /// The generation wraps around once it no longer fits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` does not fit in half a `usize`, or is all ones, which
+    /// is reserved.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mio::Token;
+    ///
+    /// let token = Token::with_generation(5, 0);
+    /// let reused = token.next_generation();
+    ///
+    /// assert_eq!(reused.index(), 5);
+    /// assert_eq!(reused.generation(), 1);
+    /// assert!(token != reused);
+    /// ```
+    ///
+    /// [`Poll::set_drop_stale`]: struct.Poll.html#method.set_drop_stale
+    /// [`TokenMap`]: struct.TokenMap.html
+    pub fn with_generation(index: usize, generation: usize) -> Token {
+        assert!(index < INDEX_MASK, "token index out of range");
+        Token((generation & MAX_GENERATION) << INDEX_BITS | index)
+    }
+
+    /// Returns the index part of a token created by `with_generation`.
+    pub fn index(&self) -> usize {
+        self.0 & INDEX_MASK
+    }
+
+    /// Returns the generation part of a token created by `with_generation`.
+    ///
+    /// Plain tokens with a value that fits in half a `usize` are generation
+    /// `0`.
+    pub fn generation(&self) -> usize {
+        self.0 >> INDEX_BITS
+    }
+
+    /// Returns the token with the same index and the next generation.
+    pub fn next_generation(&self) -> Token {
+        Token::with_generation(self.index(), self.generation().wrapping_add(1))
+    }
+}
+
 impl From<usize> for Token {
     fn from(val: usize) -> Token {
         Token(val)
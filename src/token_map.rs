@@ -0,0 +1,204 @@
+use crate::Token;
+use std::{fmt, mem};
+
+/// Stores per-registration data, keyed by generational [`Token`]s.
+///
+/// Each call to [`insert`] returns a new `Token` to register an [`Evented`]
+/// handle, or a [`Registration`], with. The token embeds the slot the value is
+/// stored in as well as a generation counter that is incremented every time
+/// the slot is freed, see [`Token::with_generation`]. A token therefore only
+/// resolves to the value it was handed out for: once the value is removed,
+/// events that still carry the old token are not mistaken for events of a
+/// value that reuses the slot.
+///
+/// # Examples
+///
+/// ```
+/// # use std::error::Error;
+/// # fn try_main() -> Result<(), Box<dyn Error>> {
+/// use mio::{Events, Poll, PollOpt, Ready, Registration, TokenMap};
+///
+/// let poll = Poll::new()?;
+/// let mut events = Events::with_capacity(16);
+/// let mut map = TokenMap::new();
+///
+/// let (registration, set_readiness) = Registration::new2();
+/// let token = map.insert("control");
+///
+/// poll.register(&registration, token, Ready::readable(), PollOpt::edge())?;
+/// set_readiness.set_readiness(Ready::readable())?;
+///
+/// poll.poll(&mut events, None)?;
+///
+/// for event in &events {
+///     assert_eq!(map.get(event.token()), Some(&"control"));
+/// }
+///
+/// // Once removed, the token is stale even if the slot is reused
+/// map.remove(token);
+/// map.insert("data");
+///
+/// assert!(map.get(token).is_none());
+/// #     Ok(())
+/// # }
+/// #
+/// # fn main() {
+/// #     try_main().unwrap();
+/// # }
+/// ```
+///
+/// [`Token`]: struct.Token.html
+/// [`Token::with_generation`]: struct.Token.html#method.with_generation
+/// [`insert`]: #method.insert
+/// [`Evented`]: event/trait.Evented.html
+/// [`Registration`]: struct.Registration.html
+pub struct TokenMap<T> {
+    slots: Vec<Slot<T>>,
+    // Head of the list of vacant slots, `slots.len()` if there are none
+    next_vacant: usize,
+    len: usize,
+}
+
+struct Slot<T> {
+    generation: usize,
+    entry: Entry<T>,
+}
+
+enum Entry<T> {
+    Occupied(T),
+    // Index of the next vacant slot
+    Vacant(usize),
+}
+
+impl<T> TokenMap<T> {
+    /// Creates an empty `TokenMap`.
+    pub fn new() -> TokenMap<T> {
+        TokenMap::with_capacity(0)
+    }
+
+    /// Creates an empty `TokenMap` with room for `capacity` values.
+    pub fn with_capacity(capacity: usize) -> TokenMap<T> {
+        TokenMap {
+            slots: Vec::with_capacity(capacity),
+            next_vacant: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the number of values in the map.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Stores `value` and returns the token it can be looked up with.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the map is full, which takes more than `2^32 - 1` values on
+    /// 64 bit platforms and `2^16 - 1` values on 32 bit platforms.
+    pub fn insert(&mut self, value: T) -> Token {
+        let index = self.next_vacant;
+        let generation = self.slots.get(index).map(|slot| slot.generation).unwrap_or(0);
+
+        // Panics once the indices run out, before the map is modified
+        let token = Token::with_generation(index, generation);
+
+        if index == self.slots.len() {
+            self.slots.push(Slot {
+                generation: generation,
+                entry: Entry::Occupied(value),
+            });
+            self.next_vacant += 1;
+        } else {
+            let slot = &mut self.slots[index];
+
+            self.next_vacant = match mem::replace(&mut slot.entry, Entry::Occupied(value)) {
+                Entry::Vacant(next) => next,
+                Entry::Occupied(..) => unreachable!(),
+            };
+        }
+
+        self.len += 1;
+        token
+    }
+
+    /// Returns `true` if `token` refers to a value in the map.
+    pub fn contains(&self, token: Token) -> bool {
+        self.get(token).is_some()
+    }
+
+    /// Returns a reference to the value of `token`, or `None` if the value was
+    /// removed.
+    pub fn get(&self, token: Token) -> Option<&T> {
+        match self.slots.get(token.index()) {
+            Some(&Slot { generation, entry: Entry::Occupied(ref value) }) if generation == token.generation() => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the value of `token`, or `None` if the
+    /// value was removed.
+    pub fn get_mut(&mut self, token: Token) -> Option<&mut T> {
+        match self.slots.get_mut(token.index()) {
+            Some(&mut Slot { generation, entry: Entry::Occupied(ref mut value) }) if generation == token.generation() => {
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Removes the value of `token` from the map and returns it.
+    ///
+    /// `token`, and any copy of it, no longer refers to a value after this
+    /// call, even once the slot is reused by `insert`.
+    pub fn remove(&mut self, token: Token) -> Option<T> {
+        if !self.contains(token) {
+            return None;
+        }
+
+        let index = token.index();
+        let slot = &mut self.slots[index];
+
+        slot.generation = token.next_generation().generation();
+        self.len -= 1;
+
+        match mem::replace(&mut slot.entry, Entry::Vacant(self.next_vacant)) {
+            Entry::Occupied(value) => {
+                self.next_vacant = index;
+                Some(value)
+            }
+            Entry::Vacant(..) => unreachable!(),
+        }
+    }
+
+    /// Returns an iterator over the tokens and values in the map.
+    pub fn iter(&self) -> impl Iterator<Item = (Token, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            match slot.entry {
+                Entry::Occupied(ref value) => Some((Token::with_generation(index, slot.generation), value)),
+                Entry::Vacant(..) => None,
+            }
+        })
+    }
+}
+
+impl<T> Default for TokenMap<T> {
+    fn default() -> TokenMap<T> {
+        TokenMap::new()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for TokenMap<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_map()
+            .entries(self.iter())
+            .finish()
+    }
+}
@@ -9,7 +9,7 @@
 
 #![allow(deprecated)]
 
-use crate::{sys, Ready, Poll, PollOpt, Token};
+use crate::{sys, Ready, PollOpt, Registry, Token};
 use crate::io::{self, MapNonBlock};
 use crate::event::Evented;
 use crate::poll::SelectorId;
@@ -278,17 +278,29 @@ impl UdpSocket {
 }
 
 impl Evented for UdpSocket {
-    fn register(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.selector_id.associate_selector(poll)?;
-        self.sys.register(poll, token, interest, opts)
+    fn register(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.selector_id.associate_selector(registry)?;
+        self.sys.register(registry, token, interest, opts)
     }
 
-    fn reregister(&self, poll: &Poll, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
-        self.sys.reregister(poll, token, interest, opts)
+    fn reregister(&self, registry: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.sys.reregister(registry, token, interest, opts)
     }
 
-    fn deregister(&self, poll: &Poll) -> io::Result<()> {
-        self.sys.deregister(poll)
+    fn deregister(&self, registry: &Registry) -> io::Result<()> {
+        self.sys.deregister(registry)
+    }
+
+    fn transfer(&self, from: &Registry, to: &Registry, token: Token, interest: Ready, opts: PollOpt) -> io::Result<()> {
+        self.selector_id.transfer(from, to)?;
+
+        if let Err(e) = self.sys.transfer(from, to, token, interest, opts) {
+            // Still registered with `from`
+            let _ = self.selector_id.transfer(to, from);
+            return Err(e);
+        }
+
+        Ok(())
     }
 }
 
@@ -7,22 +7,46 @@ extern crate fuchsia_zircon as zircon;
 
 pub use crate::ports::localhost;
 
+mod test_capabilities;
 mod test_custom_evented;
+mod test_close;
 mod test_close_on_drop;
+mod test_concurrent_poll;
+mod test_dns;
 mod test_double_register;
 mod test_echo_server;
+mod test_events_truncated;
+#[cfg(target_os = "linux")]
+mod test_fork;
+#[cfg(not(target_os = "horizon"))]
+mod test_interfaces;
+#[cfg(target_os = "linux")]
+mod test_listen_fds;
 mod test_local_addr_ready;
 mod test_multicast;
+mod test_nested_poll;
 mod test_oneshot;
 mod test_poll;
+mod test_poll_with;
+mod test_read_write_closed;
 mod test_register_deregister;
+mod test_register_error;
 mod test_register_multiple_event_loops;
+mod test_register_split;
+mod test_registration_pool;
+mod test_registry;
 mod test_reregister_without_poll;
+mod test_set_readiness_batch;
 mod test_smoke;
+mod test_socks5;
+mod test_source;
 mod test_tcp;
 mod test_tcp_level;
+mod test_token_map;
+mod test_transfer;
 mod test_udp_level;
 mod test_udp_socket;
+mod test_urgent;
 mod test_write_then_drop;
 
 #[cfg(feature = "with-deprecated")]
@@ -0,0 +1,83 @@
+use mio::{Capabilities, Events, Poll, PollOpt, Ready, RegisterError, Registration, Token};
+use mio::net::UdpSocket;
+use mio::unix::UnixReady;
+use std::time::Duration;
+
+#[test]
+pub fn test_capabilities() {
+    let poll = Poll::new().unwrap();
+    let caps = poll.capabilities();
+
+    // Reported by every backend mio runs on in this tree
+    assert!(caps.contains(Capabilities::hup() | Capabilities::concurrent_pollers()));
+    assert!(!caps.is_native_edge());
+    assert!(!caps.is_native_oneshot());
+    assert!(!caps.is_sub_millisecond_timeout());
+    assert_eq!(caps.is_read_closed(), cfg!(any(target_os = "linux", target_os = "android")));
+
+    assert_eq!("(empty)", format!("{:?}", Capabilities::empty()));
+    assert_eq!("Hup | Priority", format!("{:?}", Capabilities::hup() | Capabilities::priority()));
+}
+
+#[test]
+pub fn test_strict_registration() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let sender = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+
+    // Rejected by default
+    let err = poll.register(&sock, Token(0), Ready::readable(), PollOpt::edge()).unwrap_err();
+    assert_eq!(RegisterError::from_io_error(&err), Some(RegisterError::Unsupported {
+        interest: Ready::readable(),
+        opts: PollOpt::edge(),
+    }));
+
+    poll.register(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+    let opts = PollOpt::level() | PollOpt::oneshot();
+    let err = poll.reregister(&sock, Token(0), Ready::readable(), opts).unwrap_err();
+    assert_eq!(RegisterError::from_io_error(&err), Some(RegisterError::Unsupported {
+        interest: Ready::readable(),
+        opts: opts,
+    }));
+
+    // The failed reregistration left the socket registered
+    sender.send_to(b"hello", &sock.local_addr().unwrap()).unwrap();
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events.get(0).unwrap().token(), Token(0));
+
+    // Registrations support every option
+    let (r, _set) = Registration::new2();
+    poll.register(&r, Token(1), Ready::readable(), PollOpt::edge() | PollOpt::oneshot()).unwrap();
+
+    // Emulated once opted out
+    poll.set_strict_registration(false);
+    poll.reregister(&sock, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
+}
+
+#[test]
+pub fn test_read_closed_capability() {
+    let mut poll = Poll::new().unwrap();
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let interest = Ready::readable() | UnixReady::read_closed();
+
+    let res = poll.register(&sock, Token(0), interest, PollOpt::level());
+
+    if poll.capabilities().is_read_closed() {
+        res.unwrap();
+    } else {
+        let err = res.unwrap_err();
+        assert_eq!(RegisterError::from_io_error(&err), Some(RegisterError::Unsupported {
+            interest: interest,
+            opts: PollOpt::level(),
+        }));
+
+        // Only reported with a hang up once opted out
+        poll.set_strict_registration(false);
+        poll.register(&sock, Token(0), interest, PollOpt::level()).unwrap();
+    }
+}
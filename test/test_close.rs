@@ -0,0 +1,63 @@
+use mio::{Events, Poll, PollClosed, PollOpt, Ready, Registration, Token, Waker};
+use mio::net::UdpSocket;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+pub fn test_close_wakes_pollers() {
+    let poll = Arc::new(Poll::new().unwrap());
+
+    // One thread ends up in the selector, the others wait for their turn
+    let handles: Vec<_> = (0..3).map(|_| {
+        let poll = poll.clone();
+
+        thread::spawn(move || {
+            let mut events = Events::with_capacity(16);
+            poll.poll(&mut events, None)
+        })
+    }).collect();
+
+    thread::sleep(Duration::from_millis(100));
+    poll.close();
+
+    for handle in handles {
+        let err = handle.join().unwrap().unwrap_err();
+        assert!(PollClosed::matches(&err));
+    }
+
+    let mut events = Events::with_capacity(16);
+    let err = poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap_err();
+    assert!(PollClosed::matches(&err));
+}
+
+#[test]
+pub fn test_close_fails_registrations() {
+    let poll = Poll::new().unwrap();
+    let registry = poll.registry().clone();
+
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    poll.register(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+    let (r, set) = Registration::new2();
+    poll.register(&r, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
+
+    let waker = Waker::new(&poll, Token(2)).unwrap();
+
+    poll.close();
+
+    let sock2 = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let err = registry.register(&sock2, Token(3), Ready::readable(), PollOpt::level()).unwrap_err();
+    assert!(PollClosed::matches(&err));
+
+    let err = poll.reregister(&sock, Token(0), Ready::writable(), PollOpt::level()).unwrap_err();
+    assert!(PollClosed::matches(&err));
+
+    assert!(PollClosed::matches(&set.set_readiness(Ready::readable()).unwrap_err()));
+    assert!(PollClosed::matches(&waker.wake().unwrap_err()));
+    assert!(PollClosed::matches(&Waker::new(&poll, Token(4)).unwrap_err()));
+
+    // Handles can still be deregistered
+    poll.deregister(&sock).unwrap();
+    poll.deregister(&r).unwrap();
+}
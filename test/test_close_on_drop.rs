@@ -1,7 +1,18 @@
 use crate::{localhost, TryRead};
-use mio::{Events, Poll, PollOpt, Ready, Token};
+use mio::{Events, Poll, PollOpt, Ready, Registration, Token};
 use bytes::ByteBuf;
 use mio::net::{TcpListener, TcpStream};
+use mio::unix::UnixReady;
+use std::time::Duration;
+
+#[cfg(target_os = "linux")]
+use mio::net::UdpSocket;
+#[cfg(target_os = "linux")]
+use mio::unix::EventedFd;
+#[cfg(target_os = "linux")]
+use std::os::unix::io::{AsRawFd, RawFd};
+#[cfg(target_os = "linux")]
+use std::{cmp, mem};
 
 use self::TestState::{Initial, AfterRead};
 
@@ -61,7 +72,7 @@ impl TestHandler {
             }
             _ => panic!("received unknown token {:?}", tok)
         }
-        poll.reregister(&self.cli, CLIENT, Ready::readable(), PollOpt::edge()).unwrap();
+        poll.reregister(&self.cli, CLIENT, Ready::readable(), PollOpt::level()).unwrap();
     }
 
     fn handle_write(&mut self, poll: &mut Poll, tok: Token, _: Ready) {
@@ -69,7 +80,7 @@ impl TestHandler {
             SERVER => panic!("received writable for token 0"),
             CLIENT => {
                 debug!("client connected");
-                poll.reregister(&self.cli, CLIENT, Ready::readable(), PollOpt::edge()).unwrap();
+                poll.reregister(&self.cli, CLIENT, Ready::readable(), PollOpt::level()).unwrap();
             }
             _ => panic!("received unknown token {:?}", tok)
         }
@@ -82,18 +93,21 @@ pub fn test_close_on_drop() {
     debug!("Starting TEST_CLOSE_ON_DROP");
     let mut poll = Poll::new().unwrap();
 
+    // Every handle is deregistered or still open when polled
+    poll.set_debug_assertions(true);
+
     // The address to connect to - localhost + a unique port
     let addr = localhost();
 
     // == Create & setup server socket
     let srv = TcpListener::bind(&addr).unwrap();
 
-    poll.register(&srv, SERVER, Ready::readable(), PollOpt::edge()).unwrap();
+    poll.register(&srv, SERVER, Ready::readable(), PollOpt::level()).unwrap();
 
     // == Create & setup client socket
     let sock = TcpStream::connect(&addr).unwrap();
 
-    poll.register(&sock, CLIENT, Ready::writable(), PollOpt::edge()).unwrap();
+    poll.register(&sock, CLIENT, Ready::writable(), PollOpt::level()).unwrap();
 
     // == Create storage for events
     let mut events = Events::with_capacity(1024);
@@ -117,3 +131,139 @@ pub fn test_close_on_drop() {
     }
     assert!(handler.state == AfterRead, "actual={:?}", handler.state);
 }
+
+/// Socket fd duplicated to a number just below the limit on open fds, which
+/// the fds other tests open never reach. Each test picks its own `slot`, so
+/// that the number is not reused once closed. Closed on drop, unless `close`
+/// already closed it.
+#[cfg(target_os = "linux")]
+struct HighFd {
+    fd: Option<RawFd>,
+    _sock: UdpSocket,
+}
+
+#[cfg(target_os = "linux")]
+impl HighFd {
+    fn new(slot: RawFd) -> HighFd {
+        let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+
+        let target = fd_limit() - slot;
+        let fd = unsafe { libc::fcntl(sock.as_raw_fd(), libc::F_DUPFD_CLOEXEC, target) };
+        assert_eq!(fd, target);
+
+        HighFd { fd: Some(fd), _sock: sock }
+    }
+
+    fn raw(&self) -> RawFd {
+        self.fd.unwrap()
+    }
+
+    fn close(&mut self) {
+        if let Some(fd) = self.fd.take() {
+            unsafe { libc::close(fd); }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for HighFd {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// Soft limit on the number of open fds, capped to the default maximum of
+/// the kernel when unlimited.
+#[cfg(target_os = "linux")]
+fn fd_limit() -> RawFd {
+    let mut limit: libc::rlimit = unsafe { mem::zeroed() };
+    assert_eq!(unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) }, 0);
+    cmp::min(limit.rlim_cur, 1 << 20) as RawFd
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+pub fn test_close_without_deregister() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let mut fd = HighFd::new(1);
+    let interest = Ready::readable() | UnixReady::invalid();
+    poll.register(&EventedFd(&fd.raw()), Token(0), interest, PollOpt::level()).unwrap();
+
+    let mut fd2 = HighFd::new(2);
+    poll.register(&EventedFd(&fd2.raw()), Token(1), Ready::readable(), PollOpt::level()).unwrap();
+
+    fd.close();
+    fd2.close();
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+
+    // Only reported to the handle interested in it
+    assert_eq!(events.len(), 1);
+
+    let event = events.get(0).unwrap();
+    assert_eq!(event.token(), Token(0));
+    assert!(UnixReady::from(event.readiness()).is_invalid());
+
+    // Both registrations were dropped
+    poll.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+    assert!(events.is_empty());
+}
+
+#[test]
+pub fn test_registration_invalid_readiness() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (r, set) = Registration::new2();
+    let interest = Ready::readable() | UnixReady::invalid();
+    poll.register(&r, Token(0), interest, PollOpt::level()).unwrap();
+    set.set_readiness(UnixReady::invalid().into()).unwrap();
+
+    // Still level-triggered, and not made writable
+    for _ in 0..2 {
+        poll.poll(&mut events, Some(Duration::from_secs(1))).unwrap();
+        assert_eq!(events.len(), 1);
+
+        let ready = UnixReady::from(events.get(0).unwrap().readiness());
+        assert_eq!(ready, UnixReady::invalid());
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+#[should_panic(expected = "closed without being deregistered")]
+pub fn test_close_without_deregister_asserts() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    poll.set_debug_assertions(true);
+
+    let mut fd = HighFd::new(3);
+    poll.register(&EventedFd(&fd.raw()), Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+    fd.close();
+
+    let _ = poll.poll(&mut events, Some(Duration::from_secs(5)));
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+#[should_panic(expected = "closed without being deregistered")]
+pub fn test_reused_fd_asserts() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    poll.set_debug_assertions(true);
+
+    let fd = HighFd::new(4);
+    poll.register(&EventedFd(&fd.raw()), Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+    // The number now refers to another socket, still registered with the
+    // token of the closed one
+    let other = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    assert_eq!(unsafe { libc::dup2(other.as_raw_fd(), fd.raw()) }, fd.raw());
+
+    let _ = poll.poll(&mut events, Some(Duration::from_millis(100)));
+}
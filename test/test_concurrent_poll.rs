@@ -0,0 +1,165 @@
+use mio::{Events, Poll, PollClosed, PollOpt, Ready, Registration, Token, Waker};
+use mio::net::UdpSocket;
+use std::sync::{mpsc, Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const POLLERS: usize = 4;
+
+fn concurrent() -> Poll {
+    let mut poll = Poll::new().unwrap();
+    poll.set_concurrent_pollers(POLLERS).unwrap();
+    poll
+}
+
+#[test]
+pub fn test_concurrent_readiness_delivered_once() {
+    let poll = Arc::new(concurrent());
+    let done = Arc::new(AtomicBool::new(false));
+    let seen = Arc::new(Mutex::new(vec![]));
+
+    let regs: Vec<_> = (0..64).map(|i| {
+        let (r, set) = Registration::new2();
+        poll.register(&r, Token(i), Ready::readable(), PollOpt::edge()).unwrap();
+        (r, set)
+    }).collect();
+
+    let handles: Vec<_> = (0..POLLERS).map(|_| {
+        let poll = poll.clone();
+        let done = done.clone();
+        let seen = seen.clone();
+
+        thread::spawn(move || {
+            let mut events = Events::with_capacity(8);
+
+            while !done.load(Ordering::SeqCst) {
+                poll.poll(&mut events, Some(Duration::from_millis(50))).unwrap();
+                seen.lock().unwrap().extend(events.iter().map(|e| e.token().index()));
+            }
+        })
+    }).collect();
+
+    for (_, set) in &regs {
+        set.set_readiness(Ready::readable()).unwrap();
+    }
+
+    let start = Instant::now();
+
+    while seen.lock().unwrap().len() < regs.len() && start.elapsed() < Duration::from_secs(5) {
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    // Give the pollers a chance to see a node twice
+    thread::sleep(Duration::from_millis(100));
+    done.store(true, Ordering::SeqCst);
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let mut seen = seen.lock().unwrap().clone();
+    seen.sort();
+
+    assert_eq!(seen, (0..64).collect::<Vec<_>>());
+}
+
+#[test]
+pub fn test_concurrent_register_while_polling() {
+    let poll = Arc::new(concurrent());
+
+    let handles: Vec<_> = (0..POLLERS).map(|_| {
+        let poll = poll.clone();
+
+        thread::spawn(move || {
+            let mut events = Events::with_capacity(8);
+            poll.poll(&mut events, None).map(|_| events.iter().map(|e| e.token()).collect::<Vec<_>>())
+        })
+    }).collect();
+
+    thread::sleep(Duration::from_millis(100));
+
+    let sender = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+
+    sender.send_to(b"hello", &sock.local_addr().unwrap()).unwrap();
+    poll.register(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+    // The thread polling the partition of the socket picks it up
+    thread::sleep(Duration::from_millis(200));
+    poll.close();
+
+    let mut woken = 0;
+
+    for handle in handles {
+        match handle.join().unwrap() {
+            Ok(tokens) => {
+                assert_eq!(tokens, vec![Token(0)]);
+                woken += 1;
+            }
+            Err(e) => assert!(PollClosed::matches(&e)),
+        }
+    }
+
+    assert_eq!(woken, 1);
+}
+
+#[test]
+pub fn test_concurrent_close() {
+    let poll = Arc::new(concurrent());
+
+    // More threads than pollers, some wait for a partition to be freed
+    let handles: Vec<_> = (0..POLLERS + 2).map(|_| {
+        let poll = poll.clone();
+
+        thread::spawn(move || {
+            let mut events = Events::with_capacity(8);
+            poll.poll(&mut events, None)
+        })
+    }).collect();
+
+    thread::sleep(Duration::from_millis(100));
+    poll.close();
+
+    for handle in handles {
+        let err = handle.join().unwrap().unwrap_err();
+        assert!(PollClosed::matches(&err));
+    }
+}
+
+#[test]
+pub fn test_concurrent_drain_handoff() {
+    let mut poll = Poll::new().unwrap();
+    poll.set_concurrent_pollers(2).unwrap();
+
+    let poll = Arc::new(poll);
+    let waker = Waker::new(&poll, Token(0)).unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    let handles: Vec<_> = (0..2).map(|_| {
+        let poll = poll.clone();
+        let tx = tx.clone();
+
+        thread::spawn(move || {
+            let mut events = Events::with_capacity(8);
+            poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+            tx.send(events.iter().any(|e| e.token() == Token(0))).unwrap();
+
+            // Busy outside of poll while the other thread waits
+            thread::sleep(Duration::from_secs(1));
+        })
+    }).collect();
+
+    thread::sleep(Duration::from_millis(100));
+
+    waker.wake().unwrap();
+    assert!(rx.recv_timeout(Duration::from_secs(2)).unwrap());
+
+    // Reaches the thread still polling, not the one which drained last
+    waker.wake().unwrap();
+    assert!(rx.recv_timeout(Duration::from_millis(500)).unwrap());
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
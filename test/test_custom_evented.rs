@@ -8,7 +8,7 @@ fn smoke() {
     let mut events = Events::with_capacity(128);
 
     let (r, set) = Registration::new2();
-    r.register(&poll, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
+    r.register(poll.registry(), Token(0), Ready::readable(), PollOpt::edge()).unwrap();
 
     let n = poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
     assert_eq!(n, 0);
@@ -89,7 +89,7 @@ mod stress {
 
             let registrations: Vec<_> = (0..NUM_REGISTRATIONS).map(|i| {
                 let (r, s) = Registration::new2();
-                r.register(&poll, Token(i), Ready::readable(), PollOpt::edge()).unwrap();
+                r.register(poll.registry(), Token(i), Ready::readable(), PollOpt::edge()).unwrap();
                 (r, s)
             }).collect();
 
@@ -125,7 +125,7 @@ mod stress {
             while remaining.load(Acquire) > 0 {
                 // Set interest
                 for (i, &(ref r, _)) in registrations.iter().enumerate() {
-                    r.reregister(&poll, Token(i), Ready::writable(), PollOpt::edge()).unwrap();
+                    r.reregister(poll.registry(), Token(i), Ready::writable(), PollOpt::edge()).unwrap();
                 }
 
                 poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
@@ -137,7 +137,7 @@ mod stress {
                 // Update registration
                 // Set interest
                 for (i, &(ref r, _)) in registrations.iter().enumerate() {
-                    r.reregister(&poll, Token(i), Ready::readable(), PollOpt::edge()).unwrap();
+                    r.reregister(poll.registry(), Token(i), Ready::readable(), PollOpt::edge()).unwrap();
                 }
             }
 
@@ -192,7 +192,7 @@ mod stress {
         // Create entries
         for i in 0..ENTRIES {
             let (registration, set_readiness) = Registration::new2();
-            registration.register(&poll, Token(i), Ready::readable(), PollOpt::edge()).unwrap();
+            registration.register(poll.registry(), Token(i), Ready::readable(), PollOpt::edge()).unwrap();
 
             entries.push(Entry {
                 registration: registration,
@@ -350,7 +350,7 @@ fn drop_registration_from_non_main_thread() {
     const THREADS: usize = 8;
     const ITERS: usize = 50_000;
 
-    let mut poll = Poll::new().unwrap();
+    let poll = Poll::new().unwrap();
     let mut events = Events::with_capacity(1024);
     let mut senders = Vec::with_capacity(THREADS);
     let mut token_index = 0;
@@ -372,7 +372,7 @@ fn drop_registration_from_non_main_thread() {
     let mut index: usize = 0;
     for _ in 0..ITERS {
         let (registration, set_readiness) = Registration::new2();
-        registration.register(&mut poll, Token(token_index), Ready::readable(), PollOpt::edge()).unwrap();
+        registration.register(poll.registry(), Token(token_index), Ready::readable(), PollOpt::edge()).unwrap();
         let _ = senders[index].send((registration, set_readiness));
 
         token_index += 1;
@@ -381,7 +381,7 @@ fn drop_registration_from_non_main_thread() {
             index = 0;
 
             let (registration, set_readiness) = Registration::new2();
-            registration.register(&mut poll, Token(token_index), Ready::readable(), PollOpt::edge()).unwrap();
+            registration.register(poll.registry(), Token(token_index), Ready::readable(), PollOpt::edge()).unwrap();
             let _ = set_readiness.set_readiness(Ready::readable());
             drop(registration);
             drop(set_readiness);
@@ -0,0 +1,309 @@
+use mio::{Events, Poll, PollOpt, Ready, Token};
+use mio::dns::{Builder, Query, Resolver};
+use std::io::{self, Read, Write};
+use std::net::{self, IpAddr, SocketAddr};
+use std::thread;
+use std::time::Duration;
+
+const DNS: Token = Token(0);
+
+#[derive(Clone)]
+enum Reply {
+    Addrs(Vec<IpAddr>),
+    NxDomain,
+    Truncated,
+    Ignore,
+}
+
+/// Starts a stand-in name server listening for UDP and TCP on the same
+/// loopback port. `handler` is called with the queried name, the record type
+/// and whether the query came in over TCP.
+fn name_server<F>(handler: F) -> SocketAddr
+    where F: Fn(&str, u16, bool) -> Reply + Send + Sync + Clone + 'static
+{
+    let udp = net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = udp.local_addr().unwrap();
+    let tcp = net::TcpListener::bind(addr).unwrap();
+
+    let h = handler.clone();
+    thread::spawn(move || {
+        let mut buf = [0; 512];
+
+        loop {
+            let (n, from) = udp.recv_from(&mut buf).unwrap();
+            let (name, qtype) = question(&buf[..n]);
+
+            if let Some(msg) = answer(&buf[..n], h(&name, qtype, false)) {
+                udp.send_to(&msg, from).unwrap();
+            }
+        }
+    });
+
+    thread::spawn(move || {
+        for stream in tcp.incoming() {
+            let mut stream = stream.unwrap();
+            let mut len = [0; 2];
+            stream.read_exact(&mut len).unwrap();
+
+            let mut buf = vec![0; (len[0] as usize) << 8 | len[1] as usize];
+            stream.read_exact(&mut buf).unwrap();
+
+            let (name, qtype) = question(&buf);
+
+            if let Some(msg) = answer(&buf, handler(&name, qtype, true)) {
+                stream.write_all(&[(msg.len() >> 8) as u8, msg.len() as u8]).unwrap();
+                stream.write_all(&msg).unwrap();
+            }
+        }
+    });
+
+    addr
+}
+
+/// A name server address that never answers.
+fn silent_server() -> (net::UdpSocket, SocketAddr) {
+    let sock = net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = sock.local_addr().unwrap();
+    (sock, addr)
+}
+
+fn question(query: &[u8]) -> (String, u16) {
+    let mut labels = vec![];
+    let mut pos = 12;
+
+    while query[pos] != 0 {
+        let len = query[pos] as usize;
+        labels.push(String::from_utf8_lossy(&query[pos + 1..pos + 1 + len]).into_owned());
+        pos += 1 + len;
+    }
+
+    let qtype = (query[pos + 1] as u16) << 8 | query[pos + 2] as u16;
+    (labels.join("."), qtype)
+}
+
+fn answer(query: &[u8], reply: Reply) -> Option<Vec<u8>> {
+    let (_, qtype) = question(query);
+    let question_end = query.len();
+
+    let (flags, addrs) = match reply {
+        Reply::Addrs(addrs) => (0x8180, addrs),
+        Reply::NxDomain => (0x8183, vec![]),
+        Reply::Truncated => (0x8380, vec![]),
+        Reply::Ignore => return None,
+    };
+
+    let addrs: Vec<Vec<u8>> = addrs.into_iter()
+        .filter_map(|addr| match addr {
+            IpAddr::V4(a) if qtype == 1 => Some(a.octets().to_vec()),
+            IpAddr::V6(a) if qtype == 28 => Some(a.octets().to_vec()),
+            _ => None,
+        })
+        .collect();
+
+    let mut msg = query[..2].to_vec();
+    msg.extend_from_slice(&[(flags >> 8) as u8, flags as u8, 0, 1, 0, addrs.len() as u8, 0, 0, 0, 0]);
+    msg.extend_from_slice(&query[12..question_end]);
+
+    for data in addrs {
+        // Compressed name pointing at the question, type, class IN, TTL 60
+        msg.extend_from_slice(&[0xc0, 12, 0, qtype as u8, 0, 1, 0, 0, 0, 60, 0, data.len() as u8]);
+        msg.extend_from_slice(&data);
+    }
+
+    Some(msg)
+}
+
+fn setup(builder: &Builder) -> (Poll, Resolver) {
+    let poll = Poll::new().unwrap();
+    let resolver = builder.build().unwrap();
+
+    poll.register(&resolver, DNS, Ready::readable(), PollOpt::edge()).unwrap();
+
+    (poll, resolver)
+}
+
+fn wait(poll: &Poll, resolver: &mut Resolver) -> (Query, io::Result<Vec<IpAddr>>) {
+    let mut events = Events::with_capacity(16);
+
+    for _ in 0..50 {
+        let timeout = resolver.timeout().unwrap_or(Duration::from_millis(100));
+        poll.poll(&mut events, Some(timeout)).unwrap();
+
+        resolver.process().unwrap();
+
+        if let Some(res) = resolver.next_result() {
+            return res;
+        }
+    }
+
+    panic!("lookup did not complete");
+}
+
+fn addrs(s: &[&str]) -> Vec<IpAddr> {
+    s.iter().map(|a| a.parse().unwrap()).collect()
+}
+
+#[test]
+pub fn resolve_over_udp() {
+    let server = name_server(|name, _, _| {
+        assert_eq!(name, "example.com");
+        Reply::Addrs(addrs(&["192.0.2.1", "2001:db8::1"]))
+    });
+
+    let (poll, mut resolver) = setup(Builder::new().nameserver(server));
+
+    let query = resolver.resolve("example.com").unwrap();
+    let (q, res) = wait(&poll, &mut resolver);
+
+    assert_eq!(q, query);
+
+    let mut res = res.unwrap();
+    res.sort();
+    assert_eq!(res, addrs(&["192.0.2.1", "2001:db8::1"]));
+    assert!(resolver.next_result().is_none());
+}
+
+#[test]
+pub fn resolve_truncated_falls_back_to_tcp() {
+    let server = name_server(|_, _, tcp| {
+        if tcp {
+            Reply::Addrs(addrs(&["192.0.2.7"]))
+        } else {
+            Reply::Truncated
+        }
+    });
+
+    let (poll, mut resolver) = setup(Builder::new().nameserver(server));
+
+    resolver.resolve("big.example.com").unwrap();
+    let (_, res) = wait(&poll, &mut resolver);
+
+    assert_eq!(res.unwrap(), addrs(&["192.0.2.7"]));
+}
+
+#[test]
+pub fn resolve_over_tcp_after_transfer() {
+    let server = name_server(|_, _, tcp| {
+        if tcp {
+            Reply::Addrs(addrs(&["192.0.2.8"]))
+        } else {
+            Reply::Truncated
+        }
+    });
+
+    let (poll, mut resolver) = setup(Builder::new().nameserver(server));
+    let other = Poll::new().unwrap();
+
+    poll.transfer(&resolver, &other, DNS, Ready::readable(), PollOpt::edge()).unwrap();
+
+    // The TCP connection is registered with the `Poll` the resolver moved to
+    resolver.resolve("big.example.com").unwrap();
+    let (_, res) = wait(&other, &mut resolver);
+
+    assert_eq!(res.unwrap(), addrs(&["192.0.2.8"]));
+}
+
+#[test]
+pub fn resolve_ip_literals_and_hosts_without_querying() {
+    let (_silent, server) = silent_server();
+
+    let (poll, mut resolver) = setup(Builder::new()
+        .nameserver(server)
+        .parse_hosts("# comment\n192.0.2.10 printer printer.lan\n::1 localhost6\n"));
+
+    let literal = resolver.resolve("192.0.2.55").unwrap();
+
+    // The result is signaled through the resolver's token
+    let mut events = Events::with_capacity(16);
+    poll.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+
+    let event = events.iter().next().expect("no event for the resolver");
+    assert_eq!(event.token(), DNS);
+    assert!(event.readiness().is_readable());
+
+    let (q, res) = resolver.next_result().unwrap();
+    assert_eq!(q, literal);
+    assert_eq!(res.unwrap(), addrs(&["192.0.2.55"]));
+
+    resolver.resolve("Printer.LAN.").unwrap();
+    let (_, res) = resolver.next_result().unwrap();
+    assert_eq!(res.unwrap(), addrs(&["192.0.2.10"]));
+
+    resolver.resolve("localhost6").unwrap();
+    let (_, res) = resolver.next_result().unwrap();
+    assert_eq!(res.unwrap(), addrs(&["::1"]));
+}
+
+#[test]
+pub fn resolve_uses_search_list() {
+    let server = name_server(|name, _, _| {
+        if name == "www.corp.example" {
+            Reply::Addrs(addrs(&["192.0.2.20"]))
+        } else {
+            Reply::NxDomain
+        }
+    });
+
+    let (poll, mut resolver) = setup(Builder::new()
+        .nameserver(server)
+        .search("other.example")
+        .search("corp.example"));
+
+    resolver.resolve("www").unwrap();
+    let (_, res) = wait(&poll, &mut resolver);
+
+    assert_eq!(res.unwrap(), addrs(&["192.0.2.20"]));
+}
+
+#[test]
+pub fn resolve_nxdomain() {
+    let server = name_server(|_, _, _| Reply::NxDomain);
+
+    let (poll, mut resolver) = setup(Builder::new().nameserver(server).search("example.com"));
+
+    resolver.resolve("missing").unwrap();
+    let (_, res) = wait(&poll, &mut resolver);
+
+    assert_eq!(res.unwrap_err().kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+pub fn resolve_retries_next_name_server() {
+    let (_silent, first) = silent_server();
+    let second = name_server(|_, _, _| Reply::Addrs(addrs(&["192.0.2.30"])));
+
+    let (poll, mut resolver) = setup(Builder::new()
+        .nameserver(first)
+        .nameserver(second)
+        .timeout(Duration::from_millis(50))
+        .attempts(1));
+
+    resolver.resolve("example.com").unwrap();
+    let (_, res) = wait(&poll, &mut resolver);
+
+    assert_eq!(res.unwrap(), addrs(&["192.0.2.30"]));
+}
+
+#[test]
+pub fn resolve_times_out() {
+    let server = name_server(|_, _, _| Reply::Ignore);
+
+    let (poll, mut resolver) = setup(Builder::new()
+        .nameserver(server)
+        .timeout(Duration::from_millis(50))
+        .attempts(2));
+
+    resolver.resolve("example.com").unwrap();
+    let (_, res) = wait(&poll, &mut resolver);
+
+    assert_eq!(res.unwrap_err().kind(), io::ErrorKind::TimedOut);
+    assert!(resolver.timeout().is_none());
+}
+
+#[test]
+pub fn resolve_invalid_name() {
+    let mut resolver = Builder::new().build().unwrap();
+
+    let err = resolver.resolve("bad..name").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+}
@@ -12,6 +12,6 @@ pub fn test_double_register() {
     let l = TcpListener::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
 
     // Register the listener with `Poll`
-    poll.register(&l, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
-    assert!(poll.register(&l, Token(1), Ready::readable(), PollOpt::edge()).is_err());
+    poll.register(&l, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+    assert!(poll.register(&l, Token(1), Ready::readable(), PollOpt::level()).is_err());
 }
@@ -260,6 +260,9 @@ pub fn test_echo_server() {
     debug!("Starting TEST_ECHO_SERVER");
     let mut poll = Poll::new().unwrap();
 
+    // Oneshot registrations are emulated by the poll(2) selector
+    poll.set_strict_registration(false);
+
     let addr = localhost();
     let srv = TcpListener::bind(&addr).unwrap();
 
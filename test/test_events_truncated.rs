@@ -0,0 +1,89 @@
+use mio::{Events, Poll, PollOpt, Ready, Registration, SetReadiness, Token};
+use mio::net::UdpSocket;
+use std::thread;
+use std::time::Duration;
+
+fn ready_registrations(poll: &Poll, n: usize) -> Vec<(Registration, SetReadiness)> {
+    (0..n).map(|i| {
+        let (r, set) = Registration::new2();
+        poll.register(&r, Token(i), Ready::readable(), PollOpt::edge()).unwrap();
+        set.set_readiness(Ready::readable()).unwrap();
+        (r, set)
+    }).collect()
+}
+
+#[test]
+pub fn test_events_truncated() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(4);
+    let _regs = ready_registrations(&poll, 10);
+
+    let zero = Some(Duration::from_millis(0));
+
+    poll.poll(&mut events, zero).unwrap();
+    assert_eq!(events.len(), 4);
+    assert!(events.is_truncated());
+
+    poll.poll(&mut events, zero).unwrap();
+    assert_eq!(events.len(), 4);
+    assert!(events.is_truncated());
+
+    poll.poll(&mut events, zero).unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(!events.is_truncated());
+
+    poll.poll(&mut events, zero).unwrap();
+    assert!(events.is_empty());
+    assert!(!events.is_truncated());
+}
+
+#[test]
+pub fn test_events_truncated_exactly_full() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(4);
+    let _regs = ready_registrations(&poll, 4);
+
+    poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+    assert_eq!(events.len(), 4);
+    assert!(!events.is_truncated());
+}
+
+#[test]
+pub fn test_events_truncated_selector() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(2);
+
+    let sender = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let mut sockets = vec![];
+
+    for i in 0..4 {
+        let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+        poll.register(&sock, Token(i), Ready::readable(), PollOpt::level()).unwrap();
+        sender.send_to(b"hello", &sock.local_addr().unwrap()).unwrap();
+        sockets.push(sock);
+    }
+
+    thread::sleep(Duration::from_millis(50));
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(events.is_truncated());
+}
+
+#[test]
+pub fn test_events_grow() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(2);
+    let _regs = ready_registrations(&poll, 6);
+
+    poll.set_max_events(Some(8));
+
+    poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+    assert_eq!(events.len(), 6);
+    assert!(!events.is_truncated());
+    assert!(events.capacity() >= 6 && events.capacity() <= 8);
+
+    let mut tokens: Vec<_> = events.iter().map(|e| e.token()).collect();
+    tokens.sort();
+    assert_eq!(tokens, (0..6).map(Token).collect::<Vec<_>>());
+}
@@ -0,0 +1,146 @@
+use mio::{Events, Poll, PollOpt, Ready, Registration, Token, Waker};
+use mio::net::UdpSocket;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SOCKET: Token = Token(0);
+const WAKER: Token = Token(1);
+
+/// Runs `f` in a forked child and returns its pid.
+fn fork<F: FnOnce()>(f: F) -> libc::pid_t {
+    match unsafe { libc::fork() } {
+        -1 => panic!("fork failed"),
+        0 => {
+            let ok = panic::catch_unwind(AssertUnwindSafe(f)).is_ok();
+            unsafe { libc::_exit(if ok { 0 } else { 1 }) }
+        }
+        pid => pid,
+    }
+}
+
+/// Waits for the child `pid` and returns whether it succeeded.
+fn wait(pid: libc::pid_t) -> bool {
+    let mut status = 0;
+    assert_eq!(pid, unsafe { libc::waitpid(pid, &mut status, 0) });
+    libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0
+}
+
+/// Like `wait`, but kills the child if it is still running after `timeout`.
+fn wait_timeout(pid: libc::pid_t, timeout: Duration) -> bool {
+    let start = Instant::now();
+    let mut status = 0;
+
+    loop {
+        match unsafe { libc::waitpid(pid, &mut status, libc::WNOHANG) } {
+            0 if start.elapsed() < timeout => thread::sleep(Duration::from_millis(10)),
+            0 => {
+                unsafe { libc::kill(pid, libc::SIGKILL) };
+                wait(pid);
+                return false;
+            }
+            ret => {
+                assert_eq!(pid, ret);
+                return libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0;
+            }
+        }
+    }
+}
+
+#[test]
+pub fn test_after_fork_child() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let waker = Waker::new(&poll, WAKER).unwrap();
+    let socket = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    poll.register(&socket, SOCKET, Ready::readable(), PollOpt::level()).unwrap();
+
+    let child = fork(|| {
+        poll.after_fork_child().unwrap();
+
+        // Not woken up by the parent
+        let start = Instant::now();
+        poll.poll(&mut events, Some(Duration::from_millis(600))).unwrap();
+        assert!(events.is_empty());
+        assert!(start.elapsed() >= Duration::from_millis(500));
+
+        // Wakeups of the child reach it
+        let waker = Waker::new(&poll, Token(2)).unwrap();
+        waker.wake().unwrap();
+        poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+        assert!(events.iter().any(|e| e.token() == Token(2)));
+
+        // Handles registered before the fork still work
+        let sender = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+        sender.send_to(b"hello", &socket.local_addr().unwrap()).unwrap();
+        poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+        assert!(events.iter().any(|e| e.token() == SOCKET));
+    });
+
+    // Wakes the parent up while both processes are polling. Sharing an
+    // awakener, either process could consume the wakeup.
+    let waker = Arc::new(waker);
+
+    for _ in 0..10 {
+        let waker = waker.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(40));
+            waker.wake().unwrap();
+        });
+
+        poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+        assert!(events.iter().any(|e| e.token() == WAKER));
+
+        handle.join().unwrap();
+    }
+
+    assert!(wait(child), "child failed");
+}
+
+#[test]
+pub fn test_after_fork_child_while_registrations_churn() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let registry = poll.registry().clone();
+    let done = Arc::new(AtomicBool::new(false));
+    let done2 = done.clone();
+
+    // Takes the locks of the node pool and of the urgent queue
+    let churn = thread::spawn(move || {
+        let (urgent, set) = Registration::new2();
+        registry.register(&urgent, Token(2), Ready::readable(), PollOpt::level() | PollOpt::urgent()).unwrap();
+
+        while !done2.load(Ordering::SeqCst) {
+            let (r, _) = Registration::with_registry(&registry);
+            drop(r);
+
+            set.set_readiness(Ready::readable()).unwrap();
+            set.set_readiness(Ready::empty()).unwrap();
+        }
+    });
+
+    for _ in 0..100 {
+        let child = fork(|| {
+            // Fails if the fork caught the other thread holding a lock, but
+            // never blocks
+            if poll.after_fork_child().is_ok() {
+                let (r, set) = Registration::new2();
+                poll.register(&r, Token(3), Ready::readable(), PollOpt::level() | PollOpt::urgent()).unwrap();
+                set.set_readiness(Ready::readable()).unwrap();
+
+                poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+                assert!(events.iter().any(|e| e.token() == Token(3)));
+            }
+        });
+
+        assert!(wait_timeout(child, Duration::from_secs(5)), "child failed or hung");
+        poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+    }
+
+    done.store(true, Ordering::SeqCst);
+    churn.join().unwrap();
+}
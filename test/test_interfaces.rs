@@ -0,0 +1,42 @@
+use mio::net::{self, UdpSocket};
+use std::net::{IpAddr, Ipv4Addr};
+
+#[test]
+pub fn interfaces_include_loopback() {
+    let interfaces = net::interfaces().unwrap();
+
+    let lo = interfaces.iter()
+        .find(|i| i.addrs().iter().any(|a| a.addr() == IpAddr::V4(Ipv4Addr::LOCALHOST)))
+        .expect("no interface with 127.0.0.1");
+
+    assert!(lo.is_up());
+    assert!(lo.is_loopback());
+    assert!(lo.index() > 0);
+    assert!(!lo.name().is_empty());
+    assert_eq!(lo.ipv4(), Some(Ipv4Addr::LOCALHOST));
+
+    let addr = lo.addrs().iter().find(|a| a.addr().is_ipv4()).unwrap();
+    assert_eq!(addr.netmask(), Some(IpAddr::V4(Ipv4Addr::new(255, 0, 0, 0))));
+
+    // Every interface is listed once
+    for (i, interface) in interfaces.iter().enumerate() {
+        assert!(interfaces[i + 1..].iter().all(|other| other.name() != interface.name()));
+    }
+}
+
+#[test]
+pub fn interfaces_plug_into_multicast_join() {
+    let sock = UdpSocket::bind(&"0.0.0.0:0".parse().unwrap()).unwrap();
+    let group = Ipv4Addr::new(224, 0, 0, 123);
+
+    for interface in net::interfaces().unwrap() {
+        if !interface.is_up() || !interface.is_multicast() {
+            continue;
+        }
+
+        if let Some(addr) = interface.ipv4() {
+            sock.join_multicast_v4(&group, &addr).unwrap();
+            sock.leave_multicast_v4(&group, &addr).unwrap();
+        }
+    }
+}
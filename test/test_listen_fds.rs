@@ -0,0 +1,172 @@
+use mio::{Events, Poll, PollOpt, Ready, Token};
+use mio::net::TcpStream;
+use mio::unix::{listen_fds, EventedFd, ListenFd};
+use std::env;
+use std::io;
+use std::net;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+use std::time::Duration;
+use tempdir::TempDir;
+
+// Set for the child process, which then runs `adopt_inherited_sockets`
+const CHILD: &str = "MIO_TEST_LISTEN_FDS_CHILD";
+
+/// Moves `fd` above the range used for passing sockets.
+fn dup_high(fd: RawFd) -> RawFd {
+    let high = unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 100) };
+    assert!(high >= 100);
+    high
+}
+
+/// Runs a single test of this binary in a child process whose fds 3 and up
+/// are `fds`.
+fn run_child(test: &str, fds: Vec<RawFd>, envs: &[(&str, String)]) {
+    let fds_copy = fds.clone();
+    let mut cmd = Command::new(env::current_exe().unwrap());
+    cmd.args(&["--exact", test, "--test-threads=1"]).env(CHILD, "1");
+
+    for &(key, ref val) in envs {
+        cmd.env(key, val);
+    }
+
+    unsafe {
+        cmd.pre_exec(move || {
+            for (i, &fd) in fds.iter().enumerate() {
+                if libc::dup2(fd, 3 + i as RawFd) < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+
+            Ok(())
+        });
+    }
+
+    let output = cmd.output().unwrap();
+
+    for fd in fds_copy {
+        unsafe { libc::close(fd); }
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success(), "child failed: {}{}",
+            stdout, String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("1 passed"), "child did not run: {}", stdout);
+}
+
+#[test]
+pub fn listen_fds_adopts_inherited_sockets() {
+    let tcp = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let udp = net::UdpSocket::bind("127.0.0.1:0").unwrap();
+
+    let dir = TempDir::new("mio").unwrap();
+    let path = dir.path().join("ctl.sock");
+    let unix = UnixListener::bind(&path).unwrap();
+
+    let fds = vec![
+        dup_high(tcp.as_raw_fd()),
+        dup_high(udp.as_raw_fd()),
+        dup_high(unix.as_raw_fd()),
+    ];
+
+    run_child("test_listen_fds::adopt_inherited_sockets", fds, &[
+        ("LISTEN_FDS", "3".to_string()),
+        ("LISTEN_FDNAMES", "web:dns:ctl".to_string()),
+        ("TCP_ADDR", tcp.local_addr().unwrap().to_string()),
+        ("UDP_ADDR", udp.local_addr().unwrap().to_string()),
+        ("UNIX_PATH", path.to_str().unwrap().to_string()),
+    ]);
+}
+
+#[test]
+pub fn adopt_inherited_sockets() {
+    if env::var(CHILD).is_err() {
+        return;
+    }
+
+    // Another process' sockets are left alone
+    env::set_var("LISTEN_PID", "1");
+    assert!(listen_fds().unwrap().is_empty());
+
+    env::set_var("LISTEN_PID", unsafe { libc::getpid() }.to_string());
+    let mut fds = listen_fds().unwrap();
+
+    assert_eq!(fds.len(), 3);
+    assert!(env::var("LISTEN_FDS").is_err());
+    assert!(listen_fds().unwrap().is_empty());
+
+    let unix = match fds.pop().unwrap() {
+        (ref name, ListenFd::Unix(l)) if name == "ctl" => l,
+        other => panic!("unexpected {:?}", other),
+    };
+    let udp = match fds.pop().unwrap() {
+        (ref name, ListenFd::Udp(s)) if name == "dns" => s,
+        other => panic!("unexpected {:?}", other),
+    };
+    let tcp = match fds.pop().unwrap() {
+        (ref name, ListenFd::Tcp(l)) if name == "web" => l,
+        other => panic!("unexpected {:?}", other),
+    };
+
+    assert_eq!(tcp.local_addr().unwrap().to_string(), env::var("TCP_ADDR").unwrap());
+    assert_eq!(udp.local_addr().unwrap().to_string(), env::var("UDP_ADDR").unwrap());
+
+    // Each socket is non-blocking and can be registered
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    poll.register(&tcp, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+    poll.register(&udp, Token(1), Ready::readable(), PollOpt::level()).unwrap();
+    poll.register(&EventedFd(&unix.as_raw_fd()), Token(2), Ready::readable(), PollOpt::level())
+        .unwrap();
+
+    assert_eq!(tcp.accept().unwrap_err().kind(), io::ErrorKind::WouldBlock);
+    assert_eq!(unix.accept().unwrap_err().kind(), io::ErrorKind::WouldBlock);
+
+    let _client = TcpStream::connect(&tcp.local_addr().unwrap()).unwrap();
+    let _peer = UnixStream::connect(env::var("UNIX_PATH").unwrap()).unwrap();
+
+    let mut ready = vec![];
+
+    while ready.len() < 2 {
+        poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+        assert!(!events.is_empty(), "no events");
+
+        for event in &events {
+            if !ready.contains(&event.token()) {
+                ready.push(event.token());
+            }
+        }
+    }
+
+    ready.sort();
+    assert_eq!(ready, vec![Token(0), Token(2)]);
+}
+
+#[test]
+pub fn listen_fds_rejects_unsupported_fds() {
+    let (a, _b) = UnixStream::pair().unwrap();
+
+    run_child("test_listen_fds::reject_unsupported_fd", vec![dup_high(a.as_raw_fd())], &[
+        ("LISTEN_FDS", "1".to_string()),
+    ]);
+}
+
+#[test]
+pub fn reject_unsupported_fd() {
+    if env::var(CHILD).is_err() {
+        return;
+    }
+
+    env::set_var("LISTEN_PID", unsafe { libc::getpid() }.to_string());
+
+    // A connected stream is not a listener
+    let err = listen_fds().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    // Nothing was adopted
+    assert_eq!(env::var("LISTEN_FDS").unwrap(), "1");
+}
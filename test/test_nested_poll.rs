@@ -0,0 +1,170 @@
+use mio::{Events, Poll, PollOpt, Ready, RegisterError, Registration, Token};
+use mio::net::UdpSocket;
+use std::io;
+use std::sync::{Arc, Barrier};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const CHILD: Token = Token(10);
+
+fn nested() -> (Poll, Poll) {
+    let parent = Poll::new().unwrap();
+    let child = Poll::new().unwrap();
+
+    parent.register(&child, CHILD, Ready::readable(), PollOpt::level()).unwrap();
+
+    (parent, child)
+}
+
+fn tokens(poll: &Poll, timeout: Duration) -> Vec<Token> {
+    let mut events = Events::with_capacity(16);
+    poll.poll(&mut events, Some(timeout)).unwrap();
+    events.iter().map(|e| e.token()).collect()
+}
+
+#[test]
+pub fn test_nested_registration() {
+    let (parent, child) = nested();
+
+    let (r, set) = Registration::new2();
+    child.register(&r, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
+
+    // Once the spurious wakeup of the registration is out of the way
+    tokens(&parent, Duration::from_millis(0));
+    tokens(&child, Duration::from_millis(0));
+    assert!(tokens(&parent, Duration::from_millis(100)).is_empty());
+
+    set.set_readiness(Ready::readable()).unwrap();
+
+    assert_eq!(tokens(&parent, Duration::from_secs(5)), vec![CHILD]);
+
+    // Reported until the child is polled
+    assert_eq!(tokens(&parent, Duration::from_millis(0)), vec![CHILD]);
+
+    assert_eq!(tokens(&child, Duration::from_millis(0)), vec![Token(0)]);
+    assert!(tokens(&parent, Duration::from_millis(100)).is_empty());
+}
+
+#[test]
+pub fn test_nested_socket() {
+    let (parent, child) = nested();
+
+    let sender = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    child.register(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+    tokens(&child, Duration::from_millis(0));
+    assert!(tokens(&parent, Duration::from_millis(100)).is_empty());
+
+    sender.send_to(b"hello", &sock.local_addr().unwrap()).unwrap();
+
+    assert_eq!(tokens(&parent, Duration::from_secs(5)), vec![CHILD]);
+    assert_eq!(tokens(&child, Duration::from_millis(0)), vec![Token(0)]);
+
+    let mut buf = [0; 16];
+    sock.recv_from(&mut buf).unwrap();
+
+    assert!(tokens(&parent, Duration::from_millis(100)).is_empty());
+
+    parent.deregister(&child).unwrap();
+    sender.send_to(b"hello", &sock.local_addr().unwrap()).unwrap();
+
+    assert!(tokens(&parent, Duration::from_millis(100)).is_empty());
+}
+
+#[test]
+pub fn test_nested_register_while_polling() {
+    let (parent, child) = nested();
+    let registry = child.registry().clone();
+
+    tokens(&child, Duration::from_millis(0));
+
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+
+        let sender = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+        let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+
+        sender.send_to(b"hello", &sock.local_addr().unwrap()).unwrap();
+        registry.register(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+        sock
+    });
+
+    let start = Instant::now();
+
+    // The parent is woken up and polls the new socket on its next call
+    while tokens(&parent, Duration::from_secs(5)) != vec![CHILD] {
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    assert!(start.elapsed() < Duration::from_secs(5));
+
+    let _sock = handle.join().unwrap();
+
+    assert!(tokens(&child, Duration::from_millis(0)).contains(&Token(0)));
+}
+
+fn register_error(res: io::Result<()>) -> Option<RegisterError> {
+    RegisterError::from_io_error(&res.unwrap_err())
+}
+
+#[test]
+pub fn test_nested_cycle() {
+    let (parent, child) = nested();
+
+    let res = parent.register(&parent, Token(0), Ready::readable(), PollOpt::level());
+    assert_eq!(register_error(res), Some(RegisterError::NestingCycle));
+
+    let res = child.register(&parent, Token(0), Ready::readable(), PollOpt::level());
+    assert_eq!(register_error(res), Some(RegisterError::NestingCycle));
+
+    let res = parent.register(&child, CHILD, Ready::readable(), PollOpt::level());
+    assert_eq!(register_error(res), Some(RegisterError::AlreadyRegistered));
+}
+
+#[test]
+pub fn test_nested_cycle_concurrent() {
+    for _ in 0..100 {
+        let a = Arc::new(Poll::new().unwrap());
+        let b = Arc::new(Poll::new().unwrap());
+        let barrier = Arc::new(Barrier::new(2));
+
+        let handle = {
+            let (a, b, barrier) = (a.clone(), b.clone(), barrier.clone());
+
+            thread::spawn(move || {
+                barrier.wait();
+                a.register(&*b, Token(0), Ready::readable(), PollOpt::level()).is_ok()
+            })
+        };
+
+        barrier.wait();
+        let b_in_a = b.register(&*a, Token(0), Ready::readable(), PollOpt::level()).is_ok();
+        let a_in_b = handle.join().unwrap();
+
+        // At most one of them is nested in the other
+        assert!(!(a_in_b && b_in_a));
+    }
+}
+
+#[test]
+pub fn test_nested_strict_registration() {
+    let parent = Poll::new().unwrap();
+    let child = Poll::new().unwrap();
+
+    let res = parent.register(&child, CHILD, Ready::readable(), PollOpt::edge());
+    assert_eq!(register_error(res), Some(RegisterError::Unsupported {
+        interest: Ready::readable(),
+        opts: PollOpt::edge(),
+    }));
+
+    parent.register(&child, CHILD, Ready::readable(), PollOpt::level()).unwrap();
+
+    let opts = PollOpt::level() | PollOpt::oneshot();
+    let res = parent.reregister(&child, CHILD, Ready::readable(), opts);
+    assert_eq!(register_error(res), Some(RegisterError::Unsupported {
+        interest: Ready::readable(),
+        opts: opts,
+    }));
+}
@@ -12,6 +12,10 @@ pub fn test_tcp_edge_oneshot() {
     let mut poll = Poll::new().unwrap();
     let mut events = Events::with_capacity(1024);
 
+    // Edge-triggered and oneshot registrations are emulated by the poll(2)
+    // selector
+    poll.set_strict_registration(false);
+
     // Create the listener
     let l = TcpListener::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
 
@@ -1,5 +1,5 @@
 use mio::*;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 #[test]
 fn test_poll_closes_fd() {
@@ -16,3 +16,125 @@ fn test_poll_closes_fd() {
         drop(registration);
     }
 }
+
+#[test]
+fn test_waker_closes_fd() {
+    for i in 0..2000 {
+        let poll = Poll::new().unwrap();
+        let mut events = Events::with_capacity(4);
+        let waker = Waker::new(&poll, Token(0)).unwrap();
+
+        waker.wake().unwrap();
+        poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+        assert_eq!(events.iter().next().map(|e| e.token()), Some(Token(0)));
+
+        // Drop in both orders
+        if i % 2 == 0 {
+            drop(poll);
+            drop(waker);
+        } else {
+            drop(waker);
+            drop(poll);
+        }
+    }
+}
+
+#[test]
+fn test_waker_coalesces() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+    let waker = Waker::new(&poll, Token(3)).unwrap();
+
+    for _ in 0..10 {
+        waker.wake().unwrap();
+    }
+
+    poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+    assert_eq!(events.len(), 1);
+
+    let event = events.iter().next().unwrap();
+    assert_eq!(event.token(), Token(3));
+    assert!(event.readiness().is_readable());
+
+    poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+    assert!(events.is_empty());
+
+    // Waking again after the event was observed produces a new event
+    waker.wake().unwrap();
+    poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+    assert_eq!(events.len(), 1);
+}
+
+#[test]
+fn test_waker_wakes_blocked_poll() {
+    use std::sync::Arc;
+    use std::thread;
+
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+    let waker = Arc::new(Waker::new(&poll, Token(0)).unwrap());
+
+    let w = waker.clone();
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        w.wake().unwrap();
+    });
+
+    poll.poll(&mut events, None).unwrap();
+    assert_eq!(events.iter().next().map(|e| e.token()), Some(Token(0)));
+
+    handle.join().unwrap();
+}
+
+#[test]
+fn test_waker_invalid_token() {
+    let poll = Poll::new().unwrap();
+    assert!(Waker::new(&poll, Token(usize::MAX)).is_err());
+}
+
+#[test]
+fn test_waker_multi_threaded() {
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering::SeqCst;
+    use std::thread;
+
+    const NUM_THREADS: usize = 4;
+    const NUM_ITERS: usize = 10_000;
+
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+    let waker = Arc::new(Waker::new(&poll, Token(0)).unwrap());
+    let count = Arc::new(AtomicUsize::new(0));
+
+    let handles: Vec<_> = (0..NUM_THREADS).map(|_| {
+        let waker = waker.clone();
+        let count = count.clone();
+
+        thread::spawn(move || {
+            for _ in 0..NUM_ITERS {
+                count.fetch_add(1, SeqCst);
+                waker.wake().unwrap();
+            }
+        })
+    }).collect();
+
+    // Every increment is followed by a wake, so as long as the count is
+    // incomplete another event must arrive. A lost wakeup leaves `poll`
+    // blocked until the deadline.
+    let deadline = Instant::now() + Duration::from_secs(10);
+
+    while count.load(SeqCst) < NUM_THREADS * NUM_ITERS {
+        assert!(Instant::now() < deadline, "wakeup lost");
+
+        poll.poll(&mut events, Some(Duration::from_secs(10))).unwrap();
+
+        for event in &events {
+            assert_eq!(event.token(), Token(0));
+        }
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,73 @@
+use mio::{Poll, PollOpt, Ready, Registration, Token};
+use mio::net::UdpSocket;
+use std::time::Duration;
+
+#[test]
+pub fn test_poll_with_stops_early() {
+    let poll = Poll::new().unwrap();
+    let mut regs = vec![];
+
+    for i in 0..8 {
+        let (r, set) = Registration::new2();
+        poll.register(&r, Token(i), Ready::readable(), PollOpt::edge()).unwrap();
+        set.set_readiness(Ready::readable()).unwrap();
+        regs.push((r, set));
+    }
+
+    let mut seen = vec![];
+
+    let n = poll.poll_with(Some(Duration::from_millis(0)), |event| {
+        seen.push(event.token());
+        seen.len() < 3
+    }).unwrap();
+
+    assert_eq!(n, 3);
+    assert_eq!(seen.len(), 3);
+
+    // The events that were not handled are returned by the next call
+    let n = poll.poll_with(Some(Duration::from_millis(0)), |event| {
+        seen.push(event.token());
+        true
+    }).unwrap();
+
+    assert_eq!(n, 5);
+
+    seen.sort();
+    assert_eq!(seen, (0..8).map(Token).collect::<Vec<_>>());
+
+    let n = poll.poll_with(Some(Duration::from_millis(0)), |_| true).unwrap();
+    assert_eq!(n, 0);
+}
+
+#[test]
+pub fn test_poll_with_registers_from_handler() {
+    let poll = Poll::new().unwrap();
+
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = sock.local_addr().unwrap();
+    poll.register(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+    sock.send_to(b"hello", &addr).unwrap();
+
+    let other = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let mut tokens = vec![];
+
+    while tokens.is_empty() {
+        poll.poll_with(Some(Duration::from_secs(5)), |event| {
+            tokens.push(event.token());
+
+            // Handlers may use `Poll` while events are handed out
+            poll.register(&other, Token(1), Ready::writable(), PollOpt::level()).unwrap();
+            poll.deregister(&sock).unwrap();
+            true
+        }).unwrap();
+    }
+
+    assert_eq!(tokens, vec![Token(0)]);
+
+    let n = poll.poll_with(Some(Duration::from_secs(5)), |event| {
+        assert_eq!(event.token(), Token(1));
+        true
+    }).unwrap();
+
+    assert_eq!(n, 1);
+}
@@ -0,0 +1,111 @@
+use mio::{Events, Poll, PollOpt, Ready, Registration, Token};
+use mio::net::TcpStream;
+use mio::unix::UnixReady;
+use std::io::Write;
+use std::net::{self, Shutdown};
+use std::time::{Duration, Instant};
+
+/// Polls until an event for `Token(0)` is returned, and returns its readiness.
+fn wait(poll: &Poll, events: &mut Events) -> UnixReady {
+    let start = Instant::now();
+
+    loop {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        poll.poll(events, Some(Duration::from_secs(5))).unwrap();
+
+        if let Some(event) = events.iter().find(|e| e.token() == Token(0)) {
+            return UnixReady::from(event.readiness());
+        }
+    }
+}
+
+fn connect() -> (TcpStream, net::TcpStream) {
+    let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let stream = TcpStream::connect(&listener.local_addr().unwrap()).unwrap();
+    let (peer, _) = listener.accept().unwrap();
+
+    (stream, peer)
+}
+
+#[test]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn test_read_closed() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (stream, peer) = connect();
+    let interest = Ready::readable() | UnixReady::read_closed();
+    poll.register(&stream, Token(0), interest, PollOpt::level()).unwrap();
+
+    peer.shutdown(Shutdown::Write).unwrap();
+
+    let ready = wait(&poll, &mut events);
+    assert!(ready.is_read_closed(), "actual={:?}", ready);
+    assert!(!ready.is_write_closed(), "actual={:?}", ready);
+    assert!(!ready.is_hup(), "actual={:?}", ready);
+
+    // Writing still works
+    (&stream).write_all(b"hello").unwrap();
+}
+
+#[test]
+pub fn test_write_closed() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (stream, peer) = connect();
+    poll.register(&stream, Token(0), Ready::writable(), PollOpt::level()).unwrap();
+
+    let ready = wait(&poll, &mut events);
+    assert!(!ready.is_write_closed(), "actual={:?}", ready);
+
+    drop(peer);
+
+    // The peer resets the connection once data arrives after the close
+    let _ = (&stream).write_all(b"hello");
+
+    let start = Instant::now();
+
+    while !wait(&poll, &mut events).is_write_closed() {
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
+
+#[test]
+pub fn test_registration_closed_readiness() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    // Registered ahead, and drained ahead unless `r` turns urgent
+    let (first, first_set) = Registration::new2();
+    poll.register(&first, Token(1), Ready::readable(), PollOpt::level()).unwrap();
+    first_set.set_readiness(Ready::readable()).unwrap();
+
+    let (r, set) = Registration::new2();
+    let interest = Ready::readable() | UnixReady::read_closed();
+    poll.register(&r, Token(0), interest, PollOpt::level()).unwrap();
+    set.set_readiness(UnixReady::read_closed().into()).unwrap();
+
+    for _ in 0..2 {
+        poll.poll(&mut events, Some(Duration::from_secs(1))).unwrap();
+
+        let tokens: Vec<_> = events.iter().map(|e| e.token()).collect();
+        assert_eq!(tokens, vec![Token(1), Token(0)]);
+
+        let ready = UnixReady::from(events.get(1).unwrap().readiness());
+        assert_eq!(ready, UnixReady::read_closed());
+    }
+
+    // Readiness outside of the interest is filtered, and leaves the interest
+    // and options alone: still level-triggered and readable only
+    set.set_readiness(Ready::all()).unwrap();
+    poll.reregister(&r, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+    set.set_readiness(Ready::all()).unwrap();
+
+    for _ in 0..2 {
+        poll.poll(&mut events, Some(Duration::from_secs(1))).unwrap();
+
+        let event = events.iter().find(|e| e.token() == Token(0)).unwrap();
+        assert_eq!(event.readiness(), Ready::readable());
+    }
+}
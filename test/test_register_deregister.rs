@@ -65,7 +65,7 @@ pub fn test_register_deregister() {
     let server = TcpListener::bind(&addr).unwrap();
 
     info!("register server socket");
-    poll.register(&server, SERVER, Ready::readable(), PollOpt::edge()).unwrap();
+    poll.register(&server, SERVER, Ready::readable(), PollOpt::level()).unwrap();
 
     let client = TcpStream::connect(&addr).unwrap();
 
@@ -101,23 +101,47 @@ pub fn test_register_empty_interest() {
 
     let sock = TcpListener::bind(&addr).unwrap();
 
-    poll.register(&sock, Token(0), Ready::empty(), PollOpt::edge()).unwrap();
+    poll.register(&sock, Token(0), Ready::empty(), PollOpt::level()).unwrap();
 
     let client = TcpStream::connect(&addr).unwrap();
 
     // The connect is not guaranteed to have started until it is registered
     // https://docs.rs/mio/0.6.10/mio/struct.Poll.html#registering-handles
-    poll.register(&client, Token(1), Ready::empty(), PollOpt::edge()).unwrap();
+    poll.register(&client, Token(1), Ready::empty(), PollOpt::level()).unwrap();
 
     // sock is registered with empty interest, we should not receive any event
     poll.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
     assert_eq!(events.len(), 0, "Received unexpected event: {:?}", events.get(0).unwrap());
 
     // now sock is reregistered with readable, we should receive the pending event
-    poll.reregister(&sock, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
+    poll.reregister(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
     expect_events(&poll, &mut events, 2, vec![
         Event::new(Ready::readable(), Token(0))
     ]);
 
-    poll.reregister(&sock, Token(0), Ready::empty(), PollOpt::edge()).unwrap();
+    poll.reregister(&sock, Token(0), Ready::empty(), PollOpt::level()).unwrap();
+}
+
+#[test]
+pub fn test_register_reused_fd() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(1024);
+
+    let sock = TcpListener::bind(&localhost()).unwrap();
+    poll.register(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+    // Closed without being deregistered
+    drop(sock);
+
+    // The lowest free fd number is handed out, usually the one just closed
+    let sock = TcpListener::bind(&localhost()).unwrap();
+
+    poll.register(&sock, Token(1), Ready::readable(), PollOpt::level()).unwrap();
+
+    let addr = sock.local_addr().unwrap();
+    let _client = TcpStream::connect(&addr).unwrap();
+
+    expect_events(&poll, &mut events, 2, vec![
+        Event::new(Ready::readable(), Token(1))
+    ]);
 }
@@ -0,0 +1,77 @@
+use mio::{Poll, PollOpt, Ready, RegisterError, Registration, Token, Waker};
+use mio::net::UdpSocket;
+use std::io;
+
+fn register_error(res: io::Result<()>) -> Option<RegisterError> {
+    RegisterError::from_io_error(&res.unwrap_err())
+}
+
+#[test]
+pub fn test_reserved_token() {
+    let poll = Poll::new().unwrap();
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+
+    let token = Token(usize::MAX);
+    let res = poll.register(&sock, token, Ready::readable(), PollOpt::level());
+    assert_eq!(register_error(res), Some(RegisterError::ReservedToken(token)));
+
+    let err = Waker::new(&poll, token).unwrap_err();
+    assert_eq!(RegisterError::from_io_error(&err), Some(RegisterError::ReservedToken(token)));
+}
+
+#[test]
+pub fn test_already_registered() {
+    let poll = Poll::new().unwrap();
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+
+    poll.register(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+    let err = poll.register(&sock, Token(1), Ready::readable(), PollOpt::level()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    assert_eq!(RegisterError::from_io_error(&err), Some(RegisterError::AlreadyRegistered));
+}
+
+#[test]
+pub fn test_not_registered() {
+    let poll = Poll::new().unwrap();
+    let other = Poll::new().unwrap();
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+
+    let err = poll.reregister(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    assert_eq!(RegisterError::from_io_error(&err), Some(RegisterError::NotRegistered));
+
+    let res = poll.deregister(&sock);
+    assert_eq!(register_error(res), Some(RegisterError::NotRegistered));
+
+    let res = poll.transfer(&sock, &other, Token(0), Ready::readable(), PollOpt::level());
+    assert_eq!(register_error(res), Some(RegisterError::NotRegistered));
+}
+
+#[test]
+pub fn test_foreign_selector() {
+    let poll = Poll::new().unwrap();
+    let other = Poll::new().unwrap();
+
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    poll.register(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+    let res = other.register(&sock, Token(0), Ready::readable(), PollOpt::level());
+    assert_eq!(register_error(res), Some(RegisterError::ForeignSelector));
+
+    let res = other.transfer(&sock, &poll, Token(0), Ready::readable(), PollOpt::level());
+    assert_eq!(register_error(res), Some(RegisterError::ForeignSelector));
+
+    let (r, _set) = Registration::new2();
+    poll.register(&r, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
+
+    let res = other.register(&r, Token(1), Ready::readable(), PollOpt::edge());
+    assert_eq!(register_error(res), Some(RegisterError::ForeignSelector));
+}
+
+#[test]
+pub fn test_display() {
+    let err: io::Error = RegisterError::ReservedToken(Token(3)).into();
+    assert_eq!(err.to_string(), "reserved token (Token(3))");
+    assert_eq!(err.kind(), io::ErrorKind::Other);
+}
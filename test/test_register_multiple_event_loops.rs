@@ -9,33 +9,33 @@ fn test_tcp_register_multiple_event_loops() {
     let listener = TcpListener::bind(&addr).unwrap();
 
     let poll1 = Poll::new().unwrap();
-    poll1.register(&listener, Token(0), Ready::readable() | Ready::writable(), PollOpt::edge()).unwrap();
+    poll1.register(&listener, Token(0), Ready::readable() | Ready::writable(), PollOpt::level()).unwrap();
 
     let poll2 = Poll::new().unwrap();
 
     // Try registering the same socket with the initial one
-    let res = poll2.register(&listener, Token(0), Ready::readable() | Ready::writable(), PollOpt::edge());
+    let res = poll2.register(&listener, Token(0), Ready::readable() | Ready::writable(), PollOpt::level());
     assert!(res.is_err());
     assert_eq!(res.unwrap_err().kind(), ErrorKind::Other);
 
     // Try cloning the socket and registering it again
     let listener2 = listener.try_clone().unwrap();
-    let res = poll2.register(&listener2, Token(0), Ready::readable() | Ready::writable(), PollOpt::edge());
+    let res = poll2.register(&listener2, Token(0), Ready::readable() | Ready::writable(), PollOpt::level());
     assert!(res.is_err());
     assert_eq!(res.unwrap_err().kind(), ErrorKind::Other);
 
     // Try the stream
     let stream = TcpStream::connect(&addr).unwrap();
 
-    poll1.register(&stream, Token(1), Ready::readable() | Ready::writable(), PollOpt::edge()).unwrap();
+    poll1.register(&stream, Token(1), Ready::readable() | Ready::writable(), PollOpt::level()).unwrap();
 
-    let res = poll2.register(&stream, Token(1), Ready::readable() | Ready::writable(), PollOpt::edge());
+    let res = poll2.register(&stream, Token(1), Ready::readable() | Ready::writable(), PollOpt::level());
     assert!(res.is_err());
     assert_eq!(res.unwrap_err().kind(), ErrorKind::Other);
 
     // Try cloning the socket and registering it again
     let stream2 = stream.try_clone().unwrap();
-    let res = poll2.register(&stream2, Token(1), Ready::readable() | Ready::writable(), PollOpt::edge());
+    let res = poll2.register(&stream2, Token(1), Ready::readable() | Ready::writable(), PollOpt::level());
     assert!(res.is_err());
     assert_eq!(res.unwrap_err().kind(), ErrorKind::Other);
 }
@@ -46,18 +46,18 @@ fn test_udp_register_multiple_event_loops() {
     let socket = UdpSocket::bind(&addr).unwrap();
 
     let poll1 = Poll::new().unwrap();
-    poll1.register(&socket, Token(0), Ready::readable() | Ready::writable(), PollOpt::edge()).unwrap();
+    poll1.register(&socket, Token(0), Ready::readable() | Ready::writable(), PollOpt::level()).unwrap();
 
     let poll2 = Poll::new().unwrap();
 
     // Try registering the same socket with the initial one
-    let res = poll2.register(&socket, Token(0), Ready::readable() | Ready::writable(), PollOpt::edge());
+    let res = poll2.register(&socket, Token(0), Ready::readable() | Ready::writable(), PollOpt::level());
     assert!(res.is_err());
     assert_eq!(res.unwrap_err().kind(), ErrorKind::Other);
 
     // Try cloning the socket and registering it again
     let socket2 = socket.try_clone().unwrap();
-    let res = poll2.register(&socket2, Token(0), Ready::readable() | Ready::writable(), PollOpt::edge());
+    let res = poll2.register(&socket2, Token(0), Ready::readable() | Ready::writable(), PollOpt::level());
     assert!(res.is_err());
     assert_eq!(res.unwrap_err().kind(), ErrorKind::Other);
 }
@@ -0,0 +1,119 @@
+use mio::{Events, Poll, PollOpt, Ready, RegisterError, Registration, Token};
+use mio::net::TcpStream;
+use std::io::{Read, Write};
+use std::net;
+use std::time::{Duration, Instant};
+
+const READER: Token = Token(0);
+const WRITER: Token = Token(1);
+
+fn connect() -> (TcpStream, net::TcpStream) {
+    let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let stream = TcpStream::connect(&listener.local_addr().unwrap()).unwrap();
+    let (peer, _) = listener.accept().unwrap();
+
+    (stream, peer)
+}
+
+/// Polls until an event for `token` is returned
+fn wait_for(poll: &Poll, events: &mut Events, token: Token) -> Ready {
+    let start = Instant::now();
+
+    loop {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        poll.poll(events, Some(Duration::from_secs(5))).unwrap();
+
+        if let Some(event) = events.iter().find(|e| e.token() == token) {
+            return event.readiness();
+        }
+    }
+}
+
+#[test]
+pub fn test_register_split() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (mut stream, mut peer) = connect();
+    poll.register_split(&stream, READER, WRITER, PollOpt::level()).unwrap();
+
+    // Only the writer is ready
+    let ready = wait_for(&poll, &mut events, WRITER);
+    assert!(ready.is_writable() && !ready.is_readable(), "actual={:?}", ready);
+    assert!(events.iter().all(|e| e.token() != READER));
+
+    peer.write_all(b"hello").unwrap();
+
+    let ready = wait_for(&poll, &mut events, READER);
+    assert!(ready.is_readable() && !ready.is_writable(), "actual={:?}", ready);
+
+    // Both are reported by the same call
+    let tokens: Vec<_> = events.iter().map(|e| e.token()).collect();
+    assert!(tokens.contains(&WRITER), "actual={:?}", tokens);
+
+    let mut buf = [0; 16];
+    assert_eq!(stream.read(&mut buf).unwrap(), 5);
+
+    // Back to a single token
+    poll.reregister(&stream, Token(2), Ready::readable(), PollOpt::level()).unwrap();
+    peer.write_all(b"hello").unwrap();
+
+    let ready = wait_for(&poll, &mut events, Token(2));
+    assert!(ready.is_readable());
+    assert!(events.iter().all(|e| e.token() == Token(2)));
+
+    poll.deregister(&stream).unwrap();
+}
+
+#[test]
+pub fn test_register_split_errors() {
+    let poll = Poll::new().unwrap();
+
+    let (stream, _peer) = connect();
+    poll.register_split(&stream, READER, WRITER, PollOpt::level()).unwrap();
+
+    let err = poll.register_split(&stream, READER, WRITER, PollOpt::level()).unwrap_err();
+    assert_eq!(RegisterError::from_io_error(&err), Some(RegisterError::AlreadyRegistered));
+
+    let (stream2, _peer2) = connect();
+    let err = poll.register_split(&stream2, READER, Token(usize::MAX), PollOpt::level()).unwrap_err();
+    assert_eq!(RegisterError::from_io_error(&err), Some(RegisterError::ReservedToken(Token(usize::MAX))));
+
+    let (r, _set) = Registration::new2();
+    let err = poll.register_split(&r, READER, WRITER, PollOpt::edge()).unwrap_err();
+
+    match RegisterError::from_io_error(&err) {
+        Some(RegisterError::Unsupported { .. }) => {}
+        actual => panic!("actual={:?}", actual),
+    }
+}
+
+#[test]
+pub fn test_reregister_split() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (stream, mut peer) = connect();
+    poll.register_split(&stream, READER, WRITER, PollOpt::level()).unwrap();
+    wait_for(&poll, &mut events, WRITER);
+
+    // Nothing to write, the writer is no longer woken up
+    poll.reregister_split(&stream, READER, WRITER, Ready::readable(), PollOpt::level()).unwrap();
+    poll.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+    assert!(events.is_empty());
+
+    peer.write_all(b"hello").unwrap();
+
+    let ready = wait_for(&poll, &mut events, READER);
+    assert!(ready.is_readable());
+    assert!(events.iter().all(|e| e.token() == READER));
+
+    // Both tokens are kept while toggling the write interest
+    poll.reregister_split(&stream, READER, WRITER, Ready::readable() | Ready::writable(), PollOpt::level()).unwrap();
+
+    let ready = wait_for(&poll, &mut events, WRITER);
+    assert!(ready.is_writable() && !ready.is_readable(), "actual={:?}", ready);
+
+    let tokens: Vec<_> = events.iter().map(|e| e.token()).collect();
+    assert!(tokens.contains(&READER), "actual={:?}", tokens);
+}
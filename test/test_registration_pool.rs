@@ -0,0 +1,43 @@
+use mio::{Events, Poll, PollOpt, Ready, Registration, Token};
+use std::time::Duration;
+
+fn tokens(poll: &Poll) -> Vec<Token> {
+    let mut events = Events::with_capacity(16);
+    poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+    events.iter().map(|e| e.token()).collect()
+}
+
+#[test]
+pub fn test_pool_skips_live_nodes() {
+    let poll = Poll::new().unwrap();
+
+    let (r1, set1) = Registration::with_registry(poll.registry());
+    poll.register(&r1, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
+
+    // `set1` keeps the node alive after `poll` released its handle
+    drop(r1);
+    assert!(tokens(&poll).is_empty());
+
+    let (r2, set2) = Registration::with_registry(poll.registry());
+    poll.register(&r2, Token(2), Ready::readable(), PollOpt::edge()).unwrap();
+
+    set1.set_readiness(Ready::readable()).unwrap();
+    assert!(tokens(&poll).is_empty());
+
+    set2.set_readiness(Ready::readable()).unwrap();
+    assert_eq!(tokens(&poll), vec![Token(2)]);
+}
+
+#[test]
+pub fn test_registration_pool_disabled() {
+    let mut poll = Poll::new().unwrap();
+    poll.set_registration_pool(0);
+
+    for i in 0..10 {
+        let (r, set) = Registration::with_registry(poll.registry());
+        poll.register(&r, Token(i), Ready::readable(), PollOpt::edge()).unwrap();
+
+        set.set_readiness(Ready::readable()).unwrap();
+        assert_eq!(tokens(&poll), vec![Token(i)]);
+    }
+}
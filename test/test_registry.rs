@@ -0,0 +1,78 @@
+use mio::{Events, Poll, PollOpt, Ready, Registration, Token};
+use mio::net::UdpSocket;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+pub fn test_register_from_thread() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let registry = poll.registry().clone();
+
+    let handle = thread::spawn(move || {
+        let (r, set) = Registration::new2();
+        registry.register(&r, Token(7), Ready::readable(), PollOpt::edge()).unwrap();
+        set.set_readiness(Ready::readable()).unwrap();
+        r
+    });
+
+    let start = Instant::now();
+
+    // Wakes up once the other thread sets readiness, registering may wake
+    // it up before that
+    while events.is_empty() {
+        assert!(start.elapsed() < Duration::from_secs(5));
+        poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    }
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events.get(0).unwrap().token(), Token(7));
+
+    drop(handle.join().unwrap());
+}
+
+#[test]
+pub fn test_registry_shares_selector() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let sender = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+
+    let registry = poll.registry().clone();
+    registry.register(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+    // The same handle can be managed through `Poll` afterwards
+    poll.reregister(&sock, Token(1), Ready::readable(), PollOpt::level()).unwrap();
+
+    sender.send_to(b"hello", &sock.local_addr().unwrap()).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events.get(0).unwrap().token(), Token(1));
+
+    registry.deregister(&sock).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+    assert!(events.is_empty());
+
+    // Another `Poll` instance's registry is rejected
+    let other = Poll::new().unwrap();
+    assert!(other.registry().register(&sock, Token(0), Ready::readable(), PollOpt::level()).is_err());
+}
+
+#[test]
+pub fn test_registry_outlives_poll() {
+    let poll = Poll::new().unwrap();
+    let registry = poll.registry().clone();
+
+    drop(poll);
+
+    let (r, set) = Registration::new2();
+    registry.register(&r, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
+
+    // Nothing is listening anymore, but readiness can still be set
+    set.set_readiness(Ready::readable()).unwrap();
+}
@@ -0,0 +1,72 @@
+use mio::{Events, Poll, PollOpt, Ready, Registration, SetReadiness, Token};
+use std::thread;
+use std::time::Duration;
+
+fn tokens(poll: &Poll, timeout: Duration) -> Vec<usize> {
+    let mut events = Events::with_capacity(64);
+    poll.poll(&mut events, Some(timeout)).unwrap();
+
+    let mut tokens: Vec<_> = events.iter().map(|e| e.token().index()).collect();
+    tokens.sort();
+    tokens
+}
+
+#[test]
+pub fn test_batch_across_polls() {
+    let poll1 = Poll::new().unwrap();
+    let poll2 = Poll::new().unwrap();
+
+    let regs: Vec<_> = (0..8).map(|i| {
+        let (r, set) = Registration::new2();
+        let poll = if i % 2 == 0 { &poll1 } else { &poll2 };
+        poll.register(&r, Token(i), Ready::readable(), PollOpt::edge()).unwrap();
+        (r, set)
+    }).collect();
+
+    let batch: Vec<_> = regs.iter().map(|(_, set)| (set, Ready::readable())).collect();
+    SetReadiness::set_readiness_batch(&batch).unwrap();
+
+    assert_eq!(tokens(&poll1, Duration::from_millis(0)), vec![0, 2, 4, 6]);
+    assert_eq!(tokens(&poll2, Duration::from_millis(0)), vec![1, 3, 5, 7]);
+}
+
+#[test]
+pub fn test_batch_wakes_poll() {
+    let poll = Poll::new().unwrap();
+
+    let regs: Vec<_> = (0..32).map(|i| {
+        let (r, set) = Registration::new2();
+        poll.register(&r, Token(i), Ready::readable(), PollOpt::edge()).unwrap();
+        (r, set)
+    }).collect();
+
+    let (regs, sets): (Vec<_>, Vec<_>) = regs.into_iter().unzip();
+
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+
+        let batch: Vec<_> = sets.iter().map(|set| (set, Ready::readable())).collect();
+        SetReadiness::set_readiness_batch(&batch).unwrap();
+
+        sets
+    });
+
+    assert_eq!(tokens(&poll, Duration::from_secs(5)), (0..32).collect::<Vec<_>>());
+
+    drop(handle.join().unwrap());
+    drop(regs);
+}
+
+#[test]
+pub fn test_batch_skips_unregistered() {
+    let poll = Poll::new().unwrap();
+
+    let (r1, set1) = Registration::new2();
+    let (_r2, set2) = Registration::new2();
+    poll.register(&r1, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
+
+    SetReadiness::set_readiness_batch(&[(&set1, Ready::readable()), (&set2, Ready::readable())]).unwrap();
+
+    assert_eq!(tokens(&poll, Duration::from_millis(0)), vec![1]);
+    assert!(set2.readiness().is_readable());
+}
@@ -0,0 +1,226 @@
+use mio::{Events, Poll, PollOpt, Ready, Token};
+use mio::net::{Socks5Connector, Socks5Target};
+use std::io::{self, Read, Write};
+use std::net::{self, SocketAddr};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const PROXY: Token = Token(0);
+
+/// What the stand-in proxy expects from the client and how it replies.
+#[derive(Clone)]
+struct Proxy {
+    credentials: Option<(&'static str, &'static str)>,
+    target: Socks5Target,
+    reply: u8,
+}
+
+/// Serves a single SOCKS5 client, then echoes whatever is tunneled.
+fn proxy(proxy: Proxy) -> SocketAddr {
+    let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    thread::spawn(move || {
+        let (mut s, _) = listener.accept().unwrap();
+
+        // Greeting
+        let mut head = [0; 2];
+        s.read_exact(&mut head).unwrap();
+        assert_eq!(head[0], 5);
+        let mut methods = vec![0; head[1] as usize];
+        s.read_exact(&mut methods).unwrap();
+
+        match proxy.credentials {
+            Some((username, password)) => {
+                assert!(methods.contains(&2));
+                s.write_all(&[5, 2]).unwrap();
+
+                let mut buf = [0; 2];
+                s.read_exact(&mut buf).unwrap();
+                assert_eq!(buf[0], 1);
+                let mut user = vec![0; buf[1] as usize];
+                s.read_exact(&mut user).unwrap();
+                let mut len = [0; 1];
+                s.read_exact(&mut len).unwrap();
+                let mut pass = vec![0; len[0] as usize];
+                s.read_exact(&mut pass).unwrap();
+
+                if user != username.as_bytes() || pass != password.as_bytes() {
+                    s.write_all(&[1, 1]).unwrap();
+                    return;
+                }
+
+                s.write_all(&[1, 0]).unwrap();
+            }
+            None => {
+                assert!(methods.contains(&0));
+                s.write_all(&[5, 0]).unwrap();
+            }
+        }
+
+        // Connect request
+        let mut req = [0; 4];
+        s.read_exact(&mut req).unwrap();
+        assert_eq!(&req[..3], &[5, 1, 0]);
+
+        let target = match req[3] {
+            1 => {
+                let mut buf = [0; 6];
+                s.read_exact(&mut buf).unwrap();
+                let ip = net::Ipv4Addr::new(buf[0], buf[1], buf[2], buf[3]);
+                Socks5Target::Addr(SocketAddr::new(ip.into(), port(&buf[4..])))
+            }
+            3 => {
+                let mut len = [0; 1];
+                s.read_exact(&mut len).unwrap();
+                let mut buf = vec![0; len[0] as usize + 2];
+                s.read_exact(&mut buf).unwrap();
+                let name = String::from_utf8(buf[..len[0] as usize].to_vec()).unwrap();
+                Socks5Target::Domain(name, port(&buf[len[0] as usize..]))
+            }
+            atyp => panic!("unexpected address type {}", atyp),
+        };
+
+        assert_eq!(target, proxy.target);
+
+        // Bound to 10.0.0.1:4321, followed by tunneled data in the same write
+        s.write_all(&[5, proxy.reply, 0, 1, 10, 0, 0, 1, 0x10, 0xe1]).unwrap();
+
+        if proxy.reply != 0 {
+            return;
+        }
+
+        let mut buf = [0; 1024];
+
+        loop {
+            match s.read(&mut buf).unwrap() {
+                0 => return,
+                n => s.write_all(&buf[..n]).unwrap(),
+            }
+        }
+    });
+
+    addr
+}
+
+fn port(b: &[u8]) -> u16 {
+    (b[0] as u16) << 8 | b[1] as u16
+}
+
+fn handshake(poll: &Poll, connector: &mut Socks5Connector) -> io::Result<()> {
+    let mut events = Events::with_capacity(16);
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while Instant::now() < deadline {
+        if connector.handshake()? {
+            return Ok(());
+        }
+
+        // Only wait for what the handshake needs next
+        poll.reregister(connector, PROXY, connector.interest(), PollOpt::level()).unwrap();
+        poll.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+    }
+
+    panic!("handshake did not complete");
+}
+
+fn register(poll: &Poll, connector: &Socks5Connector) {
+    poll.register(connector, PROXY, Ready::readable() | Ready::writable(), PollOpt::level())
+        .unwrap();
+}
+
+#[test]
+pub fn socks5_connect_to_addr_without_auth() {
+    let target = Socks5Target::Addr("192.0.2.1:80".parse().unwrap());
+    let addr = proxy(Proxy { credentials: None, target: target.clone(), reply: 0 });
+
+    let poll = Poll::new().unwrap();
+    let mut connector = Socks5Connector::connect(&addr, target).unwrap();
+    register(&poll, &connector);
+
+    handshake(&poll, &mut connector).unwrap();
+
+    assert!(connector.is_connected());
+    assert_eq!(connector.bound_addr(), Some("10.0.0.1:4321".parse().unwrap()));
+
+    // The stream is tunneled to the echoing proxy
+    let mut stream = connector.into_stream().unwrap();
+    stream.write_all(b"hello").unwrap();
+
+    let mut events = Events::with_capacity(16);
+    let mut buf = vec![];
+    let mut chunk = [0; 16];
+
+    while buf.len() < 5 {
+        poll.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+
+        match stream.read(&mut chunk) {
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+            Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {}
+            Err(e) => panic!("{:?}", e),
+        }
+    }
+
+    assert_eq!(buf, b"hello");
+}
+
+#[test]
+pub fn socks5_connect_to_domain_with_auth() {
+    let target = Socks5Target::Domain("example.com".to_string(), 443);
+    let addr = proxy(Proxy { credentials: Some(("user", "secret")), target: target.clone(), reply: 0 });
+
+    let poll = Poll::new().unwrap();
+    let mut connector = Socks5Connector::connect(&addr, target).unwrap();
+    connector.set_credentials("user", "secret").unwrap();
+    register(&poll, &connector);
+
+    handshake(&poll, &mut connector).unwrap();
+
+    assert!(connector.into_stream().is_ok());
+}
+
+#[test]
+pub fn socks5_rejected_credentials() {
+    let target = Socks5Target::Domain("example.com".to_string(), 443);
+    let addr = proxy(Proxy { credentials: Some(("user", "secret")), target: target.clone(), reply: 0 });
+
+    let poll = Poll::new().unwrap();
+    let mut connector = Socks5Connector::connect(&addr, target).unwrap();
+    connector.set_credentials("user", "wrong").unwrap();
+    register(&poll, &connector);
+
+    let err = handshake(&poll, &mut connector).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+}
+
+#[test]
+pub fn socks5_connection_refused_reply() {
+    let target = Socks5Target::Addr("192.0.2.1:80".parse().unwrap());
+    let addr = proxy(Proxy { credentials: None, target: target.clone(), reply: 5 });
+
+    let poll = Poll::new().unwrap();
+    let mut connector = Socks5Connector::connect(&addr, target).unwrap();
+    register(&poll, &connector);
+
+    let err = handshake(&poll, &mut connector).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+}
+
+#[test]
+pub fn socks5_invalid_arguments() {
+    let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let long = "a".repeat(256);
+
+    let err = Socks5Connector::connect(&addr, Socks5Target::Domain(long.clone(), 80)).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let mut connector = Socks5Connector::connect(&addr, Socks5Target::Domain("a".to_string(), 80))
+        .unwrap();
+    let err = connector.set_credentials(&long, "secret").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+    let err = connector.into_stream().unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotConnected);
+}
@@ -0,0 +1,103 @@
+use mio::{Events, Poll, PollOpt, Ready, RegisterError, Token};
+use mio::unix::Source;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+
+fn pair() -> (Source<UnixStream>, UnixStream) {
+    let (a, b) = UnixStream::pair().unwrap();
+    a.set_nonblocking(true).unwrap();
+    (Source::new(a), b)
+}
+
+#[test]
+pub fn test_source_deregisters_on_drop() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    // Panics if the selector is left with the closed fd
+    poll.set_debug_assertions(true);
+
+    let (mut source, mut peer) = pair();
+    poll.register(&source, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+    assert_eq!(source.token(), Some(Token(0)));
+
+    peer.write_all(b"hello").unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.iter().map(|e| e.token()).collect::<Vec<_>>(), vec![Token(0)]);
+
+    let mut buf = [0; 16];
+    assert_eq!(source.read(&mut buf).unwrap(), 5);
+
+    peer.write_all(b"hello").unwrap();
+    drop(source);
+
+    poll.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+    assert!(events.is_empty());
+}
+
+#[test]
+pub fn test_source_registration() {
+    let poll = Poll::new().unwrap();
+    let other = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (source, mut peer) = pair();
+    assert_eq!(source.token(), None);
+
+    let res = poll.reregister(&source, Token(0), Ready::readable(), PollOpt::level());
+    assert_eq!(RegisterError::from_io_error(&res.unwrap_err()), Some(RegisterError::NotRegistered));
+
+    poll.register(&source, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+    let res = other.register(&source, Token(0), Ready::readable(), PollOpt::level());
+    assert_eq!(RegisterError::from_io_error(&res.unwrap_err()), Some(RegisterError::ForeignSelector));
+
+    poll.reregister(&source, Token(1), Ready::readable(), PollOpt::level()).unwrap();
+    assert_eq!(source.token(), Some(Token(1)));
+
+    poll.transfer(&source, &other, Token(2), Ready::readable(), PollOpt::level()).unwrap();
+    assert_eq!(source.token(), Some(Token(2)));
+
+    peer.write_all(b"hello").unwrap();
+
+    other.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.iter().map(|e| e.token()).collect::<Vec<_>>(), vec![Token(2)]);
+
+    // Deregistered from `other` before being handed back
+    let stream = source.into_inner();
+
+    other.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+    assert!(events.is_empty());
+
+    // Not registered with `other` anymore, so it can be registered again
+    let source = Source::new(stream);
+    other.register(&source, Token(3), Ready::readable(), PollOpt::level()).unwrap();
+}
+
+#[test]
+pub fn test_source_rejected_transfer() {
+    let poll = Poll::new().unwrap();
+    let other = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (source, mut peer) = pair();
+    poll.register(&source, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+
+    // `other` does not accept edge-triggered registrations
+    let res = poll.transfer(&source, &other, Token(1), Ready::readable(), PollOpt::edge());
+    assert_eq!(RegisterError::from_io_error(&res.unwrap_err()), Some(RegisterError::Unsupported {
+        interest: Ready::readable(),
+        opts: PollOpt::edge(),
+    }));
+    assert_eq!(source.token(), Some(Token(0)));
+
+    peer.write_all(b"hello").unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.iter().map(|e| e.token()).collect::<Vec<_>>(), vec![Token(0)]);
+
+    other.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+    assert!(events.is_empty());
+}
@@ -26,7 +26,7 @@ fn accept() {
 
     let poll = Poll::new().unwrap();
 
-    poll.register(&l, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
+    poll.register(&l, Token(1), Ready::readable(), PollOpt::level()).unwrap();
 
     let mut events = Events::with_capacity(128);
 
@@ -66,7 +66,7 @@ fn connect() {
     let poll = Poll::new().unwrap();
     let s = TcpStream::connect(&addr).unwrap();
 
-    poll.register(&s, Token(1), Ready::readable() | Ready::writable(), PollOpt::edge()).unwrap();
+    poll.register(&s, Token(1), Ready::readable() | Ready::writable(), PollOpt::level()).unwrap();
 
     let mut events = Events::with_capacity(128);
 
@@ -127,7 +127,7 @@ fn read() {
     let poll = Poll::new().unwrap();
     let s = TcpStream::connect(&addr).unwrap();
 
-    poll.register(&s, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
+    poll.register(&s, Token(1), Ready::readable(), PollOpt::level()).unwrap();
 
     let mut events = Events::with_capacity(128);
 
@@ -174,7 +174,7 @@ fn peek() {
     let poll = Poll::new().unwrap();
     let s = TcpStream::connect(&addr).unwrap();
 
-    poll.register(&s, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
+    poll.register(&s, Token(1), Ready::readable(), PollOpt::level()).unwrap();
 
     let mut events = Events::with_capacity(128);
 
@@ -301,7 +301,7 @@ fn write() {
     let poll = Poll::new().unwrap();
     let s = TcpStream::connect(&addr).unwrap();
 
-    poll.register(&s, Token(1), Ready::writable(), PollOpt::edge()).unwrap();
+    poll.register(&s, Token(1), Ready::writable(), PollOpt::level()).unwrap();
 
     let mut events = Events::with_capacity(128);
 
@@ -390,8 +390,8 @@ fn connect_then_close() {
     let l = TcpListener::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
     let s = TcpStream::connect(&l.local_addr().unwrap()).unwrap();
 
-    poll.register(&l, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
-    poll.register(&s, Token(2), Ready::readable(), PollOpt::edge()).unwrap();
+    poll.register(&l, Token(1), Ready::readable(), PollOpt::level()).unwrap();
+    poll.register(&s, Token(2), Ready::readable(), PollOpt::level()).unwrap();
 
     let mut events = Events::with_capacity(128);
 
@@ -403,7 +403,7 @@ fn connect_then_close() {
             if event.token() == Token(1) {
                 let s = h.listener.accept().unwrap().0;
                 poll.register(&s, Token(3), Ready::readable() | Ready::writable(),
-                                        PollOpt::edge()).unwrap();
+                                        PollOpt::level()).unwrap();
                 drop(s);
             } else if event.token() == Token(2) {
                 h.shutdown = true;
@@ -412,12 +412,45 @@ fn connect_then_close() {
     }
 }
 
+#[test]
+fn connect_from() {
+    let l = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = l.local_addr().unwrap();
+
+    // Port 0 lets the kernel pick the port once the connect is issued
+    let s = TcpStream::connect_from(&"127.0.0.1:0".parse().unwrap(), &addr).unwrap();
+    let (_, peer) = l.accept().unwrap();
+
+    let local = s.local_addr().unwrap();
+    assert_eq!(local.ip(), "127.0.0.1".parse::<net::IpAddr>().unwrap());
+    assert_ne!(local.port(), 0);
+    assert_eq!(local, peer);
+
+    // An explicit source port is honored as well
+    let bound = net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = bound.local_addr().unwrap().port();
+    drop(bound);
+
+    let local = format!("127.0.0.1:{}", port).parse().unwrap();
+    let s = TcpStream::connect_from(&local, &addr).unwrap();
+    let (_, peer) = l.accept().unwrap();
+
+    assert_eq!(s.local_addr().unwrap(), local);
+    assert_eq!(peer, local);
+}
+
+#[test]
+fn connect_from_mismatched_family() {
+    let addr = "127.0.0.1:80".parse().unwrap();
+    assert!(TcpStream::connect_from(&"[::1]:0".parse().unwrap(), &addr).is_err());
+}
+
 #[test]
 fn listen_then_close() {
     let poll = Poll::new().unwrap();
     let l = TcpListener::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
 
-    poll.register(&l, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
+    poll.register(&l, Token(1), Ready::readable(), PollOpt::level()).unwrap();
     drop(l);
 
     let mut events = Events::with_capacity(128);
@@ -517,10 +550,10 @@ fn connection_reset_by_peer() {
     let client = TcpStream::from_stream(client).unwrap();
 
     // Register server
-    poll.register(&l, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
+    poll.register(&l, Token(0), Ready::readable(), PollOpt::level()).unwrap();
 
     // Register interest in the client
-    poll.register(&client, Token(1), Ready::readable() | Ready::writable(), PollOpt::edge()).unwrap();
+    poll.register(&client, Token(1), Ready::readable() | Ready::writable(), PollOpt::level()).unwrap();
 
     // Wait for listener to be ready
     let mut server;
@@ -549,7 +582,7 @@ fn connection_reset_by_peer() {
     thread::sleep(Duration::from_millis(100));
 
     // Register interest in the server socket
-    poll.register(&server, Token(3), Ready::readable(), PollOpt::edge()).unwrap();
+    poll.register(&server, Token(3), Ready::readable(), PollOpt::level()).unwrap();
 
 
     loop {
@@ -589,7 +622,7 @@ fn connect_error() {
         Err(e) => panic!("TcpStream::connect unexpected error {:?}", e)
     };
 
-    poll.register(&l, Token(0), Ready::writable(), PollOpt::edge()).unwrap();
+    poll.register(&l, Token(0), Ready::writable(), PollOpt::level()).unwrap();
 
     'outer:
     loop {
@@ -624,7 +657,7 @@ fn write_error() {
     poll.register(&s,
                   Token(0),
                   Ready::readable() | Ready::writable(),
-                  PollOpt::edge()).unwrap();
+                  PollOpt::level()).unwrap();
 
     let mut wait_writable = || {
         'outer:
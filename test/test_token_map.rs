@@ -0,0 +1,193 @@
+use mio::{Events, Poll, PollOpt, Ready, Registration, Token, TokenMap};
+use mio::net::UdpSocket;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+pub fn test_token_map_stale_token() {
+    let mut map = TokenMap::new();
+
+    let a = map.insert("a");
+    let b = map.insert("b");
+    assert_eq!(map.len(), 2);
+
+    assert_eq!(map.remove(a), Some("a"));
+    assert_eq!(map.remove(a), None);
+
+    // The slot of `a` is reused with a new generation
+    let c = map.insert("c");
+    assert!(c != a);
+
+    assert_eq!(map.get(a), None);
+    assert!(!map.contains(a));
+    assert_eq!(map.get(b), Some(&"b"));
+    assert_eq!(map.get(c), Some(&"c"));
+
+    *map.get_mut(c).unwrap() = "d";
+
+    let mut entries: Vec<_> = map.iter().map(|(t, v)| (t, *v)).collect();
+    entries.sort();
+    assert_eq!(entries, vec![(b, "b"), (c, "d")]);
+    assert_eq!(map.len(), 2);
+
+    // Tokens the map never handed out do not resolve
+    assert_eq!(map.get(Token(1000)), None);
+    assert_eq!(map.get(Token(usize::MAX)), None);
+}
+
+#[test]
+pub fn test_token_map_registrations() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+    let mut map = TokenMap::new();
+
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = sock.local_addr().unwrap();
+    let sock_token = map.insert("socket");
+    poll.register(&sock, sock_token, Ready::readable(), PollOpt::level()).unwrap();
+
+    let (registration, set_readiness) = Registration::new2();
+    let reg_token = map.insert("registration");
+    poll.register(&registration, reg_token, Ready::readable(), PollOpt::edge()).unwrap();
+
+    set_readiness.set_readiness(Ready::readable()).unwrap();
+    sock.send_to(b"hello", &addr).unwrap();
+
+    let mut seen = vec![];
+
+    while seen.len() < 2 {
+        poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+        assert!(!events.is_empty(), "no events");
+
+        for event in &events {
+            seen.push(*map.get(event.token()).unwrap());
+        }
+    }
+
+    seen.sort();
+    assert_eq!(seen, vec!["registration", "socket"]);
+}
+
+#[test]
+pub fn test_drop_stale_events() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+    let mut map = TokenMap::new();
+
+    poll.set_drop_stale(true);
+
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = sock.local_addr().unwrap();
+    let old = map.insert("old");
+    poll.register(&sock, old, Ready::readable(), PollOpt::level()).unwrap();
+
+    sock.send_to(b"hello", &addr).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    // The slot is reused while the socket is still registered with the old
+    // token, which only happens by mistake or when racing with `poll`.
+    map.remove(old);
+    let new = map.insert("new");
+    assert_eq!(new.index(), old.index());
+
+    let (registration, set_readiness) = Registration::new2();
+    poll.register(&registration, new, Ready::readable(), PollOpt::edge()).unwrap();
+    set_readiness.set_readiness(Ready::readable()).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.iter().map(|e| e.token()).collect::<Vec<_>>(), vec![new]);
+
+    // Reregistering the socket with the current generation lets its events
+    // through again
+    poll.reregister(&sock, new, Ready::readable(), PollOpt::level()).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.iter().map(|e| e.token()).collect::<Vec<_>>(), vec![new]);
+
+    // Without the mode stale events are returned
+    poll.set_drop_stale(false);
+    poll.reregister(&sock, old, Ready::readable(), PollOpt::level()).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.iter().map(|e| e.token()).collect::<Vec<_>>(), vec![old]);
+}
+
+#[test]
+pub fn test_drop_stale_forgets_deregistered() {
+    let mut poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    // Registered before the mode is enabled, so its generation is not tracked
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    poll.register(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+    sock.send_to(b"hello", &sock.local_addr().unwrap()).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    poll.set_drop_stale(true);
+
+    let (registration, _set_readiness) = Registration::new2();
+    poll.register(&registration, Token::with_generation(0, 1), Ready::readable(), PollOpt::edge()).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+    assert!(events.is_empty());
+
+    // Deregistering forgets the generation
+    poll.deregister(&registration).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.iter().map(|e| e.token()).collect::<Vec<_>>(), vec![Token(0)]);
+
+    let other = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    poll.register(&other, Token::with_generation(0, 2), Ready::readable(), PollOpt::level()).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+    assert!(events.is_empty());
+
+    poll.deregister(&other).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.iter().map(|e| e.token()).collect::<Vec<_>>(), vec![Token(0)]);
+}
+
+#[test]
+pub fn test_drop_stale_forgets_transferred() {
+    let mut poll = Poll::new().unwrap();
+    let other = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    // Registered before the mode is enabled, so its generation is not tracked
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    poll.register(&sock, Token(0), Ready::readable(), PollOpt::level()).unwrap();
+    sock.send_to(b"hello", &sock.local_addr().unwrap()).unwrap();
+    thread::sleep(Duration::from_millis(50));
+
+    poll.set_drop_stale(true);
+
+    let (registration, _set_readiness) = Registration::new2();
+    poll.register(&registration, Token::with_generation(0, 1), Ready::readable(), PollOpt::edge()).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_millis(100))).unwrap();
+    assert!(events.is_empty());
+
+    // Moving the registration away forgets the generation, like deregistering
+    poll.transfer(&registration, &other, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.iter().map(|e| e.token()).collect::<Vec<_>>(), vec![Token(0)]);
+}
+
+#[test]
+pub fn test_token_generation() {
+    let token = Token::with_generation(3, 7);
+    assert_eq!(token.index(), 3);
+    assert_eq!(token.generation(), 7);
+
+    let next = token.next_generation();
+    assert_eq!(next.index(), 3);
+    assert_eq!(next.generation(), 8);
+
+    // Plain tokens are generation 0
+    assert_eq!(Token(42).index(), 42);
+    assert_eq!(Token(42).generation(), 0);
+    assert_eq!(Token::with_generation(42, 0), Token(42));
+}
@@ -0,0 +1,135 @@
+use mio::{Events, Poll, PollOpt, Ready, Registration, Token};
+use mio::net::UdpSocket;
+use std::time::Duration;
+
+fn tokens(poll: &Poll, timeout: Duration) -> Vec<Token> {
+    let mut events = Events::with_capacity(16);
+    poll.poll(&mut events, Some(timeout)).unwrap();
+    events.iter().map(|e| e.token()).collect()
+}
+
+#[test]
+pub fn test_transfer_socket() {
+    let poll1 = Poll::new().unwrap();
+    let poll2 = Poll::new().unwrap();
+
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    poll1.register(&sock, Token(0), Ready::writable(), PollOpt::level()).unwrap();
+
+    poll1.transfer(&sock, &poll2, Token(1), Ready::writable(), PollOpt::level()).unwrap();
+
+    assert!(tokens(&poll1, Duration::from_millis(100)).is_empty());
+    assert_eq!(tokens(&poll2, Duration::from_secs(1)), vec![Token(1)]);
+
+    // Now bound to `poll2`
+    assert!(poll1.register(&sock, Token(0), Ready::writable(), PollOpt::level()).is_err());
+    assert!(poll1.transfer(&sock, &poll2, Token(0), Ready::writable(), PollOpt::level()).is_err());
+
+    poll2.transfer(&sock, &poll1, Token(2), Ready::writable(), PollOpt::level()).unwrap();
+
+    assert!(tokens(&poll2, Duration::from_millis(100)).is_empty());
+    assert_eq!(tokens(&poll1, Duration::from_secs(1)), vec![Token(2)]);
+}
+
+#[test]
+pub fn test_transfer_registration() {
+    let poll1 = Poll::new().unwrap();
+    let poll2 = Poll::new().unwrap();
+
+    let (r, set) = Registration::new2();
+    poll1.register(&r, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
+
+    poll1.transfer(&r, &poll2, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
+    set.set_readiness(Ready::readable()).unwrap();
+
+    assert!(tokens(&poll1, Duration::from_millis(0)).is_empty());
+    assert_eq!(tokens(&poll2, Duration::from_secs(1)), vec![Token(1)]);
+
+    // Only `poll2` accepts the registration now
+    assert!(poll1.reregister(&r, Token(0), Ready::readable(), PollOpt::edge()).is_err());
+    poll2.reregister(&r, Token(2), Ready::readable(), PollOpt::edge()).unwrap();
+}
+
+#[test]
+pub fn test_transfer_queued_registration() {
+    let poll1 = Poll::new().unwrap();
+    let poll2 = Poll::new().unwrap();
+
+    let (r, set) = Registration::new2();
+    poll1.register(&r, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
+
+    // Queued with `poll1`, which hands it over once it dequeues it
+    set.set_readiness(Ready::readable()).unwrap();
+    poll1.transfer(&r, &poll2, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
+
+    assert!(tokens(&poll1, Duration::from_millis(0)).is_empty());
+    assert_eq!(tokens(&poll2, Duration::from_secs(1)), vec![Token(1)]);
+}
+
+#[test]
+pub fn test_transfer_from_dropped_poll() {
+    let poll1 = Poll::new().unwrap();
+    let poll2 = Poll::new().unwrap();
+
+    let (r, set) = Registration::new2();
+    poll1.register(&r, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
+
+    set.set_readiness(Ready::readable()).unwrap();
+    poll1.transfer(&r, &poll2, Token(1), Ready::readable(), PollOpt::edge()).unwrap();
+
+    drop(poll1);
+
+    assert_eq!(tokens(&poll2, Duration::from_secs(1)), vec![Token(1)]);
+}
+
+#[test]
+pub fn test_transfer_from_wrong_poll() {
+    let poll1 = Poll::new().unwrap();
+    let poll2 = Poll::new().unwrap();
+    let poll3 = Poll::new().unwrap();
+
+    let (r, _set) = Registration::new2();
+    poll1.register(&r, Token(0), Ready::readable(), PollOpt::edge()).unwrap();
+
+    assert!(poll2.transfer(&r, &poll3, Token(1), Ready::readable(), PollOpt::edge()).is_err());
+
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    poll1.register(&sock, Token(2), Ready::writable(), PollOpt::level()).unwrap();
+
+    assert!(poll2.transfer(&sock, &poll3, Token(3), Ready::writable(), PollOpt::level()).is_err());
+}
+
+#[test]
+pub fn test_transfer_deregistered_socket() {
+    let poll1 = Poll::new().unwrap();
+    let poll2 = Poll::new().unwrap();
+
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    poll1.register(&sock, Token(0), Ready::writable(), PollOpt::level()).unwrap();
+    poll1.deregister(&sock).unwrap();
+
+    // Fails without moving the socket to `poll2`
+    assert!(poll1.transfer(&sock, &poll2, Token(1), Ready::writable(), PollOpt::level()).is_err());
+
+    poll1.register(&sock, Token(2), Ready::writable(), PollOpt::level()).unwrap();
+    assert_eq!(tokens(&poll1, Duration::from_secs(1)), vec![Token(2)]);
+}
+
+#[test]
+pub fn test_transfer_rejected_socket() {
+    let poll1 = Poll::new().unwrap();
+    let poll2 = Poll::new().unwrap();
+
+    let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    poll1.register(&sock, Token(0), Ready::writable(), PollOpt::level()).unwrap();
+
+    // `poll2` does not accept edge-triggered registrations
+    assert!(poll1.transfer(&sock, &poll2, Token(1), Ready::writable(), PollOpt::edge()).is_err());
+
+    assert!(tokens(&poll2, Duration::from_millis(100)).is_empty());
+    assert_eq!(tokens(&poll1, Duration::from_secs(1)), vec![Token(0)]);
+
+    // Still bound to `poll1`
+    poll1.transfer(&sock, &poll2, Token(1), Ready::writable(), PollOpt::level()).unwrap();
+    assert_eq!(tokens(&poll2, Duration::from_secs(1)), vec![Token(1)]);
+}
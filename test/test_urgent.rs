@@ -0,0 +1,123 @@
+use mio::{Events, Poll, PollOpt, Ready, Registration, Token};
+use mio::net::UdpSocket;
+use std::thread;
+use std::time::Duration;
+
+const URGENT: Token = Token(1000);
+
+fn tokens(events: &Events) -> Vec<Token> {
+    events.iter().map(|e| e.token()).collect()
+}
+
+#[test]
+pub fn test_urgent_registration_drained_first() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(4);
+
+    let mut regs = vec![];
+
+    for i in 0..64 {
+        let (r, set) = Registration::new2();
+        poll.register(&r, Token(i), Ready::readable(), PollOpt::edge()).unwrap();
+        set.set_readiness(Ready::readable()).unwrap();
+        regs.push((r, set));
+    }
+
+    // Made ready last, behind all the other registrations
+    let (r, set) = Registration::new2();
+    poll.register(&r, URGENT, Ready::readable(), PollOpt::edge() | PollOpt::urgent()).unwrap();
+    set.set_readiness(Ready::readable()).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+
+    assert_eq!(events.len(), 4);
+    assert_eq!(tokens(&events)[0], URGENT);
+    assert!(!tokens(&events)[1..].contains(&URGENT));
+
+    // The remaining registrations are still delivered
+    let mut seen = 3;
+
+    while seen < 64 {
+        poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+        assert!(!events.is_empty());
+        assert!(!tokens(&events).contains(&URGENT));
+        seen += events.len();
+    }
+
+    assert_eq!(seen, 64);
+}
+
+#[test]
+pub fn test_urgent_level_registration_once_per_poll() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (r, set) = Registration::new2();
+    poll.register(&r, URGENT, Ready::readable(), PollOpt::level() | PollOpt::urgent()).unwrap();
+    set.set_readiness(Ready::readable()).unwrap();
+
+    for _ in 0..3 {
+        poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+        assert_eq!(tokens(&events), vec![URGENT]);
+    }
+
+    set.set_readiness(Ready::empty()).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_millis(0))).unwrap();
+    assert!(events.is_empty());
+}
+
+#[test]
+pub fn test_urgent_wakes_blocked_poll() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(16);
+
+    let (r, set) = Registration::new2();
+    poll.register(&r, URGENT, Ready::readable(), PollOpt::edge() | PollOpt::urgent()).unwrap();
+
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(100));
+        set.set_readiness(Ready::readable()).unwrap();
+    });
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(tokens(&events), vec![URGENT]);
+
+    handle.join().unwrap();
+}
+
+#[test]
+pub fn test_urgent_socket_first() {
+    let poll = Poll::new().unwrap();
+    let mut events = Events::with_capacity(2);
+
+    let sender = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    let mut sockets = vec![];
+
+    for i in 0..8 {
+        let sock = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+        poll.register(&sock, Token(i), Ready::readable(), PollOpt::level()).unwrap();
+        sockets.push(sock);
+    }
+
+    // Registered last
+    let urgent = UdpSocket::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+    poll.register(&urgent, URGENT, Ready::readable(), PollOpt::level() | PollOpt::urgent()).unwrap();
+
+    for sock in sockets.iter().chain(Some(&urgent)) {
+        sender.send_to(b"hello", &sock.local_addr().unwrap()).unwrap();
+    }
+
+    thread::sleep(Duration::from_millis(50));
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.len(), 2);
+    assert_eq!(tokens(&events)[0], URGENT);
+
+    // Without the urgent option the socket waits its turn
+    poll.reregister(&urgent, URGENT, Ready::readable(), PollOpt::level()).unwrap();
+
+    poll.poll(&mut events, Some(Duration::from_secs(5))).unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(!tokens(&events).contains(&URGENT));
+}
@@ -14,14 +14,14 @@ fn write_then_drop() {
 
     let poll = Poll::new().unwrap();
 
-    a.register(&poll,
+    a.register(poll.registry(),
                Token(1),
                Ready::readable(),
-               PollOpt::edge()).unwrap();
-    s.register(&poll,
+               PollOpt::level()).unwrap();
+    s.register(poll.registry(),
                Token(3),
                Ready::empty(),
-               PollOpt::edge()).unwrap();
+               PollOpt::level()).unwrap();
 
     let mut events = Events::with_capacity(1024);
     while events.len() == 0 {
@@ -32,10 +32,10 @@ fn write_then_drop() {
 
     let mut s2 = a.accept().unwrap().0;
 
-    s2.register(&poll,
+    s2.register(poll.registry(),
                 Token(2),
                 Ready::writable(),
-                PollOpt::edge()).unwrap();
+                PollOpt::level()).unwrap();
 
     let mut events = Events::with_capacity(1024);
     while events.len() == 0 {
@@ -47,10 +47,10 @@ fn write_then_drop() {
     s2.write(&[1, 2, 3, 4]).unwrap();
     drop(s2);
 
-    s.reregister(&poll,
+    s.reregister(poll.registry(),
                  Token(3),
                  Ready::readable(),
-                 PollOpt::edge()).unwrap();
+                 PollOpt::level()).unwrap();
     let mut events = Events::with_capacity(1024);
     while events.len() == 0 {
         poll.poll(&mut events, None).unwrap();
@@ -73,14 +73,14 @@ fn write_then_deregister() {
 
     let poll = Poll::new().unwrap();
 
-    a.register(&poll,
+    a.register(poll.registry(),
                Token(1),
                Ready::readable(),
-               PollOpt::edge()).unwrap();
-    s.register(&poll,
+               PollOpt::level()).unwrap();
+    s.register(poll.registry(),
                Token(3),
                Ready::empty(),
-               PollOpt::edge()).unwrap();
+               PollOpt::level()).unwrap();
 
     let mut events = Events::with_capacity(1024);
     while events.len() == 0 {
@@ -91,10 +91,10 @@ fn write_then_deregister() {
 
     let mut s2 = a.accept().unwrap().0;
 
-    s2.register(&poll,
+    s2.register(poll.registry(),
                 Token(2),
                 Ready::writable(),
-                PollOpt::edge()).unwrap();
+                PollOpt::level()).unwrap();
 
     let mut events = Events::with_capacity(1024);
     while events.len() == 0 {
@@ -104,12 +104,12 @@ fn write_then_deregister() {
     assert_eq!(events.get(0).unwrap().token(), Token(2));
 
     s2.write(&[1, 2, 3, 4]).unwrap();
-    s2.deregister(&poll).unwrap();
+    s2.deregister(poll.registry()).unwrap();
 
-    s.reregister(&poll,
+    s.reregister(poll.registry(),
                  Token(3),
                  Ready::readable(),
-                 PollOpt::edge()).unwrap();
+                 PollOpt::level()).unwrap();
     let mut events = Events::with_capacity(1024);
     while events.len() == 0 {
         poll.poll(&mut events, None).unwrap();